@@ -0,0 +1,31 @@
+//! Human-in-the-loop approval for sensitive tool calls.
+//!
+//! Tools listed in `ReActConfig::requires_approval` only run after the
+//! controller's `ApprovalHook` approves the call. Without a hook, such calls
+//! are denied.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Outcome of an approval request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalDecision {
+    /// Run the tool call.
+    Approve,
+    /// Skip the tool call, reporting the reason to the agent.
+    Deny { reason: String },
+}
+
+impl ApprovalDecision {
+    /// Create a denial with the given reason.
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::Deny { reason: reason.into() }
+    }
+}
+
+/// Gate consulted before running a tool that requires approval.
+#[async_trait]
+pub trait ApprovalHook: Send + Sync {
+    /// Decide whether `tool` may run with `args`.
+    async fn approve(&self, tool: &str, args: &serde_json::Value) -> ApprovalDecision;
+}
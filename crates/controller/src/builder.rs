@@ -1,17 +1,37 @@
 //! Builder for ReActController.
 
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
-use multi_agent_core::traits::{LlmClient, ToolRegistry, ArtifactStore, SessionStore};
-use multi_agent_governance::Guardrail;
+use multi_agent_core::traits::{Controller, LlmClient, Redactor, ToolRegistry, ArtifactStore, SessionStore};
+use multi_agent_core::{Error, Result};
+use multi_agent_governance::{ContentModerator, Guardrail};
+use multi_agent_model_gateway::{RigConfig, RigLlmClient};
+use multi_agent_store::RedisSessionStore;
 
 use crate::react::{ReActController, ReActConfig};
-use crate::context::{ContextCompressor, CompressionConfig};
+use crate::context::{ContextCompressor, CompressionConfig, SummarizingCompressor};
 use crate::delegation::Delegator;
 use crate::capability::{
     AgentCapability, CompressionCapability, DelegationCapability, McpCapability, SecurityCapability,
     ReflectionCapability,
 };
 use crate::{MemoryCapability, PlanningCapability};
+use crate::rate_limit::RateLimiter;
+use crate::persistence::InMemorySessionStore;
+use crate::tier::{HeuristicTierSelector, TierSelector};
+use crate::approval::ApprovalHook;
+use crate::stop::StopCondition;
+use crate::builtin_tools::BuiltinToolRegistry;
+use crate::parser::{ActionParser, DefaultTextParser};
+use crate::schedule::ScheduleStore;
+use multi_agent_core::types::ModelTier;
+
+/// Loop detection threshold of the reflection capability created for `enable_reflection`.
+const DEFAULT_REFLECTION_THRESHOLD: usize = 3;
+
+/// TTL for sessions persisted to Redis by `from_env` (24h).
+const ENV_REDIS_SESSION_TTL_SECS: usize = 86_400;
 
 /// Builder for constructing a ReActController.
 pub struct ReActBuilder {
@@ -20,8 +40,25 @@ pub struct ReActBuilder {
     tools: Option<Arc<dyn ToolRegistry>>,
     store: Option<Arc<dyn ArtifactStore>>,
     session_store: Option<Arc<dyn SessionStore>>,
-    compression_config: CompressionConfig,
+    compression_config: Option<CompressionConfig>,
+    persistence: Option<bool>,
+    system_prompt_template: Option<String>,
     capabilities: Vec<Arc<dyn AgentCapability>>,
+    rate_limiter: Option<Arc<dyn RateLimiter>>,
+    moderator: Option<Arc<dyn ContentModerator>>,
+    observation_compressor: Option<Arc<dyn ContextCompressor>>,
+    fallback: Option<Arc<dyn Controller>>,
+    memory: Option<Arc<MemoryCapability>>,
+    tier_llms: HashMap<ModelTier, Arc<dyn LlmClient>>,
+    tier_selector: Option<Arc<dyn TierSelector>>,
+    approval_hook: Option<Arc<dyn ApprovalHook>>,
+    requires_approval: HashSet<String>,
+    redactor: Option<Arc<dyn Redactor>>,
+    stop_conditions: Vec<Arc<dyn StopCondition>>,
+    reflection: Option<Arc<ReflectionCapability>>,
+    action_parser: Option<Arc<dyn ActionParser>>,
+    delegator: Option<Arc<dyn Delegator>>,
+    schedule_store: Option<Arc<dyn ScheduleStore>>,
 }
 
 impl ReActBuilder {
@@ -33,11 +70,100 @@ impl ReActBuilder {
             tools: None,
             store: None,
             session_store: None,
-            compression_config: CompressionConfig::default(),
+            compression_config: None,
+            persistence: None,
+            system_prompt_template: None,
             capabilities: Vec::new(),
+            rate_limiter: None,
+            moderator: None,
+            observation_compressor: None,
+            fallback: None,
+            memory: None,
+            tier_llms: HashMap::new(),
+            tier_selector: None,
+            approval_hook: None,
+            requires_approval: HashSet::new(),
+            redactor: None,
+            stop_conditions: Vec::new(),
+            reflection: None,
+            action_parser: None,
+            delegator: None,
+            schedule_store: None,
         }
     }
 
+    /// Create a builder configured from `REACT_*` environment variables.
+    ///
+    /// See `.env.example` for the supported variables.
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Create a builder from environment variables resolved by `lookup`.
+    pub(crate) fn from_env_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let require = |name: &str| lookup(name).ok_or_else(|| Error::MissingEnvVar(name.to_string()));
+
+        let mut config = ReActConfig::default();
+        if let Some(max_iterations) = parse_env(&lookup, "REACT_MAX_ITERATIONS")? {
+            config.max_iterations = max_iterations;
+        }
+        if let Some(budget) = parse_env(&lookup, "REACT_DEFAULT_BUDGET")? {
+            config.default_budget = budget;
+        }
+        if let Some(temperature) = parse_env(&lookup, "REACT_TEMPERATURE")? {
+            config.temperature = temperature;
+        }
+        let tier: Option<ModelTier> = parse_env(&lookup, "REACT_MODEL_TIER")?;
+        if let Some(tier) = tier {
+            config.pricing_tier = tier;
+        }
+        let premium = tier == Some(ModelTier::Premium);
+
+        let provider = require("REACT_LLM_PROVIDER")?;
+        let model = lookup("REACT_LLM_MODEL");
+        let llm_config = match provider.to_lowercase().as_str() {
+            "openai" => RigConfig::openai(model.unwrap_or_else(|| {
+                if premium { "gpt-4o" } else { "gpt-4o-mini" }.to_string()
+            }))
+            .with_api_key(require("REACT_LLM_API_KEY")?),
+            "anthropic" => RigConfig::anthropic(model.unwrap_or_else(|| {
+                if premium { "claude-3-5-sonnet-20240620" } else { "claude-3-haiku-20240307" }.to_string()
+            }))
+            .with_api_key(require("REACT_LLM_API_KEY")?),
+            "ollama" => RigConfig::ollama(model.unwrap_or_else(|| {
+                if premium { "llama3.1:70b" } else { "llama3.1" }.to_string()
+            })),
+            other => {
+                return Err(Error::invalid_request(format!(
+                    "Unsupported REACT_LLM_PROVIDER '{}' (expected openai, anthropic or ollama)",
+                    other
+                )))
+            }
+        }
+        .with_temperature(config.temperature);
+
+        let session_store: Arc<dyn SessionStore> =
+            match lookup("REACT_SESSION_STORE").as_deref().unwrap_or("memory") {
+                "memory" => Arc::new(InMemorySessionStore::new()),
+                "redis" => Arc::new(RedisSessionStore::new(
+                    &require("REACT_REDIS_URL")?,
+                    "multiagent:session",
+                    ENV_REDIS_SESSION_TTL_SECS,
+                )?),
+                other => {
+                    return Err(Error::invalid_request(format!(
+                        "Unsupported REACT_SESSION_STORE '{}' (expected memory or redis)",
+                        other
+                    )))
+                }
+            };
+
+        Ok(Self::new()
+            .with_config(config)
+            .with_llm(Arc::new(RigLlmClient::new(llm_config)))
+            .with_session_store(session_store))
+    }
+
     /// Set the configuration.
     pub fn with_config(mut self, config: ReActConfig) -> Self {
         self.config = config;
@@ -50,6 +176,20 @@ impl ReActBuilder {
         self
     }
 
+    /// Set one LLM client per model tier, chosen each iteration by the tier selector.
+    ///
+    /// Tiers without a client fall back to the LLM set with `with_llm`.
+    pub fn with_tier_llms(mut self, llms: HashMap<ModelTier, Arc<dyn LlmClient>>) -> Self {
+        self.tier_llms = llms;
+        self
+    }
+
+    /// Set the selector choosing the model tier per iteration (default: `HeuristicTierSelector`).
+    pub fn with_tier_selector(mut self, selector: Arc<dyn TierSelector>) -> Self {
+        self.tier_selector = Some(selector);
+        self
+    }
+
     /// Set the tool registry.
     pub fn with_tools(mut self, tools: Arc<dyn ToolRegistry>) -> Self {
         self.tools = Some(tools);
@@ -57,6 +197,9 @@ impl ReActBuilder {
     }
 
     /// Set the artifact store.
+    ///
+    /// The agent can read stored artifacts back through the built-in
+    /// `fetch_artifact` tool.
     pub fn with_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
         self.store = Some(store);
         self
@@ -68,22 +211,55 @@ impl ReActBuilder {
         self
     }
 
+    /// Replace the default system prompt; `{goal}` and `{tools}` are substituted per session.
+    pub fn with_system_prompt_override(mut self, template: impl Into<String>) -> Self {
+        self.system_prompt_template = Some(template.into());
+        self
+    }
+
+    /// Require (or disable) session persistence; `build` fails if required without a session store.
+    ///
+    /// Without this, sessions are persisted according to `ReActConfig::persist_state`
+    /// whenever a session store is set.
+    pub fn with_persistence(mut self, enabled: bool) -> Self {
+        self.persistence = Some(enabled);
+        self
+    }
+
     /// Set the context compressor (compatibility mode).
+    ///
+    /// The compressor also summarizes tool outputs longer than
+    /// `observation_compression_threshold`.
     pub fn with_compressor(mut self, compressor: Arc<dyn ContextCompressor>) -> Self {
-        let cap = CompressionCapability::new(compressor, self.compression_config.clone());
+        self.observation_compressor = Some(compressor.clone());
+        let cap = CompressionCapability::new(compressor, self.compression_config.clone().unwrap_or_default());
         self.capabilities.push(Arc::new(cap));
         self
     }
 
+    /// Compress context by having `llm` summarize older history.
+    pub fn with_summarizing_compressor(self, llm: Arc<dyn LlmClient>) -> Self {
+        self.with_compressor(Arc::new(SummarizingCompressor::new(llm)))
+    }
+
     /// Set compression configuration AND update an existing CompressionCapability (compatibility mode).
     pub fn with_compression_config(mut self, config: CompressionConfig) -> Self {
-        self.compression_config = config;
+        self.compression_config = Some(config);
         self
     }
 
-    /// Set the delegator for subagent spawning (compatibility mode).
+    /// Set the delegator for subagent spawning (compatibility mode), also used to
+    /// run the subtasks of `ReActConfig::auto_decompose`.
+    ///
+    /// Children are spawned one level below `ReActConfig::delegation_depth`.
     pub fn with_delegator(mut self, delegator: Arc<dyn Delegator>) -> Self {
-        self.capabilities.push(Arc::new(DelegationCapability::new(delegator)));
+        self.delegator = Some(delegator);
+        self
+    }
+
+    /// Set the store holding `UserIntent::ScheduledMission` schedules, run by `ReActController::tick`.
+    pub fn with_schedule_store(mut self, store: Arc<dyn ScheduleStore>) -> Self {
+        self.schedule_store = Some(store);
         self
     }
 
@@ -100,18 +276,32 @@ impl ReActBuilder {
     }
 
     /// Set reflection capability for self-correction (compatibility mode).
-    pub fn with_reflection(mut self, threshold: usize) -> Self {
-        self.capabilities.push(Arc::new(ReflectionCapability::new(threshold)));
+    pub fn with_reflection(self, threshold: usize) -> Self {
+        self.with_reflection_capability(Arc::new(ReflectionCapability::new(threshold)))
+    }
+
+    /// Set a reflection capability, also used to review final answers
+    /// (see `ReActConfig::enable_reflection`).
+    pub fn with_reflection_capability(mut self, reflection: Arc<ReflectionCapability>) -> Self {
+        self.capabilities.push(reflection.clone());
+        self.reflection = Some(reflection);
         self
     }
 
     /// Set long-term memory for RAG (compatibility mode).
     pub fn with_memory(
-        mut self, 
+        self, 
         store: Arc<dyn multi_agent_core::traits::MemoryStore>, 
         llm: Arc<dyn multi_agent_core::traits::LlmClient>
     ) -> Self {
-        self.capabilities.push(Arc::new(MemoryCapability::new(store, llm, 5, 0.7)));
+        self.with_memory_capability(Arc::new(MemoryCapability::new(store, llm, 5, 0.7)))
+    }
+
+    /// Set a long-term memory capability, also used to recall prior knowledge
+    /// into the system prompt (see `ReActConfig::memory_recall_top_k`).
+    pub fn with_memory_capability(mut self, memory: Arc<MemoryCapability>) -> Self {
+        self.capabilities.push(memory.clone());
+        self.memory = Some(memory);
         self
     }
 
@@ -121,26 +311,199 @@ impl ReActBuilder {
         self
     }
 
+    /// Set the rate limiter applied before each LLM call.
+    ///
+    /// Pass a limiter from `GlobalRateLimiter::shared` to share one budget
+    /// across several controllers.
+    pub fn with_rate_limiter(mut self, limiter: Arc<dyn RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Set the content moderator for user input and final answers.
+    pub fn with_content_moderation(mut self, moderator: Arc<dyn ContentModerator>) -> Self {
+        self.moderator = Some(moderator);
+        self
+    }
+
+    /// Set the hook approving calls to tools that require approval.
+    pub fn with_approval_hook(mut self, hook: Arc<dyn ApprovalHook>) -> Self {
+        self.approval_hook = Some(hook);
+        self
+    }
+
+    /// Require approval before running the given tools.
+    ///
+    /// Added to `ReActConfig::requires_approval`. Without an approval hook, calls to them are denied.
+    pub fn requires_approval<I, S>(mut self, tool_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.requires_approval.extend(tool_names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the redactor masking secrets in persisted sessions (e.g. `RegexRedactor`).
+    pub fn with_redactor(mut self, redactor: Arc<dyn Redactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Add a condition that ends the mission after a tool call (e.g. `ToolResultStop`).
+    pub fn with_stop_condition(mut self, condition: Arc<dyn StopCondition>) -> Self {
+        self.stop_conditions.push(condition);
+        self
+    }
+
+    /// Parse LLM responses with `parser` instead of the ReAct text format.
+    ///
+    /// Use a system prompt template describing the format `parser` expects.
+    pub fn with_action_parser(mut self, parser: Arc<dyn ActionParser>) -> Self {
+        self.action_parser = Some(parser);
+        self
+    }
+
+    /// Set the controller to fail over to when the LLM provider is unavailable.
+    pub fn with_fallback_controller(mut self, fallback: Arc<dyn Controller>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
     /// Add a generic capability (plugin architecture).
     pub fn with_capability(mut self, capability: Arc<dyn AgentCapability>) -> Self {
         self.capabilities.push(capability);
         self
     }
 
+    /// Check that the configured pieces fit together.
+    fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(Error::invalid_request(format!("Invalid controller configuration: {}", msg)));
+
+        if self.persistence == Some(true) && self.session_store.is_none() {
+            return invalid("persistence is required but no session store was provided");
+        }
+        if self.compression_config.is_some() && self.observation_compressor.is_none() {
+            return invalid("a compression config was set but no compressor was provided");
+        }
+        if self.tier_selector.is_some() && self.tier_llms.is_empty() {
+            return invalid("a tier selector was set but no tier LLMs were provided");
+        }
+        if self.config.enable_planning && self.llm.is_none() {
+            return invalid("enable_planning requires an LLM (with_llm)");
+        }
+        if self.config.auto_decompose && (self.llm.is_none() || self.delegator.is_none()) {
+            return invalid("auto_decompose requires an LLM (with_llm) and a delegator (with_delegator)");
+        }
+        if self.config.enable_reflection
+            && self.llm.is_none()
+            && !self.reflection.as_ref().is_some_and(|r| r.has_llm())
+        {
+            return invalid("enable_reflection requires an LLM (with_llm)");
+        }
+        Ok(())
+    }
+
     /// Build the ReActController.
-    pub fn build(self) -> ReActController {
-        ReActController {
+    ///
+    /// When `preload_tools` is enabled, tool preloading starts in the background
+    /// immediately and is awaited before the first execution.
+    ///
+    /// Fails on inconsistent settings, e.g. persistence required without a
+    /// session store or a compression config without a compressor.
+    pub fn build(mut self) -> Result<ReActController> {
+        self.validate()?;
+        if let Some(enabled) = self.persistence {
+            self.config.persist_state = enabled;
+        }
+        self.config.requires_approval.extend(self.requires_approval);
+        if let Some(template) = self.system_prompt_template {
+            self.config.system_prompt_template = Some(template);
+        }
+
+        let reflection = if self.config.enable_reflection {
+            let reflection = self
+                .reflection
+                .unwrap_or_else(|| Arc::new(ReflectionCapability::new(DEFAULT_REFLECTION_THRESHOLD)));
+            if let Some(ref llm) = self.llm {
+                reflection.set_default_llm(llm.clone());
+            }
+            Some(reflection)
+        } else {
+            None
+        };
+
+        if let Some(ref store) = self.store {
+            let fetch = multi_agent_skills::FetchArtifactTool::new(store.clone());
+            self.tools = Some(Arc::new(BuiltinToolRegistry::new(self.tools.take(), vec![Arc::new(fetch)])));
+        }
+
+        let tools_preloaded: Arc<tokio::sync::OnceCell<()>> = Default::default();
+        if self.config.preload_tools {
+            if let (Some(tools), Ok(handle)) = (&self.tools, tokio::runtime::Handle::try_current()) {
+                let tools = tools.clone();
+                let tools_preloaded = tools_preloaded.clone();
+                handle.spawn(async move {
+                    if let Err(e) = tools_preloaded.get_or_try_init(|| tools.preload()).await {
+                        tracing::warn!(error = %e, "Tool preloading failed");
+                    }
+                });
+            }
+        }
+
+        if let Some(ref delegator) = self.delegator {
+            let cap = DelegationCapability::new(delegator.clone()).with_depth(self.config.delegation_depth);
+            self.capabilities.push(Arc::new(cap));
+        }
+
+        let action_parser = self
+            .action_parser
+            .unwrap_or_else(|| Arc::new(DefaultTextParser::new(self.capabilities.clone())));
+
+        Ok(ReActController {
             config: self.config,
             llm: self.llm,
             tools: self.tools,
-            // store is currently unused in Controller, dropped
+            artifact_store: self.store,
             session_store: self.session_store,
             // compression_config is used to configure capabilities, not stored in Controller
             capabilities: self.capabilities,
-        }
+            rate_limiter: self.rate_limiter,
+            moderator: self.moderator,
+            fallback: self.fallback,
+            fallback_invocations: Default::default(),
+            observation_compressor: self.observation_compressor,
+            active_sessions: Default::default(),
+            memory: self.memory,
+            tool_metrics: Default::default(),
+            tool_definitions: Default::default(),
+            tool_validators: Default::default(),
+            tier_llms: self.tier_llms,
+            tier_selector: self
+                .tier_selector
+                .unwrap_or_else(|| Arc::new(HeuristicTierSelector::default())),
+            approval_hook: self.approval_hook,
+            redactor: self.redactor,
+            stop_conditions: self.stop_conditions,
+            reflection,
+            action_parser,
+            delegator: self.delegator,
+            schedule_store: self.schedule_store,
+            tools_preloaded,
+        })
     }
 }
 
+/// Parse an optional environment variable.
+fn parse_env<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>> {
+    lookup(name)
+        .map(|raw| {
+            raw.parse()
+                .map_err(|_| Error::invalid_request(format!("Invalid value for {}: '{}'", name, raw)))
+        })
+        .transpose()
+}
+
 impl Default for ReActBuilder {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,117 @@
+//! Tools the controller provides on top of the configured tool registry.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+use multi_agent_core::{
+    traits::{Tool, ToolOutputStream, ToolRegistry},
+    types::{ToolDefinition, ToolOutput},
+    Error, Result,
+};
+
+/// Registry serving built-in tools before falling back to the configured registry.
+pub(crate) struct BuiltinToolRegistry {
+    inner: Option<Arc<dyn ToolRegistry>>,
+    builtins: Vec<Arc<dyn Tool>>,
+}
+
+impl BuiltinToolRegistry {
+    /// Layer `builtins` over `inner` (None = built-in tools only).
+    pub(crate) fn new(inner: Option<Arc<dyn ToolRegistry>>, builtins: Vec<Arc<dyn Tool>>) -> Self {
+        Self { inner, builtins }
+    }
+
+    fn builtin(&self, name: &str) -> Option<&Arc<dyn Tool>> {
+        self.builtins.iter().find(|tool| tool.name() == name)
+    }
+}
+
+/// Boxable handle to a shared built-in tool.
+struct SharedTool(Arc<dyn Tool>);
+
+#[async_trait]
+impl Tool for SharedTool {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn description(&self) -> &str {
+        self.0.description()
+    }
+
+    fn parameters(&self) -> Value {
+        self.0.parameters()
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput> {
+        self.0.execute(args).await
+    }
+}
+
+#[async_trait]
+impl ToolRegistry for BuiltinToolRegistry {
+    async fn register(&self, tool: Box<dyn Tool>) -> Result<()> {
+        match self.inner {
+            Some(ref inner) => inner.register(tool).await,
+            None => Err(Error::internal("No tool registry configured to register tools into")),
+        }
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Box<dyn Tool>>> {
+        if let Some(tool) = self.builtin(name) {
+            return Ok(Some(Box::new(SharedTool(tool.clone()))));
+        }
+        match self.inner {
+            Some(ref inner) => inner.get(name).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<ToolDefinition>> {
+        let mut definitions = match self.inner {
+            Some(ref inner) => inner.list().await?,
+            None => Vec::new(),
+        };
+        definitions.extend(self.builtins.iter().map(|tool| ToolDefinition {
+            name: tool.name().to_string(),
+            description: tool.description().to_string(),
+            parameters: tool.parameters(),
+            supports_streaming: false,
+        }));
+        Ok(definitions)
+    }
+
+    async fn execute(&self, name: &str, args: Value) -> Result<ToolOutput> {
+        if let Some(tool) = self.builtin(name) {
+            return tool.execute(args).await;
+        }
+        match self.inner {
+            Some(ref inner) => inner.execute(name, args).await,
+            None => Err(Error::tool_not_found(name)),
+        }
+    }
+
+    async fn execute_streaming(&self, name: &str, args: Value) -> Result<ToolOutputStream> {
+        if let (None, Some(inner)) = (self.builtin(name), &self.inner) {
+            return inner.execute_streaming(name, args).await;
+        }
+        // Built-in tools do not stream
+        let output = self.execute(name, args).await?;
+        if !output.success {
+            return Err(Error::tool_execution(output.content));
+        }
+        Ok(Box::pin(futures::stream::once(async move { Ok(output.content) })))
+    }
+
+    async fn preload(&self) -> Result<()> {
+        match self.inner {
+            Some(ref inner) => inner.preload().await,
+            None => Ok(()),
+        }
+    }
+
+    fn is_preloaded(&self) -> bool {
+        self.inner.as_ref().is_none_or(|inner| inner.is_preloaded())
+    }
+}
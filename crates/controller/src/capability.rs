@@ -8,11 +8,15 @@
 //! - `on_pre_reasoning`: Called before sending history to the LLM (e.g., compression, security).
 //! - `on_instruction`: Called to parse custom instructions from the LLM response.
 //! - `on_execute`: Called to execute custom actions.
+//! - `check_tool_call`: Called to allow or deny a tool call before it runs.
 
 use async_trait::async_trait;
-use std::sync::Arc;
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use multi_agent_core::{Result, Error};
-use multi_agent_core::types::{Session, AgentResult, HistoryEntry};
+use multi_agent_core::traits::{ChatMessage, LlmClient};
+use multi_agent_core::types::{Session, AgentResult, HistoryEntry, REASONING_ROLE};
 use crate::parser::ReActAction;
 use chrono::Utc; // Ensure chrono is available or use via core if re-exported
 
@@ -50,6 +54,11 @@ pub trait AgentCapability: Send + Sync {
         Ok(None)
     }
 
+    /// Called before a tool runs; a denial skips the call and reports the reason to the agent.
+    fn check_tool_call(&self, _tool: &str, _args: &Value) -> PolicyDecision {
+        PolicyDecision::Allow
+    }
+
     /// Called after the agent has executed an action and observed the result.
     /// Useful for reflection, loop detection, or auto-correction.
     async fn on_post_execute(&self, _session: &mut Session) -> Result<()> {
@@ -60,6 +69,46 @@ pub trait AgentCapability: Send + Sync {
     async fn on_finish(&self, _session: &mut Session, _result: &AgentResult) -> Result<()> {
         Ok(())
     }
+
+    /// JSON schema for `configure` updates. `None` if the capability is not reconfigurable.
+    fn config_schema(&self) -> Option<Value> {
+        None
+    }
+
+    /// Apply a runtime configuration update (validated against `config_schema`).
+    ///
+    /// Only the fields present in `config` are changed.
+    async fn configure(&self, _config: Value) -> Result<()> {
+        Err(Error::controller(format!("Capability '{}' is not configurable", self.name())))
+    }
+}
+
+/// Policy decision on a tool call, see `AgentCapability::check_tool_call`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// Run the tool call.
+    Allow,
+    /// Skip the tool call, reporting the reason to the agent.
+    Deny { reason: String },
+}
+
+impl PolicyDecision {
+    /// Create a denial with the given reason.
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::Deny { reason: reason.into() }
+    }
+}
+
+/// Merge the fields of `update` into a copy of `current`.
+fn merge_config<T>(current: &T, update: Value) -> Result<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut merged = serde_json::to_value(current)?;
+    if let (Value::Object(target), Value::Object(fields)) = (&mut merged, update) {
+        target.extend(fields);
+    }
+    Ok(serde_json::from_value(merged)?)
 }
 
 // =============================================================================
@@ -69,7 +118,7 @@ pub trait AgentCapability: Send + Sync {
 /// Wrapper for Context Compression.
 pub struct CompressionCapability {
     compressor: Arc<dyn crate::context::ContextCompressor>,
-    config: crate::context::CompressionConfig,
+    config: RwLock<crate::context::CompressionConfig>,
 }
 
 impl CompressionCapability {
@@ -77,7 +126,10 @@ impl CompressionCapability {
         compressor: Arc<dyn crate::context::ContextCompressor>,
         config: crate::context::CompressionConfig,
     ) -> Self {
-        Self { compressor, config }
+        Self {
+            compressor,
+            config: RwLock::new(config),
+        }
     }
 }
 
@@ -88,30 +140,196 @@ impl AgentCapability for CompressionCapability {
     }
 
     async fn on_pre_reasoning(&self, session: &mut Session) -> Result<()> {
+        let config = self.config.read().unwrap_or_else(|e| e.into_inner()).clone();
         let messages = crate::react::ReActController::build_messages_static(session);
-        if self.compressor.needs_compression(&messages, &self.config) {
+        if self.compressor.needs_compression_for_usage(&messages, &session.token_usage, &config) {
             tracing::info!("Capability triggering context compression");
-            let _result = self.compressor.compress(messages, &self.config).await?;
-            
-            // Reconstruct history from compressed messages
-            // This is complex because we need to map back to HistoryEntry
-            // For now, simpler approach: just log it happened, as true integration 
-            // requires deep controller changes. 
-            // BETTER: The compressor should modify the session directly in v0.3 refactor.
-            // For now, we'll keep the logic in the controller until we refactor build_messages.
+            let total = messages.len();
+            let preserve_start = usize::from(messages.first().is_some_and(|m| m.role == "system"));
+            let keep_recent = total.saturating_sub(config.preserve_recent).max(preserve_start);
+            if keep_recent == preserve_start {
+                return Ok(());
+            }
+
+            // Messages skip reasoning entries, so map message positions back to history indices
+            let visible: Vec<usize> = session
+                .history
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.role != REASONING_ROLE)
+                .map(|(i, _)| i)
+                .collect();
+            let pinned = |entry: &HistoryEntry| config.preserve_tool_results && entry.tool_call.is_some();
+
+            // Pinned tool observations are kept as-is, so only the rest is handed to the compressor
+            let messages: Vec<_> = messages
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| !(preserve_start..keep_recent).contains(i) || !pinned(&session.history[visible[*i]]))
+                .map(|(_, m)| m)
+                .collect();
+            if messages.len() == total - (keep_recent - preserve_start) {
+                return Ok(());
+            }
+
+            let result = self.compressor.compress(messages, &config).await?;
+            let Some(summary) = result.messages.get(preserve_start) else {
+                return Ok(());
+            };
+
+            let end = visible.get(keep_recent).copied().unwrap_or(session.history.len());
+            let range = visible[preserve_start]..end;
+            if config.preserve_tool_results {
+                session.replace_with_summary_pinned(range, summary.content.clone(), pinned);
+            } else {
+                session.replace_with_summary(range, summary.content.clone());
+            }
         }
         Ok(())
     }
+
+    fn config_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "max_tokens": { "type": "integer", "minimum": 1 },
+                "trigger_threshold": { "type": "number", "minimum": 0, "maximum": 1 },
+                "target_ratio": { "type": "number", "minimum": 0, "maximum": 1 },
+                "preserve_recent": { "type": "integer", "minimum": 0 },
+                "compression_threshold_ratio": { "type": ["number", "null"], "minimum": 0, "maximum": 1 },
+                "preserve_tool_results": { "type": "boolean" }
+            },
+            "additionalProperties": false
+        }))
+    }
+
+    async fn configure(&self, config: Value) -> Result<()> {
+        let mut current = self.config.write().unwrap_or_else(|e| e.into_inner());
+        *current = merge_config(&*current, config)?;
+        Ok(())
+    }
+}
+
+/// Argument keys whose values are treated as shell commands.
+const COMMAND_KEYS: [&str; 4] = ["command", "cmd", "shell", "script"];
+/// Key fragments marking a value as a file path.
+const PATH_KEY_FRAGMENTS: [&str; 3] = ["path", "file", "dir"];
+
+/// Allowlist/denylist for tool arguments that look like shell commands or file paths.
+#[derive(Debug, Clone, Default)]
+pub struct CommandPolicy {
+    /// Substrings denied in any string argument (e.g. `rm -rf`).
+    deny_patterns: Vec<String>,
+    /// Allowed command prefixes; when non-empty, other commands are denied.
+    allow_commands: Vec<String>,
+    /// Directory file paths must stay within.
+    sandbox_root: Option<PathBuf>,
+}
+
+impl CommandPolicy {
+    /// Create a policy allowing everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deny arguments containing `pattern`.
+    pub fn deny(mut self, pattern: impl Into<String>) -> Self {
+        self.deny_patterns.push(pattern.into());
+        self
+    }
+
+    /// Allow commands starting with `prefix`.
+    pub fn allow_command(mut self, prefix: impl Into<String>) -> Self {
+        self.allow_commands.push(prefix.into());
+        self
+    }
+
+    /// Deny file paths outside `root`; relative paths are resolved against it.
+    pub fn with_sandbox_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.sandbox_root = Some(root.into());
+        self
+    }
+
+    /// Check the string arguments of a tool call.
+    pub fn check(&self, args: &Value) -> PolicyDecision {
+        let mut values = Vec::new();
+        collect_strings("", args, &mut values);
+
+        for (key, value) in values {
+            if let Some(pattern) = self.deny_patterns.iter().find(|p| value.contains(p.as_str())) {
+                return PolicyDecision::deny(format!("argument matches denied pattern '{}'", pattern));
+            }
+
+            let key = key.to_lowercase();
+            if COMMAND_KEYS.contains(&key.as_str())
+                && !self.allow_commands.is_empty()
+                && !self.allow_commands.iter().any(|p| value.trim_start().starts_with(p.as_str()))
+            {
+                return PolicyDecision::deny(format!("command '{}' is not in the allowlist", value));
+            }
+
+            if let Some(ref root) = self.sandbox_root {
+                let is_path = value.starts_with(['/', '~', '.'])
+                    || PATH_KEY_FRAGMENTS.iter().any(|f| key.contains(f));
+                if is_path && !within_root(root, value) {
+                    return PolicyDecision::deny(format!(
+                        "path '{}' is outside the sandbox root {}",
+                        value,
+                        root.display()
+                    ));
+                }
+            }
+        }
+        PolicyDecision::Allow
+    }
+}
+
+/// Collect the string values in `value` with the key they are stored under.
+fn collect_strings<'a>(key: &'a str, value: &'a Value, out: &mut Vec<(&'a str, &'a str)>) {
+    match value {
+        Value::String(s) => out.push((key, s)),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(key, item, out)),
+        Value::Object(fields) => fields.iter().for_each(|(k, v)| collect_strings(k, v, out)),
+        _ => {}
+    }
+}
+
+/// Whether `path` resolves (lexically) to a location under `root`.
+fn within_root(root: &Path, path: &str) -> bool {
+    if path.starts_with('~') {
+        return false;
+    }
+    let mut resolved = PathBuf::new();
+    for component in root.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return false;
+                }
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved.starts_with(root)
 }
 
 /// Wrapper for Security Guardrails.
 pub struct SecurityCapability {
     guardrail: Arc<dyn multi_agent_governance::Guardrail>,
+    /// Policy gating command and path arguments of tool calls.
+    policy: Option<CommandPolicy>,
 }
 
 impl SecurityCapability {
     pub fn new(guardrail: Arc<dyn multi_agent_governance::Guardrail>) -> Self {
-        Self { guardrail }
+        Self { guardrail, policy: None }
+    }
+
+    /// Check tool call arguments against `policy` before execution.
+    pub fn with_command_policy(mut self, policy: CommandPolicy) -> Self {
+        self.policy = Some(policy);
+        self
     }
 }
 
@@ -121,6 +339,13 @@ impl AgentCapability for SecurityCapability {
         "security_guardrails"
     }
 
+    fn check_tool_call(&self, _tool: &str, args: &Value) -> PolicyDecision {
+        match self.policy {
+            Some(ref policy) => policy.check(args),
+            None => PolicyDecision::Allow,
+        }
+    }
+
     async fn on_start(&self, session: &mut Session) -> Result<()> {
         // Check goal (initial input) for security violations
         if let Some(ref task_state) = session.task_state {
@@ -170,11 +395,19 @@ impl AgentCapability for SecurityCapability {
 /// Wrapper for Delegation.
 pub struct DelegationCapability {
     delegator: Arc<dyn crate::delegation::Delegator>,
+    /// Delegation depth of the agent owning this capability (0 = top level).
+    depth: usize,
 }
 
 impl DelegationCapability {
     pub fn new(delegator: Arc<dyn crate::delegation::Delegator>) -> Self {
-        Self { delegator }
+        Self { delegator, depth: 0 }
+    }
+
+    /// Set the delegation depth of the owning agent; its children run one level deeper.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
     }
 }
 
@@ -202,13 +435,20 @@ impl AgentCapability for DelegationCapability {
     async fn on_execute(
         &self,
         action: &ReActAction,
-        _session: &mut Session,
+        session: &mut Session,
     ) -> Result<Option<AgentResult>> {
         if let ReActAction::Delegate { objective, context } = action {
              let request = crate::delegation::DelegationRequest::new(objective)
-                .with_context(context);
+                .with_context(context)
+                .with_depth(self.depth + 1)
+                .with_parent_session(session.id.clone());
             
             let result = self.delegator.delegate(request).await?;
+            // Count the child's tokens against the parent's budget
+            session.token_usage.add(
+                result.token_usage.prompt_tokens,
+                result.token_usage.completion_tokens,
+            );
             if result.success {
                 Ok(Some(AgentResult::Text(format!("Subagent completed: {}", result.result))))
             } else {
@@ -223,11 +463,34 @@ impl AgentCapability for DelegationCapability {
 /// Wrapper for MCP Registry (autonomous selection).
 pub struct McpCapability {
     registry: Arc<multi_agent_skills::McpRegistry>,
+    /// Registry receiving the tools of connected servers, so the ReAct loop can call them.
+    tools: Option<Arc<dyn multi_agent_core::traits::ToolRegistry>>,
 }
 
 impl McpCapability {
     pub fn new(registry: Arc<multi_agent_skills::McpRegistry>) -> Self {
-        Self { registry }
+        Self { registry, tools: None }
+    }
+
+    /// Register the tools of each server connected through this capability into `tools`.
+    pub fn with_tool_registry(mut self, tools: Arc<dyn multi_agent_core::traits::ToolRegistry>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Connect to a registered server and expose its tools, returning their names.
+    pub async fn connect(&self, server_id: &str) -> Result<Vec<String>> {
+        self.registry.connect_server(server_id).await?;
+        let adapter = self.registry.adapter();
+        if let Some(ref tools) = self.tools {
+            adapter.register_tools(server_id, tools.as_ref()).await?;
+        }
+        Ok(adapter
+            .get_server_tools(server_id)
+            .await?
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect())
     }
 }
 
@@ -257,10 +520,10 @@ impl AgentCapability for McpCapability {
             
             let observation = match self.registry.select_for_task(task_description) {
                 Some(server) => {
-                    match self.registry.connect_server(&server.id).await {
-                        Ok(()) => format!(
-                            "Selected and connected to MCP server '{}' ({}). Capabilities: {:?}. You can now use tools from this server.",
-                            server.name, server.id, server.capabilities
+                    match self.connect(&server.id).await {
+                        Ok(tools) => format!(
+                            "Selected and connected to MCP server '{}' ({}). Capabilities: {:?}. You can now use tools from this server: {}",
+                            server.name, server.id, server.capabilities, tools.join(", ")
                         ),
                         Err(e) => format!("Connection failed: {}", e),
                     }
@@ -279,23 +542,98 @@ impl AgentCapability for McpCapability {
     }
 }
 
+/// Verdict of a reflection pass over a final answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReflectionOutcome {
+    /// The answer satisfies the goal.
+    Accepted,
+    /// The answer falls short; `feedback` says what to revise.
+    Rejected { feedback: String },
+}
+
 /// Capability for Self-Correction and Loop Detection.
 pub struct ReflectionCapability {
     /// Limit of repetitive actions before triggering a warning
-    threshold: usize,
+    threshold: RwLock<usize>,
+    /// LLM reviewing final answers (see `reflect`).
+    llm: RwLock<Option<Arc<dyn LlmClient>>>,
 }
 
 impl ReflectionCapability {
     pub fn new(threshold: usize) -> Self {
-        Self { threshold }
+        Self {
+            threshold: RwLock::new(threshold),
+            llm: RwLock::new(None),
+        }
+    }
+
+    /// Use a dedicated LLM to review final answers.
+    pub fn with_llm(self, llm: Arc<dyn LlmClient>) -> Self {
+        *self.llm.write().unwrap_or_else(|e| e.into_inner()) = Some(llm);
+        self
+    }
+
+    /// Use `llm` for reviews unless one was already set.
+    pub(crate) fn set_default_llm(&self, llm: Arc<dyn LlmClient>) {
+        self.llm.write().unwrap_or_else(|e| e.into_inner()).get_or_insert(llm);
+    }
+
+    /// Whether an LLM is available for reviews.
+    pub(crate) fn has_llm(&self) -> bool {
+        self.llm.read().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    /// Ask the LLM whether `answer` actually satisfies `goal`.
+    ///
+    /// Fails open: without an LLM, or if the review call fails, the answer is accepted.
+    pub async fn reflect(&self, goal: &str, answer: &str) -> ReflectionOutcome {
+        let Some(llm) = self.llm.read().unwrap_or_else(|e| e.into_inner()).clone() else {
+            return ReflectionOutcome::Accepted;
+        };
+
+        let prompt = format!(
+            "Review the answer against the goal.\n\nGOAL: {}\n\nANSWER: {}\n\n\
+            If the answer fully satisfies the goal, reply ACCEPT. Otherwise reply \
+            REVISE: followed by what is missing or wrong.",
+            goal, answer
+        );
+        let response = match llm
+            .chat(&[ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+                tool_calls: None,
+                images: Vec::new(),
+            }])
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!(error = %e, "Reflection call failed, accepting answer");
+                return ReflectionOutcome::Accepted;
+            }
+        };
+
+        let verdict = response.content.trim();
+        if !verdict.to_uppercase().starts_with("REVISE") {
+            return ReflectionOutcome::Accepted;
+        }
+        let feedback = verdict["REVISE".len()..].trim_start_matches(':').trim();
+        ReflectionOutcome::Rejected {
+            feedback: if feedback.is_empty() {
+                "The answer does not satisfy the goal.".to_string()
+            } else {
+                feedback.to_string()
+            },
+        }
     }
 
     /// Check for repetitive tool calls
     fn detect_tool_loop(&self, session: &Session) -> Option<String> {
+        let threshold = *self.threshold.read().unwrap_or_else(|e| e.into_inner());
         let history = &session.history;
         // Determine if we have enough history to detect a loop
         // We need at least 'threshold' entries, not necessarily * 2
-        if history.len() < self.threshold {
+        if history.len() < threshold {
             return None;
         }
 
@@ -305,12 +643,12 @@ impl ReflectionCapability {
             if let Some(ref tool_call) = entry.tool_call {
                 recent_tools.push((tool_call.name.clone(), tool_call.arguments.to_string()));
             }
-            if recent_tools.len() >= self.threshold {
+            if recent_tools.len() >= threshold {
                 break;
             }
         }
 
-        if recent_tools.len() < self.threshold {
+        if recent_tools.len() < threshold {
             return None;
         }
 
@@ -319,7 +657,7 @@ impl ReflectionCapability {
         if recent_tools.iter().all(|t| t == first) {
             return Some(format!(
                 "CRITICAL WARNING: You have called the tool '{}' with arguments '{}' {} times in a row. Stop looping. Analyze *why* it is failing or returning the same result. Try a different tool or approach immediately.",
-                first.0, first.1, self.threshold
+                first.0, first.1, threshold
             ));
         }
 
@@ -343,6 +681,8 @@ impl AgentCapability for ReflectionCapability {
                 content: Arc::new(warning),
                 tool_call: None,
                 timestamp: Utc::now().timestamp(),
+                compressed_from: None,
+                images: Vec::new(),
             });
         }
 
@@ -350,4 +690,21 @@ impl AgentCapability for ReflectionCapability {
 
         Ok(())
     }
+
+    fn config_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "threshold": { "type": "integer", "minimum": 1 }
+            },
+            "additionalProperties": false
+        }))
+    }
+
+    async fn configure(&self, config: Value) -> Result<()> {
+        if let Some(threshold) = config.get("threshold").and_then(Value::as_u64) {
+            *self.threshold.write().unwrap_or_else(|e| e.into_inner()) = threshold as usize;
+        }
+        Ok(())
+    }
 }
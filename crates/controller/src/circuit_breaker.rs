@@ -0,0 +1,302 @@
+//! Circuit breaker for LLM providers.
+//!
+//! Closed circuits let every call through. After `failure_threshold`
+//! consecutive failures the circuit opens for a cooldown; once it elapses a
+//! single probe call is let through (half-open). A successful probe closes
+//! the circuit, a failed one reopens it with twice the previous cooldown.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use multi_agent_core::types::ProviderHealth;
+
+/// Source of the current time, replaceable in tests.
+pub trait Clock: Send + Sync {
+    /// Current instant.
+    fn now(&self) -> Instant;
+}
+
+/// Clock backed by `Instant::now`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Observable state of a circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls pass through.
+    Closed,
+    /// Calls are rejected until the cooldown elapses.
+    Open,
+    /// The cooldown elapsed; a single probe call is allowed.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    consecutive_failures: u32,
+    /// When the open circuit becomes half-open (None = closed).
+    open_until: Option<Instant>,
+    /// Cooldown applied the next time the circuit opens.
+    cooldown: Duration,
+    /// Start of the probe call currently let through in the half-open state.
+    probe_started: Option<Instant>,
+}
+
+/// Circuit breaker with half-open probing and exponential cooldown.
+pub struct CircuitBreaker {
+    pub(crate) failure_threshold: u32,
+    base_cooldown: Duration,
+    max_cooldown: Duration,
+    clock: Arc<dyn Clock>,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker opening after `failure_threshold` consecutive failures.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            base_cooldown: cooldown,
+            max_cooldown: cooldown.saturating_mul(32),
+            clock: Arc::new(SystemClock),
+            state: Mutex::new(BreakerState {
+                consecutive_failures: 0,
+                open_until: None,
+                cooldown,
+                probe_started: None,
+            }),
+        }
+    }
+
+    /// Cap the cooldown growth after failed probes (default 32x the base cooldown).
+    pub fn with_max_cooldown(mut self, max_cooldown: Duration) -> Self {
+        self.max_cooldown = max_cooldown;
+        self
+    }
+
+    /// Use a custom clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        let state = self.lock();
+        match state.open_until {
+            None => CircuitState::Closed,
+            Some(until) if self.clock.now() < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Provider health derived from the circuit state.
+    pub fn health(&self) -> ProviderHealth {
+        match self.state() {
+            CircuitState::Closed if self.lock().consecutive_failures > 0 => ProviderHealth::Degraded,
+            CircuitState::Closed => ProviderHealth::Healthy,
+            CircuitState::Open | CircuitState::HalfOpen => ProviderHealth::CircuitOpen,
+        }
+    }
+
+    /// Cooldown the circuit will use the next time it opens.
+    pub fn current_cooldown(&self) -> Duration {
+        self.lock().cooldown
+    }
+
+    /// Ask to make a call. Returns false if the circuit rejects it.
+    ///
+    /// In the half-open state only one probe is granted; a probe outstanding
+    /// for longer than the cooldown is assumed lost and granted again.
+    pub fn try_acquire(&self) -> bool {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        let Some(until) = state.open_until else {
+            return true;
+        };
+        if now < until {
+            return false;
+        }
+        match state.probe_started {
+            Some(started) if now < started + state.cooldown => false,
+            _ => {
+                state.probe_started = Some(now);
+                true
+            }
+        }
+    }
+
+    /// Record a successful call, closing the circuit.
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        if state.open_until.is_some() {
+            tracing::info!("Circuit breaker closed after successful probe");
+        }
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        state.cooldown = self.base_cooldown;
+        state.probe_started = None;
+    }
+
+    /// Record a failed call, opening the circuit at the threshold or after a failed probe.
+    pub fn record_failure(&self) {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        state.consecutive_failures += 1;
+
+        if state.probe_started.take().is_some() {
+            // Failed probe: back off exponentially
+            state.cooldown = state.cooldown.saturating_mul(2).min(self.max_cooldown);
+            state.open_until = Some(now + state.cooldown);
+            tracing::warn!(cooldown = ?state.cooldown, "Circuit breaker probe failed, reopened");
+        } else if state.open_until.is_none() && state.consecutive_failures >= self.failure_threshold {
+            state.open_until = Some(now + state.cooldown);
+            tracing::warn!(
+                failures = state.consecutive_failures,
+                cooldown = ?state.cooldown,
+                "Circuit breaker opened"
+            );
+        }
+    }
+
+    /// Open the circuit immediately for the current cooldown.
+    pub fn trip(&self) {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.max(self.failure_threshold);
+        state.open_until = Some(now + state.cooldown);
+        state.probe_started = None;
+    }
+
+    /// Close the circuit and forget past failures.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        state.cooldown = self.base_cooldown;
+        state.probe_started = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Clock advanced manually by the test.
+    struct FakeClock {
+        now: Mutex<Instant>,
+    }
+
+    impl FakeClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                now: Mutex::new(Instant::now()),
+            })
+        }
+
+        fn advance(&self, by: Duration) {
+            *self.now.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            *self.now.lock().unwrap()
+        }
+    }
+
+    fn breaker(clock: &Arc<FakeClock>) -> CircuitBreaker {
+        CircuitBreaker::new(2, Duration::from_secs(10)).with_clock(clock.clone())
+    }
+
+    #[test]
+    fn test_open_half_open_closed_cycle() {
+        let clock = FakeClock::new();
+        let breaker = breaker(&clock);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.health(), ProviderHealth::Degraded);
+
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.health(), ProviderHealth::CircuitOpen);
+        assert!(!breaker.try_acquire());
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        // Exactly one probe is let through
+        assert!(breaker.try_acquire());
+        assert!(!breaker.try_acquire());
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.health(), ProviderHealth::Healthy);
+        assert!(breaker.try_acquire());
+    }
+
+    #[test]
+    fn test_failed_probe_doubles_cooldown() {
+        let clock = FakeClock::new();
+        let breaker = breaker(&clock);
+        breaker.record_failure();
+        breaker.record_failure();
+
+        clock.advance(Duration::from_secs(10));
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.current_cooldown(), Duration::from_secs(20));
+
+        clock.advance(Duration::from_secs(19));
+        assert!(!breaker.try_acquire());
+        clock.advance(Duration::from_secs(1));
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.current_cooldown(), Duration::from_secs(40));
+
+        // A successful probe restores the base cooldown
+        clock.advance(Duration::from_secs(40));
+        assert!(breaker.try_acquire());
+        breaker.record_success();
+        assert_eq!(breaker.current_cooldown(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_cooldown_is_capped() {
+        let clock = FakeClock::new();
+        let breaker = breaker(&clock).with_max_cooldown(Duration::from_secs(15));
+        breaker.trip();
+
+        clock.advance(Duration::from_secs(10));
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.current_cooldown(), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_lost_probe_is_granted_again() {
+        let clock = FakeClock::new();
+        let breaker = breaker(&clock);
+        breaker.trip();
+
+        clock.advance(Duration::from_secs(10));
+        assert!(breaker.try_acquire());
+        // The probe never reports back
+        clock.advance(Duration::from_secs(10));
+        assert!(breaker.try_acquire());
+    }
+}
@@ -5,7 +5,12 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use multi_agent_core::{Result, traits::{LlmClient, ChatMessage}};
+use std::sync::Arc;
+use multi_agent_core::{
+    Error, Result,
+    traits::{ArtifactStore, LlmClient, ChatMessage},
+    types::{RefId, TokenUsage},
+};
 
 /// Configuration for context compression.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +23,12 @@ pub struct CompressionConfig {
     pub target_ratio: f32,
     /// Number of recent messages to always preserve.
     pub preserve_recent: usize,
+    /// Also compress once the session has used this fraction (0.0-1.0) of its token budget.
+    #[serde(default)]
+    pub compression_threshold_ratio: Option<f32>,
+    /// Keep tool observations verbatim and only summarize the surrounding chatter.
+    #[serde(default)]
+    pub preserve_tool_results: bool,
 }
 
 impl Default for CompressionConfig {
@@ -27,10 +38,75 @@ impl Default for CompressionConfig {
             trigger_threshold: 0.8,
             target_ratio: 0.5,
             preserve_recent: 10,
+            compression_threshold_ratio: None,
+            preserve_tool_results: false,
         }
     }
 }
 
+/// Estimates the token count of text for compression decisions.
+pub trait TokenEstimator: Send + Sync {
+    /// Estimate the token count of `text`.
+    fn estimate(&self, text: &str) -> usize;
+
+    /// Estimate the token count of a single message.
+    fn estimate_message(&self, message: &ChatMessage) -> usize {
+        self.estimate(&message.content)
+    }
+
+    /// Estimate the token count of a list of messages.
+    fn estimate_messages(&self, messages: &[ChatMessage]) -> usize {
+        messages.iter().map(|m| self.estimate_message(m)).sum()
+    }
+
+    /// Pair each message with its token estimate.
+    fn estimate_each(&self, messages: Vec<ChatMessage>) -> Vec<EstimatedMessage> {
+        messages
+            .into_iter()
+            .map(|message| EstimatedMessage {
+                tokens: self.estimate_message(&message),
+                message,
+            })
+            .collect()
+    }
+}
+
+/// A chat message carrying its estimated token count.
+#[derive(Debug, Clone)]
+pub struct EstimatedMessage {
+    /// The message.
+    pub message: ChatMessage,
+    /// Estimated tokens of the message.
+    pub tokens: usize,
+}
+
+/// Estimates tokens from character count (~4 chars per token by default).
+#[derive(Debug, Clone)]
+pub struct CharHeuristicEstimator {
+    chars_per_token: usize,
+}
+
+impl CharHeuristicEstimator {
+    /// Create an estimator assuming `chars_per_token` characters per token (min 1).
+    pub fn new(chars_per_token: usize) -> Self {
+        Self {
+            chars_per_token: chars_per_token.max(1),
+        }
+    }
+}
+
+impl Default for CharHeuristicEstimator {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl TokenEstimator for CharHeuristicEstimator {
+    fn estimate(&self, text: &str) -> usize {
+        text.len() / self.chars_per_token
+    }
+}
+
 /// Result of a compression operation.
 #[derive(Debug, Clone)]
 pub struct CompressionResult {
@@ -40,6 +116,8 @@ pub struct CompressionResult {
     pub estimated_tokens: usize,
     /// Number of messages removed/summarized.
     pub messages_compressed: usize,
+    /// Reference to the original messages, when the compressor archived them.
+    pub archived: Option<RefId>,
 }
 
 /// Strategy for context compression.
@@ -54,6 +132,14 @@ pub trait ContextCompressor: Send + Sync {
     
     /// Estimate token count for messages.
     fn estimate_tokens(&self, messages: &[ChatMessage]) -> usize;
+
+    /// Recover the original messages of a compression archived as `ref_id`.
+    async fn restore(&self, ref_id: &RefId) -> Result<Vec<ChatMessage>> {
+        Err(Error::controller(format!(
+            "Cannot restore {}: this compressor does not archive compressed messages",
+            ref_id
+        )))
+    }
     
     /// Check if compression is needed.
     fn needs_compression(&self, messages: &[ChatMessage], config: &CompressionConfig) -> bool {
@@ -61,14 +147,53 @@ pub trait ContextCompressor: Send + Sync {
         let threshold = (config.max_tokens as f32 * config.trigger_threshold) as usize;
         tokens > threshold
     }
+
+    /// Check if compression is needed, also considering the session's budget usage.
+    ///
+    /// Triggers when `usage.total_tokens` exceeds `compression_threshold_ratio`
+    /// of `usage.budget_limit`, or when `needs_compression` does.
+    fn needs_compression_for_usage(
+        &self,
+        messages: &[ChatMessage],
+        usage: &TokenUsage,
+        config: &CompressionConfig,
+    ) -> bool {
+        let over_budget_ratio = config.compression_threshold_ratio.is_some_and(|ratio| {
+            usage.budget_limit > 0 && usage.total_tokens as f64 > usage.budget_limit as f64 * ratio as f64
+        });
+        over_budget_ratio || self.needs_compression(messages, config)
+    }
 }
 
-/// Simple truncation strategy - removes oldest messages.
-pub struct TruncationCompressor;
+/// Simple truncation strategy - removes the oldest messages until the rest
+/// fits within `target_ratio` of `max_tokens`.
+pub struct TruncationCompressor {
+    estimator: Arc<dyn TokenEstimator>,
+}
 
 impl TruncationCompressor {
     pub fn new() -> Self {
-        Self
+        Self {
+            estimator: Arc::new(CharHeuristicEstimator::default()),
+        }
+    }
+
+    /// Use `estimator` for token estimates and compression decisions.
+    pub fn with_estimator(mut self, estimator: Arc<dyn TokenEstimator>) -> Self {
+        self.estimator = estimator;
+        self
+    }
+}
+
+impl TruncationCompressor {
+    /// Message noting that `dropped` earlier messages were removed (None if none were).
+    fn placeholder(&self, dropped: usize) -> Option<ChatMessage> {
+        (dropped > 0).then(|| ChatMessage {
+            role: "system".to_string(),
+            content: format!("[Context compressed: {} earlier messages removed]", dropped),
+            tool_calls: None,
+            images: Vec::new(),
+        })
     }
 }
 
@@ -85,55 +210,76 @@ impl ContextCompressor for TruncationCompressor {
         messages: Vec<ChatMessage>,
         config: &CompressionConfig,
     ) -> Result<CompressionResult> {
-        let total = messages.len();
         let target_tokens = (config.max_tokens as f32 * config.target_ratio) as usize;
-        
+        let messages = self.estimator.estimate_each(messages);
+        let total = messages.len();
+
         // Always preserve system message (first) and recent messages
-        let system_msg = messages.first().filter(|m| m.role == "system").cloned();
-        let preserve_start = if system_msg.is_some() { 1 } else { 0 };
-        
-        // Keep recent messages
+        let preserve_start = usize::from(messages.first().is_some_and(|m| m.message.role == "system"));
         let keep_recent = total.saturating_sub(config.preserve_recent).max(preserve_start);
-        let recent: Vec<_> = messages[keep_recent..].to_vec();
-        
-        let mut result = Vec::new();
-        if let Some(sys) = system_msg {
-            result.push(sys);
+
+        // Drop the oldest messages in between until the rest (with the placeholder) fits
+        let mut tokens: usize = messages.iter().map(|m| m.tokens).sum();
+        let mut dropped = 0;
+        while preserve_start + dropped < keep_recent
+            && tokens + self.placeholder(dropped).map_or(0, |p| self.estimator.estimate_message(&p)) > target_tokens
+        {
+            tokens -= messages[preserve_start + dropped].tokens;
+            dropped += 1;
         }
-        
-        // Add a summary placeholder
-        result.push(ChatMessage {
-            role: "system".to_string(),
-            content: format!("[Context compressed: {} earlier messages removed]", keep_recent - preserve_start),
-            tool_calls: None,
-        });
-        
-        result.extend(recent);
-        
-        let compressed_count = total - result.len();
+
+        let mut result = Vec::with_capacity(total - dropped + 1);
+        let mut messages = messages.into_iter().map(|m| m.message);
+        result.extend(messages.by_ref().take(preserve_start));
+        result.extend(self.placeholder(dropped));
+        result.extend(messages.skip(dropped));
+
         let estimated = self.estimate_tokens(&result);
-        
+
         Ok(CompressionResult {
             messages: result,
-            estimated_tokens: estimated.min(target_tokens),
-            messages_compressed: compressed_count,
+            estimated_tokens: estimated,
+            messages_compressed: dropped,
+            archived: None,
         })
     }
     
     fn estimate_tokens(&self, messages: &[ChatMessage]) -> usize {
-        // Rough estimation: ~4 chars per token on average
-        messages.iter().map(|m| m.content.len() / 4).sum()
+        self.estimator.estimate_messages(messages)
     }
 }
 
 /// Summarization strategy - uses LLM to summarize old messages.
+///
+/// Messages older than the `preserve_recent` most recent ones (after the
+/// leading system prompt) are replaced by a single system summary message.
+/// With an artifact store, the replaced messages are archived and the summary
+/// names their reference so they can be restored.
 pub struct SummarizationCompressor<C: LlmClient> {
     client: C,
+    estimator: Arc<dyn TokenEstimator>,
+    store: Option<Arc<dyn ArtifactStore>>,
 }
 
 impl<C: LlmClient> SummarizationCompressor<C> {
     pub fn new(client: C) -> Self {
-        Self { client }
+        Self {
+            client,
+            estimator: Arc::new(CharHeuristicEstimator::default()),
+            store: None,
+        }
+    }
+
+    /// Use `estimator` for token estimates and compression decisions.
+    pub fn with_estimator(mut self, estimator: Arc<dyn TokenEstimator>) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Archive the summarized messages in `store` so `restore` can recover them.
+    pub fn with_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.store = Some(store);
+        self
     }
 }
 
@@ -173,6 +319,14 @@ impl<C: LlmClient + 'static> ContextCompressor for SummarizationCompressor<C> {
         } else {
             None
         };
+
+        let archived = match self.store {
+            Some(ref store) if !old_messages.is_empty() => {
+                let data = serde_json::to_vec(old_messages)?;
+                Some(store.save_with_type(data.into(), "application/json").await?)
+            }
+            _ => None,
+        };
         
         // Build compressed result
         let mut result = Vec::new();
@@ -181,10 +335,18 @@ impl<C: LlmClient + 'static> ContextCompressor for SummarizationCompressor<C> {
         }
         
         if let Some(summary_text) = summary {
+            let content = match archived {
+                Some(ref ref_id) => format!(
+                    "[Previous context summary: {}]\n[Original messages archived as RefID: {}, call fetch_artifact to restore them]",
+                    summary_text, ref_id
+                ),
+                None => format!("[Previous context summary: {}]", summary_text),
+            };
             result.push(ChatMessage {
                 role: "system".to_string(),
-                content: format!("[Previous context summary: {}]", summary_text),
+                content,
                 tool_calls: None,
+                images: Vec::new(),
             });
         }
         
@@ -197,14 +359,30 @@ impl<C: LlmClient + 'static> ContextCompressor for SummarizationCompressor<C> {
             messages: result,
             estimated_tokens: estimated,
             messages_compressed: compressed_count,
+            archived,
         })
     }
     
     fn estimate_tokens(&self, messages: &[ChatMessage]) -> usize {
-        messages.iter().map(|m| m.content.len() / 4).sum()
+        self.estimator.estimate_messages(messages)
+    }
+
+    async fn restore(&self, ref_id: &RefId) -> Result<Vec<ChatMessage>> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| Error::controller("No artifact store configured to restore compressed messages"))?;
+        let data = store
+            .load(ref_id)
+            .await?
+            .ok_or_else(|| Error::controller(format!("Archived messages {} not found", ref_id)))?;
+        Ok(serde_json::from_slice(&data)?)
     }
 }
 
+/// Summarizing compressor over a shared LLM client.
+pub type SummarizingCompressor = SummarizationCompressor<Arc<dyn LlmClient>>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +392,7 @@ mod tests {
             role: "system".to_string(),
             content: "You are a helpful assistant.".to_string(),
             tool_calls: None,
+            images: Vec::new(),
         }];
         
         for i in 0..count {
@@ -221,6 +400,7 @@ mod tests {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {}", i),
                 tool_calls: None,
+                images: Vec::new(),
             });
         }
         msgs
@@ -232,6 +412,7 @@ mod tests {
         let messages = make_messages(20);
         
         let config = CompressionConfig {
+            max_tokens: 10,
             preserve_recent: 5,
             ..Default::default()
         };
@@ -242,7 +423,260 @@ mod tests {
         assert_eq!(result.messages.len(), 7);
         assert!(result.messages[1].content.contains("compressed"));
     }
+
+    #[tokio::test]
+    async fn test_truncation_drops_just_enough_messages() {
+        let compressor = TruncationCompressor::new().with_estimator(Arc::new(WordEstimator));
+        // System prompt is 5 tokens, each "Message i" is 2
+        let messages = make_messages(10);
+        assert_eq!(compressor.estimate_tokens(&messages), 25);
+
+        let config = CompressionConfig {
+            max_tokens: 20,
+            target_ratio: 1.0,
+            preserve_recent: 2,
+            ..Default::default()
+        };
+        let result = compressor.compress(messages, &config).await.unwrap();
+
+        // Dropping 5 messages (10 tokens) leaves 15 + a 6-token placeholder = 21;
+        // the sixth brings it to 19
+        assert_eq!(result.messages_compressed, 6);
+        assert_eq!(result.estimated_tokens, 19);
+        assert_eq!(result.messages[1].content, "[Context compressed: 6 earlier messages removed]");
+        assert_eq!(result.messages[2].content, "Message 6");
+        assert_eq!(result.messages.len(), 1 + 1 + 4);
+
+        // Already within the ceiling: nothing is removed
+        let roomy = CompressionConfig { max_tokens: 100, ..config };
+        let untouched = compressor.compress(make_messages(10), &roomy).await.unwrap();
+        assert_eq!(untouched.messages_compressed, 0);
+        assert_eq!(untouched.messages.len(), 11);
+    }
+
+    /// Counts one token per whitespace-separated word.
+    struct WordEstimator;
+
+    impl TokenEstimator for WordEstimator {
+        fn estimate(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn test_estimator_changes_trigger_point() {
+        // 40 chars, 2 words
+        let messages = vec![ChatMessage {
+            role: "user".to_string(),
+            content: format!("{} {}", "a".repeat(20), "b".repeat(19)),
+            tool_calls: None,
+            images: Vec::new(),
+        }];
+        let config = CompressionConfig {
+            max_tokens: 10,
+            trigger_threshold: 0.5,
+            ..Default::default()
+        };
+
+        let default = TruncationCompressor::new();
+        assert_eq!(default.estimate_tokens(&messages), 10);
+        assert!(default.needs_compression(&messages, &config));
+
+        let words = TruncationCompressor::new().with_estimator(Arc::new(WordEstimator));
+        assert_eq!(words.estimate_tokens(&messages), 2);
+        assert!(!words.needs_compression(&messages, &config));
+
+        let dense = TruncationCompressor::new().with_estimator(Arc::new(CharHeuristicEstimator::new(10)));
+        assert_eq!(dense.estimate_tokens(&messages), 4);
+        assert!(!dense.needs_compression(&messages, &config));
+
+        let summarizer = SummarizationCompressor::new(multi_agent_core::mocks::MockLlm::constant("summary"))
+            .with_estimator(Arc::new(WordEstimator));
+        assert!(!summarizer.needs_compression(&messages, &config));
+    }
+
+    #[tokio::test]
+    async fn test_summarizing_compressor() {
+        use multi_agent_core::mocks::MockLlm;
+
+        let llm: Arc<dyn LlmClient> = Arc::new(MockLlm::constant("The user counted to 14."));
+        let compressor = SummarizingCompressor::new(llm.clone());
+        let messages = make_messages(20);
+
+        let config = CompressionConfig {
+            preserve_recent: 5,
+            ..Default::default()
+        };
+        let result = compressor.compress(messages.clone(), &config).await.unwrap();
+
+        // system + one summary + 5 recent
+        assert_eq!(result.messages.len(), 7);
+        assert_eq!(result.messages[0].content, messages[0].content);
+        assert_eq!(result.messages[1].role, "system");
+        assert!(result.messages[1].content.contains("The user counted to 14."));
+        assert!(result.messages.iter().all(|m| m.content != "Message 0"));
+        for (kept, original) in result.messages[2..].iter().zip(&messages[16..]) {
+            assert_eq!(kept.content, original.content);
+        }
+        assert_eq!(result.messages_compressed, 15);
+        assert_eq!(result.estimated_tokens, compressor.estimate_tokens(&result.messages));
+
+        let controller = crate::ReActController::builder()
+            .with_summarizing_compressor(llm)
+            .build().unwrap();
+        assert!(controller.capabilities.iter().any(|c| c.name() == "context_compression"));
+    }
     
+    #[tokio::test]
+    async fn test_summarized_messages_can_be_restored() {
+        use multi_agent_core::mocks::MockLlm;
+        use multi_agent_store::InMemoryStore;
+
+        let store = Arc::new(InMemoryStore::new());
+        let compressor = SummarizationCompressor::new(MockLlm::constant("The user counted."))
+            .with_store(store.clone());
+        let messages = make_messages(8);
+        let config = CompressionConfig {
+            preserve_recent: 3,
+            ..Default::default()
+        };
+
+        let result = compressor.compress(messages.clone(), &config).await.unwrap();
+        let ref_id = result.archived.unwrap();
+        assert!(result.messages[1].content.contains(ref_id.as_str()));
+
+        let restored = compressor.restore(&ref_id).await.unwrap();
+        assert_eq!(restored.len(), 5);
+        for (restored, original) in restored.iter().zip(&messages[1..6]) {
+            assert_eq!(restored.role, original.role);
+            assert_eq!(restored.content, original.content);
+        }
+
+        // Truncation keeps nothing to restore
+        assert!(TruncationCompressor::new().restore(&ref_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_compress_then_expand_round_trip() {
+        use crate::capability::{AgentCapability, CompressionCapability};
+        use multi_agent_core::types::{HistoryEntry, Session};
+        use std::sync::Arc;
+
+        let history: Vec<HistoryEntry> = make_messages(20)
+            .into_iter()
+            .enumerate()
+            .map(|(i, m)| HistoryEntry {
+                role: m.role,
+                content: Arc::new(m.content),
+                tool_call: None,
+                timestamp: i as i64,
+                compressed_from: None,
+                images: Vec::new(),
+            })
+            .collect();
+        let mut session = Session {
+            created_at: 0,
+            updated_at: 0,
+            ..Session::new("s1")
+        };
+        session.history = history.clone();
+        assert!(!session.is_compressed());
+
+        let capability = CompressionCapability::new(
+            Arc::new(TruncationCompressor::new()),
+            CompressionConfig {
+                max_tokens: 10,
+                preserve_recent: 5,
+                ..Default::default()
+            },
+        );
+        capability.on_pre_reasoning(&mut session).await.unwrap();
+
+        // system + summary + 5 recent
+        assert_eq!(session.history.len(), 7);
+        assert!(session.is_compressed());
+        assert_eq!(session.history[1].compressed_from, Some((1..16).collect()));
+        assert!(!session.history[0].is_summary());
+
+        session.expand_summaries(&history);
+        assert!(!session.is_compressed());
+        let restored: Vec<_> = session.history.iter().map(|e| e.content.as_str()).collect();
+        let original: Vec<_> = history.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(restored, original);
+    }
+
+    #[tokio::test]
+    async fn test_compression_preserves_tool_results() {
+        use crate::capability::{AgentCapability, CompressionCapability};
+        use multi_agent_core::types::{HistoryEntry, Session, ToolCallInfo};
+        use std::sync::Arc;
+
+        let entry = |role: &str, content: &str, tool: Option<&str>| HistoryEntry {
+            role: role.to_string(),
+            content: Arc::new(content.to_string()),
+            tool_call: tool.map(|name| ToolCallInfo {
+                name: name.to_string(),
+                arguments: serde_json::json!({}),
+                result: Some(Arc::new(content.to_string())),
+                retry_count: 0,
+            }),
+            timestamp: 0,
+            compressed_from: None,
+            images: Vec::new(),
+        };
+        let filler = "Please take an action using a tool, or provide your FINAL ANSWER if the task is complete.";
+        let mut session = Session {
+            created_at: 0,
+            updated_at: 0,
+            ..Session::new("s1")
+        };
+        session.history = vec![
+            entry("system", "You are a helpful assistant.", None),
+            entry("user", "Find the weather", None),
+            entry("user", "OBSERVATION: 21C in Paris", Some("weather")),
+            entry("user", filler, None),
+            entry("user", "OBSERVATION: 18C in London", Some("weather")),
+            entry("user", filler, None),
+            entry("assistant", "Thinking...", None),
+            entry("user", filler, None),
+        ];
+
+        let capability = CompressionCapability::new(
+            Arc::new(TruncationCompressor::new()),
+            CompressionConfig {
+                max_tokens: 10,
+                preserve_recent: 2,
+                preserve_tool_results: true,
+                ..Default::default()
+            },
+        );
+        capability.on_pre_reasoning(&mut session).await.unwrap();
+
+        let contents: Vec<_> = session.history.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents.len(), 6);
+        assert_eq!(contents[0], "You are a helpful assistant.");
+        assert!(session.history[1].is_summary());
+        assert_eq!(session.history[1].compressed_from, Some(vec![1, 3, 5]));
+        assert_eq!(&contents[2..4], ["OBSERVATION: 21C in Paris", "OBSERVATION: 18C in London"]);
+        assert_eq!(&contents[4..], ["Thinking...", filler]);
+        assert_eq!(contents.iter().filter(|c| **c == filler).count(), 1);
+    }
+
+    #[test]
+    fn test_empty_compressed_from_is_not_summary() {
+        use multi_agent_core::types::HistoryEntry;
+
+        let entry = HistoryEntry {
+            role: "system".to_string(),
+            content: std::sync::Arc::new("summary".to_string()),
+            tool_call: None,
+            timestamp: 0,
+            compressed_from: Some(vec![]),
+            images: Vec::new(),
+        };
+        assert!(!entry.is_summary());
+    }
+
     #[test]
     fn test_needs_compression() {
         let compressor = TruncationCompressor::new();
@@ -257,6 +691,7 @@ mod tests {
             role: "user".to_string(),
             content: "Hi".to_string(),
             tool_calls: None,
+            images: Vec::new(),
         }];
         assert!(!compressor.needs_compression(&small, &config));
         
@@ -265,7 +700,80 @@ mod tests {
             role: "user".to_string(),
             content: "x".repeat(500),
             tool_calls: None,
+            images: Vec::new(),
         }];
         assert!(compressor.needs_compression(&large, &config));
     }
+
+    #[test]
+    fn test_needs_compression_for_usage_ratio() {
+        let compressor = TruncationCompressor::new();
+        let config = CompressionConfig {
+            compression_threshold_ratio: Some(0.5),
+            ..Default::default()
+        };
+        let messages = make_messages(2);
+        let usage = |total_tokens| TokenUsage {
+            total_tokens,
+            ..TokenUsage::with_budget(1000)
+        };
+
+        assert!(!compressor.needs_compression(&messages, &config));
+        assert!(!compressor.needs_compression_for_usage(&messages, &usage(499), &config));
+        assert!(!compressor.needs_compression_for_usage(&messages, &usage(500), &config));
+        assert!(compressor.needs_compression_for_usage(&messages, &usage(501), &config));
+
+        // Without a ratio or a budget only the message estimate counts
+        let no_ratio = CompressionConfig::default();
+        assert!(!compressor.needs_compression_for_usage(&messages, &usage(999), &no_ratio));
+        assert!(!compressor.needs_compression_for_usage(&messages, &TokenUsage::default(), &config));
+    }
+
+    #[tokio::test]
+    async fn test_capability_compresses_at_budget_ratio() {
+        use crate::capability::{AgentCapability, CompressionCapability};
+        use multi_agent_core::types::{HistoryEntry, Session};
+
+        let session_with_usage = |total_tokens| {
+            let mut session = Session {
+                created_at: 0,
+                updated_at: 0,
+                ..Session::new("s1")
+            };
+            session.history = make_messages(8)
+                .into_iter()
+                .map(|m| HistoryEntry {
+                    role: m.role,
+                    content: Arc::new(m.content),
+                    tool_call: None,
+                    timestamp: 0,
+                    compressed_from: None,
+                    images: Vec::new(),
+                })
+                .collect();
+            session.token_usage = TokenUsage {
+                total_tokens,
+                ..TokenUsage::with_budget(10_000)
+            };
+            session
+        };
+        let capability = CompressionCapability::new(
+            Arc::new(TruncationCompressor::new()),
+            CompressionConfig {
+                compression_threshold_ratio: Some(0.75),
+                preserve_recent: 2,
+                ..Default::default()
+            },
+        );
+
+        let mut below = session_with_usage(7_500);
+        capability.on_pre_reasoning(&mut below).await.unwrap();
+        assert!(!below.is_compressed());
+        assert_eq!(below.history.len(), 9);
+
+        let mut above = session_with_usage(7_501);
+        capability.on_pre_reasoning(&mut above).await.unwrap();
+        assert!(above.is_compressed());
+        assert!(above.history.len() < 9);
+    }
 }
@@ -2,10 +2,12 @@
 //!
 //! Handles the parallel execution of tasks with dependencies.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use multi_agent_core::{Error, Result};
+use multi_agent_core::{traits::ToolRegistry, Error, Result};
+
+use crate::parser::ReActAction;
 
 /// A unit of work in the DAG.
 #[async_trait::async_trait]
@@ -18,6 +20,88 @@ pub trait DagTask: Send + Sync {
     async fn execute(&self, context: &HashMap<String, String>) -> Result<String>;
 }
 
+/// A DAG node that runs a ReAct action once its dependencies have finished.
+///
+/// Tool calls receive the outputs of their dependencies in the
+/// `_previous_results` argument.
+#[derive(Debug, Clone)]
+pub struct TaskNode {
+    /// Unique node ID.
+    pub id: String,
+    /// Action to run (`ToolCall`, `ParallelToolCalls` or `FinalAnswer`).
+    pub action: ReActAction,
+    /// IDs of the nodes this node depends on.
+    pub depends_on: Vec<String>,
+}
+
+impl TaskNode {
+    /// Create a node with the given dependencies.
+    pub fn new(id: impl Into<String>, action: ReActAction, depends_on: Vec<String>) -> Self {
+        Self {
+            id: id.into(),
+            action,
+            depends_on,
+        }
+    }
+}
+
+/// A `TaskNode` bound to the registry that executes its tools.
+struct ToolTask {
+    node: TaskNode,
+    tools: Arc<dyn ToolRegistry>,
+}
+
+impl ToolTask {
+    async fn call(&self, name: &str, args: &serde_json::Value, context: &HashMap<String, String>) -> Result<String> {
+        let mut args = args.clone();
+        if let serde_json::Value::Object(ref mut map) = args {
+            let previous: HashMap<&str, &str> = self
+                .node
+                .depends_on
+                .iter()
+                .filter_map(|dep| context.get(dep).map(|output| (dep.as_str(), output.as_str())))
+                .collect();
+            map.insert("_previous_results".to_string(), serde_json::to_value(previous)?);
+        }
+
+        let output = self
+            .tools
+            .execute(name, args)
+            .await
+            .map_err(|e| Error::controller(format!("Node '{}' failed: {}", self.node.id, e)))?;
+        Ok(output.content)
+    }
+}
+
+#[async_trait::async_trait]
+impl DagTask for ToolTask {
+    fn name(&self) -> &str {
+        &self.node.id
+    }
+
+    fn dependencies(&self) -> &[String] {
+        &self.node.depends_on
+    }
+
+    async fn execute(&self, context: &HashMap<String, String>) -> Result<String> {
+        match &self.node.action {
+            ReActAction::ToolCall { name, args } => self.call(name, args, context).await,
+            ReActAction::ParallelToolCalls(calls) => {
+                let outputs = futures::future::try_join_all(
+                    calls.iter().map(|(name, args)| self.call(name, args, context)),
+                )
+                .await?;
+                Ok(outputs.join("\n"))
+            }
+            ReActAction::FinalAnswer(answer) => Ok(answer.clone()),
+            other => Err(Error::controller(format!(
+                "Node '{}' has an action that cannot run in a DAG: {:?}",
+                self.node.id, other
+            ))),
+        }
+    }
+}
+
 /// Executor for a DAG of tasks.
 pub struct DagExecutor {
     allow_parallel: bool,
@@ -41,6 +125,20 @@ impl DagExecutor {
         }
     }
 
+    /// Run `TaskNode`s in dependency order, executing their tools with `tools`.
+    ///
+    /// Returns the output of every node keyed by node ID.
+    pub async fn run(&self, nodes: Vec<TaskNode>, tools: Arc<dyn ToolRegistry>) -> Result<HashMap<String, String>> {
+        let tasks = nodes
+            .into_iter()
+            .map(|node| ToolTask {
+                node,
+                tools: tools.clone(),
+            })
+            .collect();
+        self.execute(tasks).await
+    }
+
     async fn execute_sequential<T>(&self, tasks: Vec<T>) -> Result<HashMap<String, String>>
     where
         T: DagTask,
@@ -88,8 +186,9 @@ impl DagExecutor {
         }
 
         // Check for cycles
-        if self.detect_cycle(&task_map) {
-             return Err(Error::SopExecution("Cycle detected in DAG".to_string()));
+        let graph = task_map.iter().map(|(name, task)| (name.as_str(), task.dependencies())).collect();
+        if let Some(cycle) = find_cycle(&graph) {
+            return Err(cycle_error(&cycle));
         }
 
         // 2. Execution Loop
@@ -243,17 +342,308 @@ impl DagExecutor {
         }
 
         if !task_map.is_empty() {
-             return Err(Error::SopExecution("Cycle detected in DAG".to_string()));
+            let graph = task_map.iter().map(|(name, t)| (name.as_str(), t.dependencies())).collect();
+            return Err(find_cycle(&graph).map_or_else(
+                || Error::SopExecution("Cycle detected in DAG".to_string()),
+                |cycle| cycle_error(&cycle),
+            ));
         }
 
         Ok(sorted)
     }
+}
+
+/// Identifier of a node in a `Dag`.
+pub type NodeId = String;
+
+/// A node of a `Dag`, weighted by its estimated duration.
+#[derive(Debug, Clone)]
+pub struct DagNode {
+    /// Unique node ID.
+    pub id: NodeId,
+    /// IDs of the nodes this node depends on.
+    pub depends_on: Vec<NodeId>,
+    /// Estimated execution time in milliseconds.
+    pub estimated_duration_ms: u64,
+}
+
+impl DagNode {
+    /// Create a node with the given dependencies and estimated duration.
+    pub fn new(id: impl Into<NodeId>, depends_on: Vec<NodeId>, estimated_duration_ms: u64) -> Self {
+        Self {
+            id: id.into(),
+            depends_on,
+            estimated_duration_ms,
+        }
+    }
+}
+
+/// A dependency graph of `DagNode`s, checked to be acyclic on construction.
+#[derive(Debug, Clone)]
+pub struct Dag {
+    nodes: Vec<DagNode>,
+    /// Node indices in topological order.
+    order: Vec<usize>,
+}
+
+impl Dag {
+    /// Build a DAG, failing on unknown dependencies or cycles.
+    pub fn new(nodes: Vec<DagNode>) -> Result<Self> {
+        let index: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        let mut in_degree = vec![0usize; nodes.len()];
+
+        for (i, node) in nodes.iter().enumerate() {
+            for dep in &node.depends_on {
+                let j = *index
+                    .get(dep.as_str())
+                    .ok_or_else(|| Error::SopExecution(format!("Unknown dependency: {}", dep)))?;
+                dependents[j].push(i);
+                in_degree[i] += 1;
+            }
+        }
+
+        let mut queue: Vec<usize> = (0..nodes.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(nodes.len());
+        while let Some(u) = queue.pop() {
+            order.push(u);
+            for &v in &dependents[u] {
+                in_degree[v] -= 1;
+                if in_degree[v] == 0 {
+                    queue.push(v);
+                }
+            }
+        }
+
+        if order.len() != nodes.len() {
+            let graph = nodes.iter().map(|n| (n.id.as_str(), n.depends_on.as_slice())).collect();
+            return Err(find_cycle(&graph).map_or_else(
+                || Error::SopExecution("Cycle detected in DAG".to_string()),
+                |cycle| cycle_error(&cycle),
+            ));
+        }
+
+        Ok(Self { nodes, order })
+    }
 
-    fn detect_cycle<T>(&self, _tasks: &HashMap<String, Arc<T>>) -> bool {
-        // Topological sort checks this implicitly, but parallel build creates graph first.
-        // Parallel executor does implicit cycle detection if pending_count > 0 but running_count == 0.
-        // So explicit check not strictly required if we trust that logic, but safe to add.
-        // For now relying on deadlock check.
-        false
+    /// Get the nodes of the DAG.
+    pub fn nodes(&self) -> &[DagNode] {
+        &self.nodes
+    }
+
+    /// Get the IDs of the nodes on the longest weighted path from any source to any sink.
+    ///
+    /// Each node is weighted by `DagNode::estimated_duration_ms`.
+    pub fn critical_path(&self) -> Vec<NodeId> {
+        let index: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+
+        // dist[i] = longest path duration ending at node i (inclusive)
+        let mut dist = vec![0u64; self.nodes.len()];
+        let mut prev: Vec<Option<usize>> = vec![None; self.nodes.len()];
+
+        for &i in &self.order {
+            let best = self.nodes[i]
+                .depends_on
+                .iter()
+                .map(|dep| index[dep.as_str()])
+                .max_by_key(|&j| dist[j]);
+            dist[i] = best.map_or(0, |j| dist[j]) + self.nodes[i].estimated_duration_ms;
+            prev[i] = best;
+        }
+
+        let Some(mut current) = self.order.iter().copied().max_by_key(|&i| dist[i]) else {
+            return Vec::new();
+        };
+
+        let mut path = vec![self.nodes[current].id.clone()];
+        while let Some(p) = prev[current] {
+            path.push(self.nodes[p].id.clone());
+            current = p;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Get the minimum wall clock time (in ms) of the DAG, the sum of the critical path durations.
+    pub fn estimated_wall_clock_time(&self) -> u64 {
+        let path = self.critical_path();
+        self.nodes
+            .iter()
+            .filter(|n| path.contains(&n.id))
+            .map(|n| n.estimated_duration_ms)
+            .sum()
+    }
+}
+
+/// Find a dependency cycle in a graph of task name -> dependencies.
+///
+/// Returns the task names along the cycle, starting and ending with the same task.
+fn find_cycle(graph: &HashMap<&str, &[String]>) -> Option<Vec<String>> {
+    fn visit<'a>(
+        node: &'a str,
+        graph: &HashMap<&'a str, &'a [String]>,
+        done: &mut HashSet<&'a str>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(pos) = path.iter().position(|n| *n == node) {
+            let mut cycle: Vec<String> = path[pos..].iter().map(|n| n.to_string()).collect();
+            cycle.push(node.to_string());
+            return Some(cycle);
+        }
+        if done.contains(node) {
+            return None;
+        }
+
+        path.push(node);
+        for dep in graph.get(node).copied().unwrap_or_default() {
+            if let Some(cycle) = visit(dep, graph, done, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(node);
+        None
+    }
+
+    let mut roots: Vec<&str> = graph.keys().copied().collect();
+    roots.sort_unstable();
+
+    let mut done = HashSet::new();
+    roots
+        .into_iter()
+        .find_map(|root| visit(root, graph, &mut done, &mut Vec::new()))
+}
+
+fn cycle_error(cycle: &[String]) -> Error {
+    Error::controller(format!("Cycle detected in DAG: {}", cycle.join(" -> ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_path_diamond() {
+        //        -> b (100) -> d (50)  -
+        // a (10)                         -> f (10)
+        //        -> c (20)  -> e (300) -
+        let node = |id: &str, deps: &[&str], ms| DagNode::new(id, deps.iter().map(|d| d.to_string()).collect(), ms);
+        let dag = Dag::new(vec![
+            node("a", &[], 10),
+            node("b", &["a"], 100),
+            node("c", &["a"], 20),
+            node("d", &["b"], 50),
+            node("e", &["c"], 300),
+            node("f", &["d", "e"], 10),
+        ])
+        .unwrap();
+
+        assert_eq!(dag.critical_path(), vec!["a", "c", "e", "f"]);
+        assert_eq!(dag.estimated_wall_clock_time(), 340);
+    }
+
+    #[test]
+    fn test_dag_rejects_cycle() {
+        let err = Dag::new(vec![
+            DagNode::new("a", vec!["b".to_string()], 10),
+            DagNode::new("b", vec!["a".to_string()], 10),
+        ])
+        .unwrap_err();
+        assert!(matches!(err, Error::Controller(_)), "{:?}", err);
+    }
+
+    /// Registry whose tools take 50ms and report which dependency results they received.
+    struct EchoRegistry;
+
+    #[async_trait::async_trait]
+    impl ToolRegistry for EchoRegistry {
+        async fn register(&self, _tool: Box<dyn multi_agent_core::traits::Tool>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn multi_agent_core::traits::Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<multi_agent_core::types::ToolDefinition>> {
+            Ok(vec![])
+        }
+
+        async fn execute(&self, name: &str, args: serde_json::Value) -> Result<multi_agent_core::types::ToolOutput> {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            let mut inputs: Vec<String> = args["_previous_results"]
+                .as_object()
+                .map(|m| m.values().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            inputs.sort();
+            Ok(multi_agent_core::types::ToolOutput::text(format!("{}[{}]", name, inputs.join(","))))
+        }
+    }
+
+    fn tool_node(id: &str, deps: &[&str]) -> TaskNode {
+        TaskNode::new(
+            id,
+            ReActAction::ToolCall {
+                name: id.to_string(),
+                args: serde_json::json!({}),
+            },
+            deps.iter().map(|d| d.to_string()).collect(),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_diamond() {
+        //    -> b -
+        // a         -> d
+        //    -> c -
+        let nodes = vec![
+            tool_node("a", &[]),
+            tool_node("b", &["a"]),
+            tool_node("c", &["a"]),
+            tool_node("d", &["b", "c"]),
+        ];
+
+        // Paused time auto-advances through the tool sleeps, so elapsed time is exact
+        let start = tokio::time::Instant::now();
+        let results = DagExecutor::new(true).run(nodes, Arc::new(EchoRegistry)).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(results["a"], "a[]");
+        assert_eq!(results["b"], "b[a[]]");
+        assert_eq!(results["c"], "c[a[]]");
+        assert_eq!(results["d"], "d[b[a[]],c[a[]]]");
+        // b and c run concurrently: 3 levels of 50ms instead of 4
+        assert_eq!(elapsed, std::time::Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_run_rejects_cycle() {
+        let nodes = vec![
+            tool_node("a", &["c"]),
+            tool_node("b", &["a"]),
+            tool_node("c", &["b"]),
+            tool_node("d", &["a"]),
+        ];
+
+        for parallel in [true, false] {
+            let err = DagExecutor::new(parallel)
+                .run(nodes.clone(), Arc::new(EchoRegistry))
+                .await
+                .unwrap_err();
+            let Error::Controller(message) = err else {
+                panic!("Expected controller error, got {:?}", err);
+            };
+            assert!(message.contains("a -> c -> b -> a"), "{}", message);
+            assert!(!message.contains("-> d"), "{}", message);
+        }
     }
 }
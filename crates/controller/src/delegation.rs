@@ -4,9 +4,23 @@
 //! and isolated contexts for divide-and-conquer problem solving.
 
 use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
-use multi_agent_core::{Result, traits::{LlmClient, ChatMessage}};
+use multi_agent_core::{
+    Error, Result,
+    traits::{LlmClient, ChatMessage, SessionStore},
+    types::{HistoryEntry, Session, SessionStatus, TaskState, TokenUsage},
+};
+
+use crate::scratchpad::{parse_scratchpad_writes, SharedScratchpad, SCRATCHPAD_WRITE_PREFIX};
+
+/// Default maximum nesting depth of delegated subagents.
+pub const DEFAULT_MAX_DELEGATION_DEPTH: usize = 3;
 
 /// A delegation request from parent to child agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +35,15 @@ pub struct DelegationRequest {
     pub max_iterations: usize,
     /// Tools the child is allowed to use.
     pub allowed_tools: Vec<String>,
+    /// Target agent (None = default subagent).
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Nesting depth of the child agent (1 = spawned by a top-level agent).
+    #[serde(default)]
+    pub depth: usize,
+    /// Session of the delegating agent.
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
 }
 
 impl DelegationRequest {
@@ -32,6 +55,9 @@ impl DelegationRequest {
             context: String::new(),
             max_iterations: 10,
             allowed_tools: Vec::new(),
+            agent_id: None,
+            depth: 1,
+            parent_session_id: None,
         }
     }
     
@@ -52,6 +78,35 @@ impl DelegationRequest {
         self.allowed_tools = tools;
         self
     }
+    
+    /// Set the target agent.
+    pub fn with_agent(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = Some(agent_id.into());
+        self
+    }
+
+    /// Set the nesting depth of the child agent.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Set the session of the delegating agent.
+    pub fn with_parent_session(mut self, session_id: impl Into<String>) -> Self {
+        self.parent_session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// How long `Delegator::broadcast_with` waits for delegates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastStrategy {
+    /// Wait for every delegate to finish.
+    WaitAll,
+    /// Return as soon as one delegate succeeds, cancelling the rest.
+    WaitFirst,
+    /// Return once N delegates succeed, cancelling the rest.
+    QuorumN(usize),
 }
 
 /// Result from a delegated subagent execution.
@@ -67,6 +122,15 @@ pub struct DelegationResult {
     pub iterations_used: usize,
     /// Any error message.
     pub error: Option<String>,
+    /// Tokens consumed by the child.
+    #[serde(default)]
+    pub token_usage: TokenUsage,
+    /// Scratchpad entries written by the child.
+    #[serde(default)]
+    pub scratchpad_writes: HashMap<String, Value>,
+    /// Session recording the child's run.
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 impl DelegationResult {
@@ -78,6 +142,9 @@ impl DelegationResult {
             result,
             iterations_used: iterations,
             error: None,
+            token_usage: TokenUsage::default(),
+            scratchpad_writes: HashMap::new(),
+            session_id: None,
         }
     }
     
@@ -89,8 +156,29 @@ impl DelegationResult {
             result: String::new(),
             iterations_used: 0,
             error: Some(error),
+            token_usage: TokenUsage::default(),
+            scratchpad_writes: HashMap::new(),
+            session_id: None,
         }
     }
+
+    /// Attach the child's token usage.
+    pub fn with_token_usage(mut self, token_usage: TokenUsage) -> Self {
+        self.token_usage = token_usage;
+        self
+    }
+
+    /// Attach the scratchpad entries written by the child.
+    pub fn with_scratchpad_writes(mut self, writes: HashMap<String, Value>) -> Self {
+        self.scratchpad_writes = writes;
+        self
+    }
+
+    /// Attach the session recording the child's run.
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
 }
 
 /// Subagent executor that runs delegated tasks in isolated contexts.
@@ -123,11 +211,13 @@ impl<C: LlmClient> SubAgentExecutor<C> {
                 role: "system".to_string(),
                 content: system_prompt,
                 tool_calls: None,
+                images: Vec::new(),
             },
             ChatMessage {
                 role: "user".to_string(),
                 content: format!("Complete the objective: {}", request.objective),
                 tool_calls: None,
+                images: Vec::new(),
             },
         ];
         
@@ -135,11 +225,13 @@ impl<C: LlmClient> SubAgentExecutor<C> {
         match self.client.chat(&messages).await {
             Ok(response) => {
                 tracing::info!(id = %request.id, "Subagent completed successfully");
+                let mut usage = TokenUsage::default();
+                usage.add(response.usage.prompt_tokens, response.usage.completion_tokens);
                 Ok(DelegationResult::success(
                     request.id,
                     response.content,
                     1,
-                ))
+                ).with_token_usage(usage))
             }
             Err(e) => {
                 tracing::error!(id = %request.id, error = %e, "Subagent failed");
@@ -157,12 +249,81 @@ pub trait Delegator: Send + Sync {
     
     /// Check if a delegation is complete.
     async fn check_delegation(&self, id: &str) -> Result<Option<DelegationResult>>;
+
+    /// Send the same request to several agents concurrently and wait for all of them.
+    async fn broadcast(
+        &self,
+        request: DelegationRequest,
+        agent_ids: Vec<String>,
+    ) -> Vec<Result<DelegationResult>> {
+        self.broadcast_with(request, agent_ids, BroadcastStrategy::WaitAll).await
+    }
+
+    /// Send the same request to several agents concurrently using `strategy`.
+    ///
+    /// Results are returned in `agent_ids` order. Delegates cancelled by the
+    /// strategy report an error.
+    async fn broadcast_with(
+        &self,
+        request: DelegationRequest,
+        agent_ids: Vec<String>,
+        strategy: BroadcastStrategy,
+    ) -> Vec<Result<DelegationResult>> {
+        let required = match strategy {
+            BroadcastStrategy::WaitAll => None,
+            BroadcastStrategy::WaitFirst => Some(1),
+            BroadcastStrategy::QuorumN(n) => Some(n),
+        };
+        let token = CancellationToken::new();
+
+        let mut pending: FuturesUnordered<_> = agent_ids
+            .iter()
+            .enumerate()
+            .map(|(i, agent_id)| {
+                let mut request = request.clone().with_agent(agent_id.clone());
+                request.id = format!("{}_{}", request.id, agent_id);
+                let token = token.child_token();
+                async move {
+                    let result = tokio::select! {
+                        result = self.delegate(request) => result,
+                        _ = token.cancelled() => Err(Error::controller(format!(
+                            "Delegation to agent '{}' cancelled", agent_id
+                        ))),
+                    };
+                    (i, result)
+                }
+            })
+            .collect();
+
+        let mut results: Vec<Option<Result<DelegationResult>>> =
+            (0..agent_ids.len()).map(|_| None).collect();
+        let mut successes = 0;
+
+        while let Some((i, result)) = pending.next().await {
+            if matches!(&result, Ok(r) if r.success) {
+                successes += 1;
+                if required.is_some_and(|n| successes >= n) && !token.is_cancelled() {
+                    tracing::info!(successes = successes, "Broadcast quorum reached, cancelling remaining delegates");
+                    token.cancel();
+                }
+            }
+            results[i] = Some(result);
+        }
+
+        results.into_iter().flatten().collect()
+    }
 }
 
 /// In-memory delegation manager for tracking subagent tasks.
 pub struct DelegationManager<C: LlmClient> {
     executor: SubAgentExecutor<C>,
     results: std::sync::Arc<dashmap::DashMap<String, DelegationResult>>,
+    /// Deepest subagent nesting allowed.
+    max_depth: usize,
+    /// Scratchpad shared by every subagent this manager runs.
+    scratchpad: SharedScratchpad,
+    /// Store receiving a session per subagent run (None = not recorded).
+    session_store: Option<Arc<dyn SessionStore>>,
 }
 
 impl<C: LlmClient> DelegationManager<C> {
@@ -171,15 +332,117 @@ impl<C: LlmClient> DelegationManager<C> {
         Self {
             executor: SubAgentExecutor::new(client),
             results: std::sync::Arc::new(dashmap::DashMap::new()),
+            max_depth: DEFAULT_MAX_DELEGATION_DEPTH,
+            scratchpad: SharedScratchpad::new(),
+            session_store: None,
+        }
+    }
+
+    /// Set the deepest subagent nesting allowed.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Share `scratchpad` with every delegated subagent.
+    pub fn with_scratchpad(mut self, scratchpad: SharedScratchpad) -> Self {
+        self.scratchpad = scratchpad;
+        self
+    }
+
+    /// Scratchpad shared with delegated subagents.
+    pub fn scratchpad(&self) -> &SharedScratchpad {
+        &self.scratchpad
+    }
+
+    /// Record each subagent run as a session linked to its parent in `store`.
+    pub fn with_session_store(mut self, store: Arc<dyn SessionStore>) -> Self {
+        self.session_store = Some(store);
+        self
+    }
+}
+
+/// Build the session recording a subagent run.
+fn child_session(id: &str, request: &DelegationRequest, result: &DelegationResult) -> Session {
+    let now = crate::react::chrono_timestamp();
+    let entry = |role: &str, content: String| HistoryEntry {
+        role: role.to_string(),
+        content: Arc::new(content),
+        tool_call: None,
+        timestamp: now,
+        compressed_from: None,
+        images: Vec::new(),
+    };
+    let outcome = if result.success {
+        result.result.clone()
+    } else {
+        result.error.clone().unwrap_or_default()
+    };
+
+    let mut task_state = TaskState::new(request.objective.clone());
+    task_state.iteration = result.iterations_used;
+
+    let mut session = Session::new(id);
+    session.status = if result.success { SessionStatus::Completed } else { SessionStatus::Failed };
+    session.history = vec![
+        entry("user", request.objective.clone()),
+        entry("assistant", outcome),
+    ];
+    session.task_state = Some(task_state);
+    session.token_usage = result.token_usage.clone();
+    session.parent_session_id = request.parent_session_id.clone();
+    session
+}
+
+/// Append the scratchpad entries and write instructions to a subagent context.
+fn scratchpad_context(context: &str, entries: &HashMap<String, Value>) -> String {
+    let mut context = context.to_string();
+    if !entries.is_empty() {
+        let mut keys: Vec<_> = entries.keys().collect();
+        keys.sort();
+        context.push_str("\nShared scratchpad:");
+        for key in keys {
+            context.push_str(&format!("\n- {} = {}", key, entries[key]));
         }
     }
+    context.push_str(&format!(
+        "\nTo share a finding with sibling agents, add a line `{} key = value`.",
+        SCRATCHPAD_WRITE_PREFIX
+    ));
+    context
 }
 
 #[async_trait]
 impl<C: LlmClient + 'static> Delegator for DelegationManager<C> {
     async fn delegate(&self, request: DelegationRequest) -> Result<DelegationResult> {
         let id = request.id.clone();
-        let result = self.executor.execute(request).await?;
+        if request.depth > self.max_depth {
+            tracing::warn!(id = %id, depth = request.depth, max_depth = self.max_depth, "Delegation depth limit reached");
+            let result = DelegationResult::failure(
+                id.clone(),
+                format!(
+                    "Delegation depth {} exceeds max_depth {}; complete the objective without delegating",
+                    request.depth, self.max_depth
+                ),
+            );
+            self.results.insert(id, result.clone());
+            return Ok(result);
+        }
+
+        let mut request = request;
+        request.context = scratchpad_context(&request.context, &self.scratchpad.snapshot());
+
+        let mut result = self.executor.execute(request.clone()).await?;
+        if result.success {
+            let writes = parse_scratchpad_writes(&result.result);
+            self.scratchpad.merge(writes.clone());
+            result = result.with_scratchpad_writes(writes);
+        }
+        if let Some(ref store) = self.session_store {
+            let session = child_session(&Uuid::new_v4().to_string(), &request, &result);
+            store.save(&session).await?;
+            result = result.with_session_id(session.id);
+        }
         self.results.insert(id, result.clone());
         Ok(result)
     }
@@ -205,8 +468,141 @@ mod tests {
         assert_eq!(request.context, "Document is about AI safety");
         assert_eq!(request.max_iterations, 5);
         assert_eq!(request.allowed_tools, vec!["read_file"]);
+        assert_eq!(request.depth, 1);
+    }
+
+    #[tokio::test]
+    async fn test_delegation_depth_limit() {
+        use multi_agent_core::mocks::MockLlm;
+
+        let manager = DelegationManager::new(MockLlm::constant("child result")).with_max_depth(2);
+
+        let ok = manager.delegate(DelegationRequest::new("Task").with_depth(2)).await.unwrap();
+        assert!(ok.success);
+
+        let rejected = manager.delegate(DelegationRequest::new("Task").with_depth(3)).await.unwrap();
+        assert!(!rejected.success);
+        assert!(rejected.error.unwrap().contains("exceeds max_depth 2"));
     }
     
+    #[tokio::test]
+    async fn test_delegation_shares_scratchpad() {
+        use multi_agent_core::mocks::MockLlm;
+
+        let manager = DelegationManager::new(MockLlm::new(vec![
+            "Found the lead.\nSCRATCHPAD: lead = {\"id\": 7}".to_string(),
+            "Following up.".to_string(),
+        ]));
+
+        let first = manager.delegate(DelegationRequest::new("Find a lead")).await.unwrap();
+        assert_eq!(first.scratchpad_writes["lead"], serde_json::json!({ "id": 7 }));
+        assert_eq!(manager.scratchpad().read("lead"), Some(serde_json::json!({ "id": 7 })));
+
+        let context = scratchpad_context("", &manager.scratchpad().snapshot());
+        assert!(context.contains("lead = {\"id\":7}"));
+
+        let second = manager.delegate(DelegationRequest::new("Follow up")).await.unwrap();
+        assert!(second.scratchpad_writes.is_empty());
+        assert!(manager.scratchpad().read("lead").is_some());
+    }
+
+    /// Agent behaviour is encoded in its ID: "<ok|fail>_<delay ms>".
+    struct ScriptedDelegator;
+
+    #[async_trait]
+    impl Delegator for ScriptedDelegator {
+        async fn delegate(&self, request: DelegationRequest) -> Result<DelegationResult> {
+            let agent = request.agent_id.clone().unwrap_or_default();
+            let (outcome, delay) = agent.split_once('_').unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(delay.parse().unwrap())).await;
+            match outcome {
+                "ok" => Ok(DelegationResult::success(request.id, agent.clone(), 1)),
+                "fail" => Ok(DelegationResult::failure(request.id, "agent failed".to_string())),
+                _ => Err(Error::controller("agent crashed")),
+            }
+        }
+
+        async fn check_delegation(&self, _id: &str) -> Result<Option<DelegationResult>> {
+            Ok(None)
+        }
+    }
+
+    fn agents(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn succeeded(results: &[Result<DelegationResult>]) -> Vec<String> {
+        results
+            .iter()
+            .filter_map(|r| r.as_ref().ok())
+            .filter(|r| r.success)
+            .map(|r| r.result.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_wait_all() {
+        let results = ScriptedDelegator
+            .broadcast(
+                DelegationRequest::new("Research"),
+                agents(&["ok_30", "fail_10", "crash_5", "ok_20"]),
+            )
+            .await;
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(succeeded(&results), vec!["ok_30", "ok_20"]);
+        assert!(!results[1].as_ref().unwrap().success);
+        assert!(results[2].is_err());
+        assert!(results[0].as_ref().unwrap().delegation_id.ends_with("_ok_30"));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_wait_first() {
+        let start = std::time::Instant::now();
+        let results = ScriptedDelegator
+            .broadcast_with(
+                DelegationRequest::new("Research"),
+                agents(&["fail_5", "ok_20", "ok_2000"]),
+                BroadcastStrategy::WaitFirst,
+            )
+            .await;
+
+        assert!(start.elapsed() < std::time::Duration::from_millis(1000));
+        assert_eq!(succeeded(&results), vec!["ok_20"]);
+        // The slow agent was cancelled
+        assert!(results[2].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_quorum() {
+        let start = std::time::Instant::now();
+        let results = ScriptedDelegator
+            .broadcast_with(
+                DelegationRequest::new("Research"),
+                agents(&["ok_10", "fail_5", "ok_30", "ok_2000"]),
+                BroadcastStrategy::QuorumN(2),
+            )
+            .await;
+
+        assert!(start.elapsed() < std::time::Duration::from_millis(1000));
+        assert_eq!(succeeded(&results), vec!["ok_10", "ok_30"]);
+        assert!(results[3].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_quorum_not_reached() {
+        let results = ScriptedDelegator
+            .broadcast_with(
+                DelegationRequest::new("Research"),
+                agents(&["ok_10", "fail_5", "crash_5"]),
+                BroadcastStrategy::QuorumN(2),
+            )
+            .await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(succeeded(&results), vec!["ok_10"]);
+    }
+
     #[test]
     fn test_delegation_result() {
         let success = DelegationResult::success("del_123".to_string(), "Done".to_string(), 3);
@@ -57,13 +57,16 @@ impl ToolExecutor {
                 name: name.clone(),
                 arguments: args,
                 result: Some(Arc::new(observation.clone())),
+                retry_count: 0,
             }),
             timestamp: crate::react::chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
         });
 
         // Update task state
         if let Some(ref mut task_state) = session.task_state {
-            task_state.observations.push(Arc::new(observation.clone()));
+            task_state.observe(Some(&name), observation.clone());
         }
 
         // Run post-execute hooks
@@ -83,20 +86,15 @@ impl ToolExecutor {
     ) -> Result<()> {
         for cap in &self.capabilities {
             if cap.name() == "security_guardrails" {
-                let mut temp_session = Session {
-                    id: "security_check".to_string(),
-                    status: multi_agent_core::types::SessionStatus::Running,
-                    history: vec![HistoryEntry {
-                        role: "user".to_string(),
-                        content: Arc::new(serde_json::to_string(args).unwrap_or_default()),
-                        tool_call: None,
-                        timestamp: crate::react::chrono_timestamp(),
-                    }],
-                    task_state: None,
-                    token_usage: Default::default(),
-                    created_at: crate::react::chrono_timestamp(),
-                    updated_at: crate::react::chrono_timestamp(),
-                };
+                let mut temp_session = Session::new("security_check");
+                temp_session.history.push(HistoryEntry {
+                    role: "user".to_string(),
+                    content: Arc::new(serde_json::to_string(args).unwrap_or_default()),
+                    tool_call: None,
+                    timestamp: crate::react::chrono_timestamp(),
+                    compressed_from: None,
+                    images: Vec::new(),
+                });
                 cap.on_pre_reasoning(&mut temp_session)
                     .await
                     .map_err(|e| Error::controller(e.to_string()))?;
@@ -0,0 +1,261 @@
+//! Provider failover for LLM calls.
+//!
+//! `FailoverLlmClient` wraps an ordered list of providers and sends each call
+//! to the first available one. Each provider has a `CircuitBreaker`, so a
+//! failing provider is taken out of rotation for a cooldown period.
+
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use multi_agent_core::{
+    traits::{ChatMessage, LlmClient, LlmResponse},
+    types::ProviderHealth,
+    Error, Result,
+};
+
+use crate::circuit_breaker::CircuitBreaker;
+
+/// A provider and its health state.
+struct Provider {
+    client: Arc<dyn LlmClient>,
+    breaker: CircuitBreaker,
+    /// Taken out of rotation by `ProviderHealth::Unhealthy`.
+    drained: AtomicBool,
+}
+
+impl Provider {
+    fn health(&self) -> ProviderHealth {
+        if self.drained.load(Ordering::SeqCst) {
+            ProviderHealth::Unhealthy
+        } else {
+            self.breaker.health()
+        }
+    }
+
+    fn set_health(&self, health: ProviderHealth) {
+        self.drained.store(health == ProviderHealth::Unhealthy, Ordering::SeqCst);
+        match health {
+            ProviderHealth::CircuitOpen => self.breaker.trip(),
+            _ => self.breaker.reset(),
+        }
+    }
+}
+
+/// LLM client that fails over between providers in priority order.
+///
+/// A provider failing `failure_threshold` calls in a row has its circuit
+/// opened and is skipped for a cooldown, after which a single probe call is
+/// let through. Providers marked `Unhealthy` are skipped until marked otherwise.
+pub struct FailoverLlmClient {
+    providers: Vec<Provider>,
+}
+
+impl FailoverLlmClient {
+    /// Create a client over providers in priority order with their initial health.
+    ///
+    /// `Degraded` is treated as `Healthy`.
+    pub fn new(providers: Vec<(Arc<dyn LlmClient>, ProviderHealth)>) -> Self {
+        let providers = providers
+            .into_iter()
+            .map(|(client, health)| {
+                let provider = Provider {
+                    client,
+                    breaker: CircuitBreaker::new(3, Duration::from_secs(30)),
+                    drained: AtomicBool::new(false),
+                };
+                provider.set_health(health);
+                provider
+            })
+            .collect();
+        Self { providers }
+    }
+
+    /// Set the consecutive failures after which a provider's circuit opens (default 3).
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        for provider in &mut self.providers {
+            provider.breaker.failure_threshold = threshold.max(1);
+        }
+        self
+    }
+
+    /// Set how long an open circuit first keeps a provider out of rotation (default 30s).
+    ///
+    /// The cooldown doubles after every failed probe, up to 32x this value.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        for provider in &mut self.providers {
+            let health = provider.health();
+            provider.breaker = CircuitBreaker::new(provider.breaker.failure_threshold, cooldown);
+            provider.set_health(health);
+        }
+        self
+    }
+
+    /// Current health of each provider, in priority order.
+    pub fn health(&self) -> Vec<ProviderHealth> {
+        self.providers.iter().map(Provider::health).collect()
+    }
+
+    /// Override the health of the provider at `index`, e.g. to drain or restore it.
+    pub fn set_health(&self, index: usize, health: ProviderHealth) {
+        if let Some(provider) = self.providers.get(index) {
+            provider.set_health(health);
+        }
+    }
+
+    /// Run `call` against available providers in order until one succeeds.
+    async fn route<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn(Arc<dyn LlmClient>) -> Fut + Send,
+        Fut: Future<Output = Result<T>> + Send,
+        T: Send,
+    {
+        let mut last_error = None;
+        for (index, provider) in self.providers.iter().enumerate() {
+            if provider.drained.load(Ordering::SeqCst) || !provider.breaker.try_acquire() {
+                continue;
+            }
+            match call(provider.client.clone()).await {
+                Ok(value) => {
+                    provider.breaker.record_success();
+                    return Ok(value);
+                }
+                Err(e) => {
+                    tracing::warn!(provider = index, error = %e, "LLM provider call failed, failing over");
+                    provider.breaker.record_failure();
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::ModelProvider("No available LLM provider".to_string())))
+    }
+}
+
+#[async_trait]
+impl LlmClient for FailoverLlmClient {
+    async fn complete(&self, prompt: &str) -> Result<LlmResponse> {
+        self.route(|llm| async move { llm.complete(prompt).await }).await
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse> {
+        self.route(|llm| async move { llm.chat(messages).await }).await
+    }
+
+    async fn chat_with_max_tokens(&self, messages: &[ChatMessage], max_tokens: u64) -> Result<LlmResponse> {
+        self.route(|llm| async move { llm.chat_with_max_tokens(messages, max_tokens).await })
+            .await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.route(|llm| async move { llm.embed(text).await }).await
+    }
+
+    async fn count_tokens(&self, messages: &[ChatMessage]) -> Result<u64> {
+        self.route(|llm| async move { llm.count_tokens(messages).await }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multi_agent_core::mocks::MockLlm;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Provider that always fails, counting its calls.
+    #[derive(Default)]
+    struct DownLlm {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmClient for DownLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(Error::ModelProvider("503 service unavailable".to_string()))
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    fn user(content: &str) -> Vec<ChatMessage> {
+        vec![ChatMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+            tool_calls: None,
+            images: Vec::new(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_traffic_shifts_to_secondary() {
+        let primary = Arc::new(DownLlm::default());
+        let secondary = Arc::new(MockLlm::constant("from secondary"));
+        let client = FailoverLlmClient::new(vec![
+            (primary.clone(), ProviderHealth::Healthy),
+            (secondary.clone(), ProviderHealth::Healthy),
+        ])
+        .with_failure_threshold(2)
+        .with_cooldown(Duration::from_secs(3600));
+
+        for _ in 0..2 {
+            let response = client.chat(&user("hi")).await.unwrap();
+            assert_eq!(response.content, "from secondary");
+        }
+        assert_eq!(client.health(), vec![ProviderHealth::CircuitOpen, ProviderHealth::Healthy]);
+
+        // The open circuit keeps the primary out of rotation
+        client.chat(&user("hi")).await.unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(secondary.call_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_primary_retried_after_cooldown() {
+        let primary = Arc::new(DownLlm::default());
+        let secondary = Arc::new(MockLlm::constant("from secondary"));
+        let client = FailoverLlmClient::new(vec![
+            (primary.clone(), ProviderHealth::Healthy),
+            (secondary.clone(), ProviderHealth::Healthy),
+        ])
+        .with_failure_threshold(1)
+        .with_cooldown(Duration::from_millis(50));
+
+        client.chat(&user("hi")).await.unwrap();
+        client.chat(&user("hi")).await.unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        client.chat(&user("hi")).await.unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_providers_are_skipped() {
+        let primary = Arc::new(MockLlm::constant("from primary"));
+        let client = FailoverLlmClient::new(vec![(primary.clone(), ProviderHealth::Unhealthy)]);
+
+        let err = client.chat(&user("hi")).await.unwrap_err();
+        assert!(err.to_string().contains("No available LLM provider"), "{}", err);
+        assert_eq!(primary.call_count(), 0);
+
+        client.set_health(0, ProviderHealth::Healthy);
+        assert_eq!(client.chat(&user("hi")).await.unwrap().content, "from primary");
+    }
+
+    #[tokio::test]
+    async fn test_all_providers_failing_returns_last_error() {
+        let client = FailoverLlmClient::new(vec![(Arc::new(DownLlm::default()), ProviderHealth::Healthy)]);
+
+        let err = client.chat(&user("hi")).await.unwrap_err();
+        assert!(err.to_string().contains("503"), "{}", err);
+        assert_eq!(client.health(), vec![ProviderHealth::Degraded]);
+    }
+}
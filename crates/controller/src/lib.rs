@@ -16,15 +16,42 @@ pub mod planning;
 pub mod builder;
 pub mod parser;
 pub mod executor;
+pub mod rate_limit;
+pub mod schedule;
+pub mod pool;
+pub mod tier;
+pub mod failover;
+pub mod circuit_breaker;
+pub mod approval;
+pub mod stop;
+pub mod scratchpad;
+mod builtin_tools;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
-pub use persistence::InMemorySessionStore;
+pub use persistence::{FileSessionStore, InMemorySessionStore};
+#[cfg(feature = "sqlite")]
+pub use persistence::SqliteSessionStore;
 pub use multi_agent_core::traits::SessionStore;
-pub use react::{ReActConfig, ReActController, chrono_timestamp};
-pub use parser::{ActionParser, ReActAction};
+pub use react::{ReActConfig, ReActController, ReActEvent, ToolMetrics, chrono_timestamp};
+pub use parser::{ActionParser, DefaultTextParser, JsonActionParser, ReActAction};
+pub use dag::{Dag, DagExecutor, DagNode, DagTask, NodeId, TaskNode};
+pub use sop::{PromptStep, Sop, SopRunner, SuccessCondition};
 pub use builder::ReActBuilder;
 pub use capability::{
-    AgentCapability, CompressionCapability, DelegationCapability, McpCapability, SecurityCapability,
-    ReflectionCapability,
+    AgentCapability, CommandPolicy, CompressionCapability, DelegationCapability, McpCapability, PolicyDecision,
+    SecurityCapability, ReflectionCapability, ReflectionOutcome,
 };
-pub use memory::MemoryCapability;
+pub use memory::{EmbeddingRetriever, KeywordRetriever, MemoryCapability, MemoryHit, MemoryLink, MemoryRetriever};
 pub use planning::PlanningCapability;
+pub use rate_limit::{GlobalRateLimiter, RateLimiter, TokenBucketRateLimiter};
+pub use pool::PooledController;
+pub use tier::{HeuristicTierSelector, TierSelector};
+pub use failover::FailoverLlmClient;
+pub use circuit_breaker::{CircuitBreaker, CircuitState, Clock, SystemClock};
+pub use approval::{ApprovalDecision, ApprovalHook};
+pub use stop::{StopCondition, ToolResultStop};
+pub use scratchpad::{ScratchpadTool, SharedScratchpad};
+pub use schedule::{
+    InMemoryScheduleStore, ScheduleClock, ScheduleEntry, ScheduleStore, ScheduledController, SystemScheduleClock,
+};
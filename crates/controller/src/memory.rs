@@ -4,29 +4,207 @@
 //! and archives the execution result upon completion.
 
 use async_trait::async_trait;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
 use tokio::sync::Mutex;
 use chrono::Utc;
 
 use multi_agent_core::{
     traits::{MemoryStore, MemoryEntry, LlmClient},
-    types::{Session, AgentResult, HistoryEntry},
+    types::{Session, AgentResult, HistoryEntry, RefId},
     Result, Error,
 };
 use crate::capability::AgentCapability;
 
+/// Heading of the system prompt section listing recalled memories.
+pub const PRIOR_KNOWLEDGE_HEADER: &str = "RELEVANT PRIOR KNOWLEDGE";
+
+/// A directed, labelled link between two memory entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryLink {
+    /// Source entry.
+    pub from: RefId,
+    /// Target entry.
+    pub to: RefId,
+    /// Relation label (e.g., "manages", "supersedes").
+    pub relation: String,
+    /// Link strength (0.0 to 1.0).
+    pub weight: f32,
+}
+
+/// A memory returned by `MemoryCapability::recall`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryHit {
+    /// Key the memory was stored under.
+    pub key: String,
+    /// Stored content.
+    pub content: String,
+    /// Relevance to the query (higher is better; scale depends on the retriever).
+    pub score: f32,
+}
+
+/// Backend that stores memories and recalls the ones relevant to a query.
+#[async_trait]
+pub trait MemoryRetriever: Send + Sync {
+    /// Store `content` under `key`, replacing any previous content.
+    async fn store(&self, key: &str, content: &str, metadata: HashMap<String, String>) -> Result<()>;
+
+    /// Recall up to `top_k` memories, most relevant first.
+    async fn recall(&self, query: &str, top_k: usize) -> Result<Vec<MemoryHit>>;
+}
+
+/// Retriever backed by a vector store and an embedding model.
+pub struct EmbeddingRetriever {
+    store: Arc<dyn MemoryStore>,
+    llm: Arc<dyn LlmClient>,
+}
+
+impl EmbeddingRetriever {
+    /// Create a retriever embedding text with `llm` and searching `store`.
+    pub fn new(store: Arc<dyn MemoryStore>, llm: Arc<dyn LlmClient>) -> Self {
+        Self { store, llm }
+    }
+}
+
+#[async_trait]
+impl MemoryRetriever for EmbeddingRetriever {
+    async fn store(&self, key: &str, content: &str, metadata: HashMap<String, String>) -> Result<()> {
+        let embedding = self.llm.embed(content).await
+            .map_err(|e| Error::controller(format!("Failed to embed memory: {}", e)))?;
+        self.store.add(MemoryEntry {
+            id: key.to_string(),
+            content: content.to_string(),
+            embedding,
+            metadata,
+        }).await
+    }
+
+    async fn recall(&self, query: &str, top_k: usize) -> Result<Vec<MemoryHit>> {
+        let embedding = self.llm.embed(query).await
+            .map_err(|e| Error::controller(format!("Failed to embed query: {}", e)))?;
+        let entries = self.store.search(&embedding, top_k).await?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| MemoryHit {
+                score: cosine_similarity(&embedding, &entry.embedding),
+                key: entry.id,
+                content: entry.content,
+            })
+            .collect())
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|y| y * y).sum::<f32>().sqrt();
+    if norm == 0.0 { 0.0 } else { dot / norm }
+}
+
+/// Embedding-free retriever scoring memories by TF-IDF keyword overlap.
+///
+/// Entries are also written to `store` so they can be linked and fetched,
+/// but only entries stored through this retriever are searchable.
+pub struct KeywordRetriever {
+    store: Arc<dyn MemoryStore>,
+    /// Term counts per entry key.
+    index: RwLock<HashMap<String, HashMap<String, usize>>>,
+}
+
+impl KeywordRetriever {
+    /// Create a keyword retriever writing entries to `store`.
+    pub fn new(store: Arc<dyn MemoryStore>) -> Self {
+        Self {
+            store,
+            index: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+/// Lowercased alphanumeric terms of `text`.
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[async_trait]
+impl MemoryRetriever for KeywordRetriever {
+    async fn store(&self, key: &str, content: &str, metadata: HashMap<String, String>) -> Result<()> {
+        self.store.add(MemoryEntry {
+            id: key.to_string(),
+            content: content.to_string(),
+            embedding: Vec::new(),
+            metadata,
+        }).await?;
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for term in terms(content) {
+            *counts.entry(term).or_default() += 1;
+        }
+        self.index.write().unwrap_or_else(|e| e.into_inner()).insert(key.to_string(), counts);
+        Ok(())
+    }
+
+    async fn recall(&self, query: &str, top_k: usize) -> Result<Vec<MemoryHit>> {
+        let query_terms: HashSet<String> = terms(query).collect();
+        let mut scored: Vec<(String, f32)> = {
+            let index = self.index.read().unwrap_or_else(|e| e.into_inner());
+            let docs = index.len() as f32;
+            let idf: HashMap<&str, f32> = query_terms
+                .iter()
+                .map(|term| {
+                    let df = index.values().filter(|counts| counts.contains_key(term)).count() as f32;
+                    (term.as_str(), ((docs + 1.0) / (df + 1.0)).ln() + 1.0)
+                })
+                .collect();
+
+            index
+                .iter()
+                .filter_map(|(key, counts)| {
+                    let len = counts.values().sum::<usize>().max(1) as f32;
+                    let score: f32 = idf
+                        .iter()
+                        .filter_map(|(term, idf)| counts.get(*term).map(|tf| *tf as f32 / len * idf))
+                        .sum();
+                    (score > 0.0).then(|| (key.clone(), score))
+                })
+                .collect()
+        };
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        scored.truncate(top_k);
+
+        let mut hits = Vec::with_capacity(scored.len());
+        for (key, score) in scored {
+            if let Some(entry) = self.store.get(&key).await? {
+                hits.push(MemoryHit {
+                    key,
+                    content: entry.content,
+                    score,
+                });
+            }
+        }
+        Ok(hits)
+    }
+}
+
 /// Capability for Long-Term Memory (RAG).
 pub struct MemoryCapability {
     /// The vector store for memory.
     store: Arc<dyn MemoryStore>,
-    /// The LLM client for generating embeddings.
-    llm: Arc<dyn LlmClient>,
+    /// Backend used to store and recall memories.
+    retriever: Arc<dyn MemoryRetriever>,
     /// Number of memories to inject.
     limit: usize,
     /// Minimum similarity score (0.0 to 1.0) - logic to be added in search.
     _threshold: f32,
     /// Cached plan/goal to archive on finish.
     current_goal: Mutex<Option<String>>,
+    /// Knowledge graph of links between entries, keyed by source entry.
+    links: Mutex<HashMap<RefId, Vec<MemoryLink>>>,
 }
 
 impl MemoryCapability {
@@ -38,21 +216,102 @@ impl MemoryCapability {
         threshold: f32,
     ) -> Self {
         Self {
+            retriever: Arc::new(EmbeddingRetriever::new(store.clone(), llm)),
             store,
-            llm,
             limit,
             _threshold: threshold,
             current_goal: Mutex::new(None),
+            links: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a MemoryCapability with embedding-free keyword retrieval.
+    pub fn keyword(store: Arc<dyn MemoryStore>, limit: usize) -> Self {
+        Self {
+            retriever: Arc::new(KeywordRetriever::new(store.clone())),
+            store,
+            limit,
+            _threshold: 0.0,
+            current_goal: Mutex::new(None),
+            links: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Use a custom retrieval backend.
+    pub fn with_retriever(mut self, retriever: Arc<dyn MemoryRetriever>) -> Self {
+        self.retriever = retriever;
+        self
+    }
+
+    /// Store `content` under `key`.
+    pub async fn store(&self, key: &str, content: &str) -> Result<()> {
+        self.retriever.store(key, content, HashMap::new()).await
+    }
+
+    /// Recall up to `top_k` memories relevant to `query`, most relevant first.
+    pub async fn recall(&self, query: &str, top_k: usize) -> Result<Vec<MemoryHit>> {
+        self.retriever.recall(query, top_k).await
+    }
+
+    /// Link two memory entries with a named relation.
+    pub async fn link(&self, entry_a: &RefId, entry_b: &RefId, relation: &str) -> Result<()> {
+        if entry_a == entry_b {
+            return Err(Error::controller(format!("Cannot link memory entry {} to itself", entry_a)));
         }
+
+        self.links.lock().await.entry(entry_a.clone()).or_default().push(MemoryLink {
+            from: entry_a.clone(),
+            to: entry_b.clone(),
+            relation: relation.to_string(),
+            weight: 1.0,
+        });
+        Ok(())
     }
 
-    async fn retrieve_context(&self, goal: &str) -> Result<Vec<MemoryEntry>> {
-        // 1. Generate embedding for the goal
-        let embedding = self.llm.embed(goal).await
-            .map_err(|e| Error::controller(format!("Failed to embed goal: {}", e)))?;
+    /// Get the outgoing links of an entry.
+    pub async fn links_from(&self, entry_id: &RefId) -> Vec<MemoryLink> {
+        self.links.lock().await.get(entry_id).cloned().unwrap_or_default()
+    }
+
+    /// Find entries reachable from `entry_id` within `max_hops` links.
+    ///
+    /// Each entry is scored by the product of link weights along the shortest
+    /// path, divided by the path length, so closer entries rank higher.
+    pub async fn related(&self, entry_id: &RefId, max_hops: usize) -> Result<Vec<(MemoryEntry, f32)>> {
+        let reachable = {
+            let links = self.links.lock().await;
+            let mut visited: HashSet<RefId> = HashSet::from([entry_id.clone()]);
+            let mut frontier: Vec<(RefId, f32)> = vec![(entry_id.clone(), 1.0)];
+            let mut reachable: Vec<(RefId, f32)> = Vec::new();
 
-        // 2. Search memory
-        self.store.search(&embedding, self.limit).await
+            for hop in 1..=max_hops {
+                let mut next = Vec::new();
+                for (id, path_weight) in &frontier {
+                    for link in links.get(id).into_iter().flatten() {
+                        if visited.insert(link.to.clone()) {
+                            let weight = path_weight * link.weight;
+                            reachable.push((link.to.clone(), weight / hop as f32));
+                            next.push((link.to.clone(), weight));
+                        }
+                    }
+                }
+                if next.is_empty() {
+                    break;
+                }
+                frontier = next;
+            }
+            reachable
+        };
+
+        let mut related = Vec::with_capacity(reachable.len());
+        for (id, score) in reachable {
+            if let Some(entry) = self.store.get(id.as_str()).await? {
+                related.push((entry, score));
+            }
+        }
+        related.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(related)
     }
 }
 
@@ -72,8 +331,13 @@ impl AgentCapability for MemoryCapability {
         // Cache goal for archiving later
         *self.current_goal.lock().await = Some(goal.clone());
 
+        // The controller already recalled memories into the system prompt
+        if session.history.first().is_some_and(|e| e.role == "system" && e.content.starts_with(PRIOR_KNOWLEDGE_HEADER)) {
+            return Ok(());
+        }
+
         tracing::info!(goal = %goal, "Searching memory for context");
-        match self.retrieve_context(&goal).await {
+        match self.recall(&goal, self.limit).await {
             Ok(memories) => {
                 if !memories.is_empty() {
                     let mut context_msg = "Here are some relevant past experiences found in long-term memory:\n\n".to_string();
@@ -88,6 +352,8 @@ impl AgentCapability for MemoryCapability {
                         content: Arc::new(context_msg),
                         tool_call: None,
                         timestamp: Utc::now().timestamp(),
+                        compressed_from: None,
+                        images: Vec::new(),
                     });
                      tracing::info!("Injected {} memories into context", memories.len());
                 }
@@ -116,28 +382,23 @@ impl AgentCapability for MemoryCapability {
                  AgentResult::Text(text) => format!("Goal: {}\nResult: {}", goal_text, text),
                  AgentResult::Data(val) => format!("Goal: {}\nResult Data: {}", goal_text, val),
                  AgentResult::File { filename, .. } => format!("Goal: {}\nResult File: {}", goal_text, filename),
+                 AgentResult::Files(files) => {
+                     let names: Vec<&str> = files.iter().map(|f| f.filename.as_str()).collect();
+                     format!("Goal: {}\nResult Files: {}", goal_text, names.join(", "))
+                 }
                  _ => return Ok(()),
              };
 
              tracing::info!("Archiving experience to memory");
 
-             // Embed
-             let embedding = self.llm.embed(&content).await
-                 .map_err(|e| Error::controller(format!("Failed to embed experience: {}", e)))?;
-
-             // Store
-             let entry = MemoryEntry {
-                 id: uuid::Uuid::new_v4().to_string(),
-                 content,
-                 embedding,
-                 metadata: std::collections::HashMap::from([
-                     ("type".to_string(), "experience".to_string()),
-                     ("session_id".to_string(), session.id.clone()),
-                     ("timestamp".to_string(), Utc::now().to_rfc3339()),
-                 ]),
-             };
+             let metadata = HashMap::from([
+                 ("type".to_string(), "experience".to_string()),
+                 ("session_id".to_string(), session.id.clone()),
+                 ("timestamp".to_string(), Utc::now().to_rfc3339()),
+             ]);
+             let key = uuid::Uuid::new_v4().to_string();
 
-             if let Err(e) = self.store.add(entry).await {
+             if let Err(e) = self.retriever.store(&key, &content, metadata).await {
                  tracing::warn!("Failed to save experience to memory: {}", e);
              }
         }
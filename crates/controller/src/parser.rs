@@ -1,6 +1,8 @@
 //! Parser module for LLM response parsing.
 //!
 //! Extracts structured actions (ToolCall, FinalAnswer, etc.) from raw LLM text.
+//! `DefaultTextParser` reads the ReAct text format of the default system
+//! prompt; `JsonActionParser` reads `{"action": ..., "args": ...}` objects.
 
 use crate::capability::AgentCapability;
 use std::sync::Arc;
@@ -17,6 +19,8 @@ pub enum ReActAction {
     FinalAnswer(String),
     /// Continue thinking (no action yet).
     Think(String),
+    /// Call several independent tools concurrently.
+    ParallelToolCalls(Vec<(String, serde_json::Value)>),
     /// Delegate to a subagent (v0.2 autonomous capability).
     Delegate {
         objective: String,
@@ -28,20 +32,21 @@ pub enum ReActAction {
     },
 }
 
-/// Parser for LLM responses, supporting multiple formats.
-pub struct ActionParser {
+/// Turns an LLM response into the next action of the ReAct loop.
+pub trait ActionParser: Send + Sync {
+    /// Parse an LLM response into a structured action.
+    fn parse(&self, response: &str) -> ReActAction;
+}
+
+/// Parser for the ReAct text format (`ACTION:`/`ARGS:`, `FINAL ANSWER:`),
+/// also accepting OpenAI-style function call JSON.
+pub struct DefaultTextParser {
     /// Registered capabilities for custom action parsing.
     capabilities: Vec<Arc<dyn AgentCapability>>,
 }
 
-impl ActionParser {
-    /// Create a new parser with the given capabilities.
-    pub fn new(capabilities: Vec<Arc<dyn AgentCapability>>) -> Self {
-        Self { capabilities }
-    }
-
-    /// Parse an LLM response into a structured action.
-    pub fn parse(&self, response: &str) -> ReActAction {
+impl ActionParser for DefaultTextParser {
+    fn parse(&self, response: &str) -> ReActAction {
         let response_trimmed = response.trim();
 
         // 1. Check capabilities for custom actions (Delegation, MCP, etc.)
@@ -69,6 +74,13 @@ impl ActionParser {
         // Default: treat as thought
         ReActAction::Think(response_trimmed.to_string())
     }
+}
+
+impl DefaultTextParser {
+    /// Create a new parser with the given capabilities.
+    pub fn new(capabilities: Vec<Arc<dyn AgentCapability>>) -> Self {
+        Self { capabilities }
+    }
 
     /// Try to parse OpenAI-style function call from JSON.
     fn try_parse_function_call(&self, response: &str) -> Option<ReActAction> {
@@ -88,13 +100,15 @@ impl ActionParser {
         None
     }
 
-    /// Extract tool call from a JSON object.
-    fn extract_tool_call(&self, json: &serde_json::Value) -> Option<ReActAction> {
+    /// Extract a tool call from a JSON object, such as a native `LlmResponse::tool_calls` entry.
+    pub fn extract_tool_call(&self, json: &serde_json::Value) -> Option<ReActAction> {
         // OpenAI format: { "function": { "name": "...", "arguments": "..." } }
         if let Some(func) = json.get("function") {
             let name = func.get("name")?.as_str()?.to_string();
-            let args_str = func.get("arguments")?.as_str()?;
-            let args = serde_json::from_str(args_str).ok()?;
+            let args = match func.get("arguments")? {
+                serde_json::Value::String(args_str) => serde_json::from_str(args_str).ok()?,
+                args => args.clone(),
+            };
             return Some(ReActAction::ToolCall { name, args });
         }
 
@@ -111,36 +125,220 @@ impl ActionParser {
     }
 
     /// Try to parse ACTION/ARGS text format.
+    ///
+    /// ARGS may span multiple lines and be wrapped in a markdown code fence. The
+    /// block ends at a blank line (outside a fence), the next keyword, or the end
+    /// of the response.
     fn try_parse_text_format(&self, response: &str) -> Option<ReActAction> {
-        let lines: Vec<&str> = response.lines().collect();
-        let mut tool_name = None;
-        let mut args_json = None;
+        // Each ACTION starts a new block; ARGS attaches to the most recent one
+        let mut blocks: Vec<(String, Option<Vec<&str>>)> = Vec::new();
+        let mut collecting = false;
+        let mut in_fence = false;
 
-        for line in lines {
-            if line.starts_with("ACTION:") {
-                tool_name = Some(line.trim_start_matches("ACTION:").trim().to_string());
-            } else if line.starts_with("ARGS:") {
-                args_json = Some(line.trim_start_matches("ARGS:").trim().to_string());
+        for line in response.lines() {
+            let trimmed = line.trim();
+            if let Some(name) = line.strip_prefix("ACTION:") {
+                blocks.push((name.trim().to_string(), None));
+                collecting = false;
+            } else if let Some(first) = line.strip_prefix("ARGS:") {
+                let Some((_, args_lines)) = blocks.last_mut() else {
+                    continue;
+                };
+                let first = first.trim();
+                in_fence = first.starts_with("```");
+                *args_lines = Some(vec![first]);
+                collecting = true;
+            } else if collecting {
+                if !in_fence && is_keyword_line(trimmed) {
+                    collecting = false;
+                    continue;
+                }
+                let lines = blocks
+                    .last_mut()
+                    .and_then(|(_, lines)| lines.as_mut())
+                    .expect("collecting implies ARGS was seen");
+                let has_content = lines.iter().any(|l| !l.trim().is_empty());
+                if trimmed.is_empty() && !in_fence && has_content {
+                    collecting = false;
+                    continue;
+                }
+                if trimmed.starts_with("```") {
+                    // Opening fence on its own line, or the closing fence
+                    in_fence = !in_fence;
+                    if !in_fence {
+                        lines.push(trimmed);
+                        collecting = false;
+                        continue;
+                    }
+                }
+                lines.push(line);
             }
         }
 
-        if let (Some(name), Some(args_str)) = (tool_name, args_json) {
-            if let Ok(args) = serde_json::from_str::<serde_json::Value>(&args_str) {
-                return Some(ReActAction::ToolCall { name, args });
+        let mut calls: Vec<(String, serde_json::Value)> = blocks
+            .into_iter()
+            .filter_map(|(name, args_lines)| {
+                let args_block = args_lines?.join("\n");
+                let args_str = strip_code_fence(&args_block);
+                let args = serde_json::from_str::<serde_json::Value>(args_str).unwrap_or_else(|e| {
+                    tracing::warn!(tool = %name, error = %e, "Failed to parse ARGS, falling back to {{}}");
+                    serde_json::json!({})
+                });
+                Some((name, args))
+            })
+            .collect();
+
+        match calls.len() {
+            0 => None,
+            1 => {
+                let (name, args) = calls.remove(0);
+                Some(ReActAction::ToolCall { name, args })
             }
+            _ => Some(ReActAction::ParallelToolCalls(calls)),
         }
+    }
+}
 
-        None
+/// Parser for JSON responses, one action object per response.
+///
+/// Accepted shapes:
+/// - `{"type": "tool_call", "name": "<tool>", "args": {...}}`
+/// - `{"type": "final", "answer": "..."}`
+/// - `{"type": "delegate", "objective": "...", "context": "..."}`
+/// - `{"action": "<tool>" | "final_answer" | "delegate", "args": {...}}`
+///
+/// The first balanced JSON object is used, so surrounding prose and code
+/// fences are tolerated. Responses without a valid action object are treated
+/// as thoughts. Pair it with a system prompt template describing the format.
+#[derive(Debug, Clone, Default)]
+pub struct JsonActionParser;
+
+impl JsonActionParser {
+    /// Create a new JSON action parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `{"type": ...}` objects.
+    fn parse_typed(json: &serde_json::Value, kind: &str) -> Option<ReActAction> {
+        let field = |name: &str| json.get(name).and_then(|v| v.as_str()).map(str::to_string);
+        match kind {
+            "tool_call" => Some(ReActAction::ToolCall {
+                name: field("name")?,
+                args: json.get("args").cloned().unwrap_or_else(|| serde_json::json!({})),
+            }),
+            "final" => Some(ReActAction::FinalAnswer(field("answer")?)),
+            "delegate" => Some(ReActAction::Delegate {
+                objective: field("objective")?,
+                context: field("context").unwrap_or_default(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// `{"action": ..., "args": ...}` objects.
+    fn parse_action(json: &serde_json::Value, action: &str) -> ReActAction {
+        let args = json.get("args").cloned().unwrap_or_else(|| serde_json::json!({}));
+        let field = |name: &str| args.get(name).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+        match action {
+            "final_answer" => ReActAction::FinalAnswer(match args {
+                serde_json::Value::String(ref answer) => answer.clone(),
+                _ => field("answer"),
+            }),
+            "delegate" => ReActAction::Delegate {
+                objective: field("objective"),
+                context: field("context"),
+            },
+            name => ReActAction::ToolCall {
+                name: name.to_string(),
+                args,
+            },
+        }
     }
 }
 
+impl ActionParser for JsonActionParser {
+    fn parse(&self, response: &str) -> ReActAction {
+        let response_trimmed = response.trim();
+        let json = first_json_object(response_trimmed)
+            .and_then(|object| serde_json::from_str::<serde_json::Value>(object).ok());
+
+        let action = json.as_ref().and_then(|json| {
+            if let Some(kind) = json.get("type").and_then(|t| t.as_str()) {
+                Self::parse_typed(json, kind)
+            } else {
+                let action = json.get("action").and_then(|a| a.as_str())?;
+                Some(Self::parse_action(json, action))
+            }
+        });
+        action.unwrap_or_else(|| {
+            tracing::debug!("No JSON action found in response, treating it as a thought");
+            ReActAction::Think(response_trimmed.to_string())
+        })
+    }
+}
+
+/// The first balanced `{...}` in `text`, skipping braces inside JSON strings.
+fn first_json_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[start..start + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether a line starts a new section of a ReAct response.
+fn is_keyword_line(line: &str) -> bool {
+    ["ACTION:", "ARGS:", "THOUGHT:", "OBSERVATION:", "FINAL ANSWER:"]
+        .iter()
+        .any(|kw| line.starts_with(kw))
+}
+
+/// Strip a surrounding markdown code fence (e.g. ```json ... ```), if any.
+fn strip_code_fence(block: &str) -> &str {
+    let block = block.trim();
+    let Some(inner) = block.strip_prefix("```") else {
+        return block;
+    };
+    // Drop the language tag on the opening line
+    let inner = match inner.find('\n') {
+        Some(pos) => &inner[pos + 1..],
+        None => inner,
+    };
+    inner.trim_end().strip_suffix("```").unwrap_or(inner).trim()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_final_answer() {
-        let parser = ActionParser::new(vec![]);
+        let parser = DefaultTextParser::new(vec![]);
         let action = parser.parse("FINAL ANSWER: The result is 42.");
         match action {
             ReActAction::FinalAnswer(answer) => assert!(answer.contains("42")),
@@ -150,7 +348,7 @@ mod tests {
 
     #[test]
     fn test_parse_text_tool_call() {
-        let parser = ActionParser::new(vec![]);
+        let parser = DefaultTextParser::new(vec![]);
         let action = parser.parse("THOUGHT: I need to search.\nACTION: search\nARGS: {\"query\": \"rust\"}");
         match action {
             ReActAction::ToolCall { name, args } => {
@@ -161,9 +359,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_multiline_args() {
+        let parser = DefaultTextParser::new(vec![]);
+        let response = "THOUGHT: I need to search.
+ACTION: search
+ARGS: {
+  \"query\": \"rust\",
+  \"filters\": {
+    \"year\": 2024,
+    \"tags\": [\"async\", \"tokio\"]
+  }
+}
+
+OBSERVATION: pending";
+        match parser.parse(response) {
+            ReActAction::ToolCall { name, args } => {
+                assert_eq!(name, "search");
+                assert_eq!(args["query"], "rust");
+                assert_eq!(args["filters"]["year"], 2024);
+                assert_eq!(args["filters"]["tags"][1], "tokio");
+            }
+            other => panic!("Expected ToolCall, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_fenced_args() {
+        let parser = DefaultTextParser::new(vec![]);
+        for response in [
+            "ACTION: search\nARGS:\n```json\n{\n  \"query\": \"rust\"\n}\n```\nTHOUGHT: done",
+            "ACTION: search\nARGS: ```json\n{\"query\": \"rust\"}\n```",
+            "ACTION: search\nARGS: ```{\"query\": \"rust\"}```",
+        ] {
+            match parser.parse(response) {
+                ReActAction::ToolCall { name, args } => {
+                    assert_eq!(name, "search");
+                    assert_eq!(args["query"], "rust", "response: {}", response);
+                }
+                other => panic!("Expected ToolCall, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_args_falls_back_to_empty() {
+        let parser = DefaultTextParser::new(vec![]);
+        match parser.parse("ACTION: search\nARGS: {\"query\": ") {
+            ReActAction::ToolCall { name, args } => {
+                assert_eq!(name, "search");
+                assert_eq!(args, serde_json::json!({}));
+            }
+            other => panic!("Expected ToolCall, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_multiple_actions() {
+        let parser = DefaultTextParser::new(vec![]);
+        let response = "THOUGHT: Two lookups\nACTION: search\nARGS: {\"q\": \"a\"}\nACTION: lookup\nARGS: {\"id\": 1}";
+        match parser.parse(response) {
+            ReActAction::ParallelToolCalls(calls) => {
+                assert_eq!(calls.len(), 2);
+                assert_eq!(calls[0].0, "search");
+                assert_eq!(calls[0].1["q"], "a");
+                assert_eq!(calls[1].0, "lookup");
+                assert_eq!(calls[1].1["id"], 1);
+            }
+            other => panic!("Expected ParallelToolCalls, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_extract_native_tool_call() {
+        let parser = DefaultTextParser::new(vec![]);
+        let encoded = serde_json::json!({"function": {"name": "search", "arguments": "{\"q\": \"rust\"}"}});
+        let object = serde_json::json!({"function": {"name": "search", "arguments": {"q": "rust"}}});
+        for call in [encoded, object] {
+            match parser.extract_tool_call(&call) {
+                Some(ReActAction::ToolCall { name, args }) => {
+                    assert_eq!(name, "search");
+                    assert_eq!(args["q"], "rust");
+                }
+                other => panic!("Expected ToolCall, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_json_function_call() {
-        let parser = ActionParser::new(vec![]);
+        let parser = DefaultTextParser::new(vec![]);
         let action = parser.parse(r#"{"name": "calculator", "arguments": {"a": 5, "b": 3}}"#);
         match action {
             ReActAction::ToolCall { name, args } => {
@@ -176,11 +461,81 @@ mod tests {
 
     #[test]
     fn test_parse_think() {
-        let parser = ActionParser::new(vec![]);
+        let parser = DefaultTextParser::new(vec![]);
         let action = parser.parse("I'm still thinking about this problem...");
         match action {
             ReActAction::Think(thought) => assert!(thought.contains("thinking")),
             _ => panic!("Expected Think"),
         }
     }
+
+    #[test]
+    fn test_json_parser() {
+        let parser = JsonActionParser::new();
+
+        match parser.parse(r#"{"action": "search", "args": {"query": "rust"}}"#) {
+            ReActAction::ToolCall { name, args } => {
+                assert_eq!(name, "search");
+                assert_eq!(args["query"], "rust");
+            }
+            other => panic!("Expected ToolCall, got {:?}", other),
+        }
+
+        match parser.parse("```json\n{\"action\": \"final_answer\", \"args\": {\"answer\": \"42\"}}\n```") {
+            ReActAction::FinalAnswer(answer) => assert_eq!(answer, "42"),
+            other => panic!("Expected FinalAnswer, got {:?}", other),
+        }
+
+        assert!(matches!(
+            parser.parse(r#"{"action": "delegate", "args": {"objective": "Research"}}"#),
+            ReActAction::Delegate { ref objective, .. } if objective == "Research"
+        ));
+        // The text format is not understood
+        assert!(matches!(parser.parse("FINAL ANSWER: 42"), ReActAction::Think(_)));
+    }
+
+    #[test]
+    fn test_json_parser_typed_actions() {
+        let parser = JsonActionParser::new();
+
+        match parser.parse(r#"I'll search now. {"type": "tool_call", "name": "search", "args": {"q": "a {b} \"c\""}} Done."#) {
+            ReActAction::ToolCall { name, args } => {
+                assert_eq!(name, "search");
+                assert_eq!(args["q"], "a {b} \"c\"");
+            }
+            other => panic!("Expected ToolCall, got {:?}", other),
+        }
+
+        match parser.parse(r#"{"type": "final", "answer": "42"}"#) {
+            ReActAction::FinalAnswer(answer) => assert_eq!(answer, "42"),
+            other => panic!("Expected FinalAnswer, got {:?}", other),
+        }
+
+        match parser.parse("Handing off:\n```json\n{\"type\": \"delegate\", \"objective\": \"Research\", \"context\": \"EU only\"}\n```") {
+            ReActAction::Delegate { objective, context } => {
+                assert_eq!(objective, "Research");
+                assert_eq!(context, "EU only");
+            }
+            other => panic!("Expected Delegate, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_json_parser_malformed_falls_back_to_think() {
+        let parser = JsonActionParser::new();
+
+        // Unbalanced, invalid, unknown type, and missing required field
+        for response in [
+            r#"{"type": "final", "answer": "42""#,
+            r#"{"type": "final", answer: 42}"#,
+            r#"{"type": "dance"}"#,
+            r#"{"type": "tool_call", "args": {}}"#,
+            "no json here",
+        ] {
+            match parser.parse(response) {
+                ReActAction::Think(thought) => assert_eq!(thought, response),
+                other => panic!("Expected Think for {:?}, got {:?}", response, other),
+            }
+        }
+    }
 }
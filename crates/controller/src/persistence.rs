@@ -1,11 +1,13 @@
 //! Session persistence for crash recovery.
 
 use dashmap::DashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use multi_agent_core::{
     traits::SessionStore,
-    types::Session,
-    Result,
+    types::{Session, SessionStatus},
+    Error, Result,
 };
 
 /// In-memory session store.
@@ -56,33 +58,259 @@ impl SessionStore for InMemorySessionStore {
         Ok(())
     }
 
-    async fn list_running(&self) -> Result<Vec<String>> {
-        use multi_agent_core::types::SessionStatus;
-        
+    async fn list_by_status(&self, status: SessionStatus) -> Result<Vec<String>> {
         Ok(self
             .sessions
             .iter()
-            .filter(|r| r.status == SessionStatus::Running)
+            .filter(|r| r.status == status)
             .map(|r| r.key().clone())
             .collect())
     }
 }
 
+/// Session store that keeps each session as a JSON file named after its ID.
+pub struct FileSessionStore {
+    dir: PathBuf,
+    /// Per-session locks serializing concurrent writes to the same file.
+    locks: DashMap<String, Arc<tokio::sync::Mutex<()>>>,
+}
+
+impl FileSessionStore {
+    /// Create a store under `dir`, creating the directory if missing.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            Error::StatePersistence(format!("Failed to create session dir {}: {}", dir.display(), e))
+        })?;
+        Ok(Self {
+            dir,
+            locks: DashMap::new(),
+        })
+    }
+
+    /// Get the directory sessions are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, session_id: &str) -> Result<PathBuf> {
+        if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.starts_with('.') {
+            return Err(Error::StatePersistence(format!("Invalid session ID: {:?}", session_id)));
+        }
+        Ok(self.dir.join(format!("{}.json", session_id)))
+    }
+
+    fn lock(&self, session_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.locks.entry(session_id.to_string()).or_default().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for FileSessionStore {
+    async fn save(&self, session: &Session) -> Result<()> {
+        let path = self.path(&session.id)?;
+        let data = serde_json::to_vec_pretty(session)?;
+
+        let lock = self.lock(&session.id);
+        let _guard = lock.lock().await;
+        // Write to a temp file and rename so readers never see a partial session
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, data).await.map_err(|e| {
+            Error::StatePersistence(format!("Failed to write session {}: {}", session.id, e))
+        })?;
+        tokio::fs::rename(&tmp, &path).await.map_err(|e| {
+            Error::StatePersistence(format!("Failed to write session {}: {}", session.id, e))
+        })?;
+
+        tracing::debug!(session_id = %session.id, "Session saved");
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &str) -> Result<Option<Session>> {
+        let path = self.path(session_id)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::StatePersistence(format!("Failed to read session {}: {}", session_id, e))),
+        }
+    }
+
+    async fn delete(&self, session_id: &str) -> Result<()> {
+        let path = self.path(session_id)?;
+        let lock = self.lock(session_id);
+        let guard = lock.lock().await;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(Error::StatePersistence(format!("Failed to delete session {}: {}", session_id, e)))
+            }
+        }
+        drop(guard);
+        self.locks.remove(session_id);
+
+        tracing::debug!(session_id = %session_id, "Session deleted");
+        Ok(())
+    }
+
+    async fn list_by_status(&self, status: SessionStatus) -> Result<Vec<String>> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .map_err(|e| Error::StatePersistence(format!("Failed to list sessions: {}", e)))?;
+
+        let mut ids = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| Error::StatePersistence(format!("Failed to list sessions: {}", e)))?
+        {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match self.load(session_id).await {
+                Ok(Some(session)) if session.status == status => ids.push(session.id),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(path = %path.display(), error = %e, "Skipping unreadable session file"),
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+/// SQLite-backed session store.
+///
+/// Sessions live in a `sessions` table with `id`, `status` and
+/// `updated_at` columns plus the full session as JSON. `status` is
+/// indexed for crash recovery queries.
+#[cfg(feature = "sqlite")]
+pub struct SqliteSessionStore {
+    pool: sqlx::SqlitePool,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSessionStore {
+    /// Connect to a database URL (e.g. `sqlite://sessions.db` or `sqlite::memory:`), creating it if missing.
+    pub async fn connect(url: &str) -> Result<Self> {
+        use std::str::FromStr;
+
+        let options = sqlx::sqlite::SqliteConnectOptions::from_str(url)
+            .map_err(sqlite_error)?
+            .create_if_missing(true);
+        // An in-memory database only exists on a single connection
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(if url.contains(":memory:") { 1 } else { 5 })
+            .connect_with(options)
+            .await
+            .map_err(sqlite_error)?;
+        Self::with_pool(pool).await
+    }
+
+    /// Use an existing pool, creating the schema if missing.
+    pub async fn with_pool(pool: sqlx::SqlitePool) -> Result<Self> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                id TEXT PRIMARY KEY NOT NULL,
+                status TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                data TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .map_err(sqlite_error)?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions (status)")
+            .execute(&pool)
+            .await
+            .map_err(sqlite_error)?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn status_key(status: SessionStatus) -> Result<String> {
+    match serde_json::to_value(status)? {
+        serde_json::Value::String(key) => Ok(key),
+        other => Err(Error::StatePersistence(format!("Unexpected status encoding: {}", other))),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_error(e: sqlx::Error) -> Error {
+    Error::StatePersistence(format!("SQLite error: {}", e))
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait::async_trait]
+impl SessionStore for SqliteSessionStore {
+    async fn save(&self, session: &Session) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO sessions (id, status, updated_at, data) VALUES (?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status,
+                updated_at = excluded.updated_at,
+                data = excluded.data",
+        )
+        .bind(&session.id)
+        .bind(status_key(session.status)?)
+        .bind(session.updated_at)
+        .bind(serde_json::to_string(session)?)
+        .execute(&self.pool)
+        .await
+        .map_err(sqlite_error)?;
+
+        tracing::debug!(session_id = %session.id, "Session saved");
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &str) -> Result<Option<Session>> {
+        let data: Option<String> = sqlx::query_scalar("SELECT data FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(sqlite_error)?;
+        Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+    }
+
+    async fn delete(&self, session_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await
+            .map_err(sqlite_error)?;
+
+        tracing::debug!(session_id = %session_id, "Session deleted");
+        Ok(())
+    }
+
+    /// Most recently updated sessions come first.
+    async fn list_by_status(&self, status: SessionStatus) -> Result<Vec<String>> {
+        sqlx::query_scalar("SELECT id FROM sessions WHERE status = ? ORDER BY updated_at DESC")
+            .bind(status_key(status)?)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(sqlite_error)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use multi_agent_core::types::{SessionStatus, TokenUsage};
+    use multi_agent_core::types::TokenUsage;
 
     fn create_test_session(id: &str) -> Session {
-        Session {
-            id: id.to_string(),
-            status: SessionStatus::Running,
-            history: vec![],
-            task_state: None,
-            token_usage: TokenUsage::with_budget(10000),
+        let mut session = Session {
             created_at: 0,
             updated_at: 0,
-        }
+            ..Session::new(id)
+        };
+        session.token_usage = TokenUsage::with_budget(10000);
+        session
     }
 
     #[tokio::test]
@@ -112,4 +340,126 @@ mod tests {
         assert_eq!(running_list.len(), 1);
         assert!(running_list.contains(&"s1".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_list_by_status() {
+        let store = InMemorySessionStore::new();
+        for (id, status) in [
+            ("r1", SessionStatus::Running),
+            ("f1", SessionStatus::Failed),
+            ("r2", SessionStatus::Running),
+            ("c1", SessionStatus::Cancelled),
+        ] {
+            let mut session = create_test_session(id);
+            session.status = status;
+            store.save(&session).await.unwrap();
+        }
+
+        let mut running = store.list_by_status(SessionStatus::Running).await.unwrap();
+        running.sort();
+        assert_eq!(running, vec!["r1".to_string(), "r2".to_string()]);
+        assert_eq!(store.list_by_status(SessionStatus::Failed).await.unwrap(), vec!["f1".to_string()]);
+        assert_eq!(store.list_by_status(SessionStatus::Cancelled).await.unwrap(), vec!["c1".to_string()]);
+        assert!(store.list_by_status(SessionStatus::Completed).await.unwrap().is_empty());
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("multi-agent-sessions-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_file_store_survives_restart() {
+        let dir = temp_dir();
+        let mut session = create_test_session("file-1");
+        session.history.push(multi_agent_core::types::HistoryEntry {
+            role: "user".to_string(),
+            content: "hello".to_string().into(),
+            tool_call: None,
+            timestamp: 1,
+            compressed_from: None,
+            images: Vec::new(),
+        });
+
+        {
+            let store = FileSessionStore::new(&dir).unwrap();
+            store.save(&session).await.unwrap();
+        }
+
+        let store = FileSessionStore::new(&dir).unwrap();
+        let loaded = store.load("file-1").await.unwrap().unwrap();
+        assert_eq!(loaded.id, "file-1");
+        assert_eq!(loaded.history.len(), 1);
+        assert_eq!(loaded.history[0].content.as_str(), "hello");
+        assert_eq!(store.list_running().await.unwrap(), vec!["file-1".to_string()]);
+
+        store.delete("file-1").await.unwrap();
+        assert!(store.load("file-1").await.unwrap().is_none());
+        assert!(store.load("missing").await.unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_store_concurrent_saves() {
+        let dir = temp_dir();
+        let store = Arc::new(FileSessionStore::new(&dir).unwrap());
+
+        let saves = (0..20).map(|i| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let mut session = create_test_session("shared");
+                session.updated_at = i;
+                store.save(&session).await
+            })
+        });
+        for result in futures::future::join_all(saves).await {
+            result.unwrap().unwrap();
+        }
+
+        // The file always holds one complete session
+        let loaded = store.load("shared").await.unwrap().unwrap();
+        assert!((0..20).contains(&loaded.updated_at));
+        assert!(store.load("../escape").await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_round_trip() {
+        let store = SqliteSessionStore::connect("sqlite::memory:").await.unwrap();
+        let mut session = create_test_session("sql-1");
+        session.metadata.user_id = Some("ops".to_string());
+
+        store.save(&session).await.unwrap();
+        session.updated_at = 5;
+        store.save(&session).await.unwrap();
+
+        let loaded = store.load("sql-1").await.unwrap().unwrap();
+        assert_eq!(loaded.updated_at, 5);
+        assert_eq!(loaded.metadata.user_id.as_deref(), Some("ops"));
+
+        store.delete("sql-1").await.unwrap();
+        assert!(store.load("sql-1").await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_list_by_status() {
+        let store = SqliteSessionStore::connect("sqlite::memory:").await.unwrap();
+        for (id, status, updated_at) in [
+            ("a", SessionStatus::Running, 1),
+            ("b", SessionStatus::Completed, 2),
+            ("c", SessionStatus::Running, 3),
+        ] {
+            let mut session = create_test_session(id);
+            session.status = status;
+            session.updated_at = updated_at;
+            store.save(&session).await.unwrap();
+        }
+
+        assert_eq!(store.list_running().await.unwrap(), vec!["c".to_string(), "a".to_string()]);
+        assert_eq!(store.list_by_status(SessionStatus::Completed).await.unwrap(), vec!["b".to_string()]);
+        assert!(store.list_by_status(SessionStatus::Failed).await.unwrap().is_empty());
+    }
 }
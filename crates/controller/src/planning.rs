@@ -45,8 +45,10 @@ impl PlanningCapability {
         }
     }
 
-    /// Generate a plan from the goal using the LLM.
-    async fn generate_plan(&self, goal: &str) -> Result<Vec<PlanStep>> {
+    /// Ask the LLM for a numbered plan for `goal` and parse it into steps.
+    ///
+    /// The first step is marked `InProgress`, the rest `Pending`.
+    pub async fn plan(&self, goal: &str) -> Result<Vec<PlanStep>> {
         let prompt = format!(
             "You are an expert planner. Break down the following goal into a clear, numbered list of steps.\n\
             Goal: {}\n\
@@ -60,25 +62,24 @@ impl PlanningCapability {
         let response = self.llm.complete(&prompt).await
             .map_err(|e| Error::controller(format!("Failed to generate plan: {}", e)))?;
 
-        let mut steps = Vec::new();
-        for (i, line) in response.content.lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() { continue; }
-            
-            // Simple parsing: just take the line content
-            // Remove "1. " prefix if present
-            let description = if let Some((_, rest)) = line.split_once('.') {
-                rest.trim().to_string()
-            } else {
-                line.to_string()
-            };
+        let lines: Vec<&str> = response.content.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        let numbered: Vec<String> = lines.iter().filter_map(|l| strip_step_number(l)).collect();
+        // Without numbering, treat every line as a step
+        let descriptions = if numbered.is_empty() {
+            lines.iter().map(|l| l.to_string()).collect()
+        } else {
+            numbered
+        };
 
-            steps.push(PlanStep {
+        let mut steps: Vec<PlanStep> = descriptions
+            .into_iter()
+            .enumerate()
+            .map(|(i, description)| PlanStep {
                 id: i + 1,
                 description,
                 status: StepStatus::Pending,
-            });
-        }
+            })
+            .collect();
 
         if steps.is_empty() {
              // Fallback if parsing fails or LLM is weird
@@ -97,7 +98,7 @@ impl PlanningCapability {
         Ok(steps)
     }
 
-    fn format_plan(steps: &[PlanStep]) -> String {
+    pub(crate) fn format_plan(steps: &[PlanStep]) -> String {
         let mut out = String::from("Current Plan:\n");
         for step in steps {
             let mark = match step.status {
@@ -112,6 +113,16 @@ impl PlanningCapability {
     }
 }
 
+/// Strip a `1.` or `1)` prefix, returning the step text of a numbered line.
+fn strip_step_number(line: &str) -> Option<String> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let rest = line[digits..].strip_prefix(['.', ')'])?.trim();
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
 #[async_trait]
 impl AgentCapability for PlanningCapability {
     fn name(&self) -> &str {
@@ -125,8 +136,20 @@ impl AgentCapability for PlanningCapability {
             return Ok(());
         };
 
+        // The controller already planned (`ReActConfig::enable_planning`) and put the plan in the prompt
+        let seeded: Vec<PlanStep> = session
+            .task_state
+            .iter()
+            .flat_map(|state| &state.pending_actions)
+            .filter_map(|action| serde_json::from_value(action.clone()).ok())
+            .collect();
+        if !seeded.is_empty() {
+            *self.plan.lock().await = Some(seeded);
+            return Ok(());
+        }
+
         tracing::info!("Generating plan for goal: {}", goal);
-        let steps = self.generate_plan(goal).await?;
+        let steps = self.plan(goal).await?;
         
         let plan_str = Self::format_plan(&steps);
         tracing::info!("Generated Plan:\n{}", plan_str);
@@ -140,6 +163,8 @@ impl AgentCapability for PlanningCapability {
             content: Arc::new(format!("I have generated a plan for your goal. Follow this plan:\n\n{}", plan_str)),
             tool_call: None,
             timestamp: chrono::Utc::now().timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
         });
 
         Ok(())
@@ -168,6 +193,8 @@ impl AgentCapability for PlanningCapability {
                      content: Arc::new(reminder),
                      tool_call: None,
                      timestamp: chrono::Utc::now().timestamp(),
+                     compressed_from: None,
+                     images: Vec::new(),
                  });
              }
         }
@@ -178,3 +205,29 @@ impl AgentCapability for PlanningCapability {
     // For now, we rely on the LLM to follow the plan implicitly, 
     // or we can add a tool `complete_step(id)`?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multi_agent_core::mocks::MockLlm;
+
+    #[tokio::test]
+    async fn test_plan_parses_numbered_steps() {
+        let llm = Arc::new(MockLlm::constant("Here is the plan:\n1. Gather data\n2) Analyze it\n\n3. Report findings"));
+        let steps = PlanningCapability::new(llm).plan("Analyze sales").await.unwrap();
+
+        let descriptions: Vec<&str> = steps.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(descriptions, ["Gather data", "Analyze it", "Report findings"]);
+        assert_eq!(steps[0].status, StepStatus::InProgress);
+        assert_eq!(steps[2].id, 3);
+        assert_eq!(steps[2].status, StepStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_plan_falls_back_to_lines() {
+        let llm = Arc::new(MockLlm::constant("Gather data\nReport findings"));
+        let steps = PlanningCapability::new(llm).plan("Analyze sales").await.unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].description, "Report findings");
+    }
+}
@@ -0,0 +1,197 @@
+//! Concurrency-limited controller pool.
+//!
+//! Bounds the number of sessions a shared `ReActController` runs at once so
+//! that many concurrent tasks cannot overload the LLM provider.
+
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use multi_agent_core::{
+    traits::Controller,
+    types::{AgentResult, UserIntent},
+    Error, Result,
+};
+
+use crate::react::ReActController;
+
+/// Controller wrapper that limits concurrent sessions with a semaphore.
+pub struct PooledController {
+    inner: Arc<ReActController>,
+    semaphore: Arc<Semaphore>,
+    /// Callers currently waiting for a permit.
+    waiting: AtomicU64,
+}
+
+/// Decrements the waiting count when dropped, including on timeout.
+struct WaitGuard<'a>(&'a AtomicU64);
+
+impl Drop for WaitGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl PooledController {
+    /// Wrap a controller, allowing `max_concurrent_sessions` (from its config) at once.
+    pub fn new(inner: Arc<ReActController>) -> Self {
+        let permits = inner.config.max_concurrent_sessions.max(1);
+        Self {
+            inner,
+            semaphore: Arc::new(Semaphore::new(permits)),
+            waiting: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the number of callers waiting for a permit.
+    pub fn waiting_count(&self) -> u64 {
+        self.waiting.load(Ordering::SeqCst)
+    }
+
+    /// Get the number of sessions that can start without waiting.
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// Execute an intent, failing with `Error::PoolTimeout` if no permit is acquired within `timeout`.
+    pub async fn execute_with_timeout(&self, intent: UserIntent, timeout: Duration) -> Result<AgentResult> {
+        let _permit = tokio::time::timeout(timeout, self.acquire())
+            .await
+            .map_err(|_| Error::PoolTimeout)??;
+        self.inner.execute(intent).await
+    }
+
+    async fn acquire(&self) -> Result<SemaphorePermit<'_>> {
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        let _guard = WaitGuard(&self.waiting);
+        self.semaphore
+            .acquire()
+            .await
+            .map_err(|e| Error::controller(format!("Controller pool closed: {}", e)))
+    }
+}
+
+#[async_trait]
+impl Controller for PooledController {
+    async fn execute(&self, intent: UserIntent) -> Result<AgentResult> {
+        let _permit = self.acquire().await?;
+        self.inner.execute(intent).await
+    }
+
+    async fn resume(&self, session_id: &str) -> Result<AgentResult> {
+        let _permit = self.acquire().await?;
+        self.inner.resume(session_id).await
+    }
+
+    async fn cancel(&self, session_id: &str) -> Result<()> {
+        // Cancellation must never queue behind running sessions
+        self.inner.cancel(session_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::react::ReActConfig;
+    use multi_agent_core::traits::{ChatMessage, LlmClient, LlmResponse, LlmUsage};
+    use std::sync::atomic::AtomicUsize;
+
+    /// Answers after a delay while tracking peak concurrency.
+    #[derive(Default)]
+    struct SlowLlm {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmClient for SlowLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(LlmResponse {
+                content: "FINAL ANSWER: done".to_string(),
+                finish_reason: "stop".to_string(),
+                usage: LlmUsage::default(),
+                tool_calls: None,
+                reasoning: None,
+            })
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    fn pool(llm: Arc<SlowLlm>, max_concurrent_sessions: usize) -> Arc<PooledController> {
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_concurrent_sessions,
+                persist_state: false,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .build().unwrap();
+        Arc::new(PooledController::new(Arc::new(controller)))
+    }
+
+    fn mission() -> UserIntent {
+        UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limits_concurrent_sessions() {
+        let llm = Arc::new(SlowLlm::default());
+        let pool = pool(llm.clone(), 2);
+
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move { pool.execute(mission()).await })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(pool.waiting_count(), 4);
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+        assert_eq!(llm.peak.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.waiting_count(), 0);
+        assert_eq!(pool.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_timeout() {
+        let pool = pool(Arc::new(SlowLlm::default()), 1);
+
+        let busy = {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.execute(mission()).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let result = pool.execute_with_timeout(mission(), Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(Error::PoolTimeout)));
+        assert_eq!(pool.waiting_count(), 0);
+
+        busy.await.unwrap().unwrap();
+        let result = pool.execute_with_timeout(mission(), Duration::from_secs(1)).await;
+        assert!(matches!(result, Ok(AgentResult::Text(_))));
+    }
+}
@@ -0,0 +1,141 @@
+//! Rate limiting for LLM calls.
+//!
+//! Prevents a single agent session from consuming the entire provider rate
+//! limit in shared deployments. The controller acquires capacity from the
+//! configured limiter before every LLM call.
+
+use async_trait::async_trait;
+use governor::{DefaultDirectRateLimiter, Quota};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use multi_agent_core::{Error, Result};
+
+/// Limiter for outgoing LLM calls.
+#[async_trait]
+pub trait RateLimiter: Send + Sync {
+    /// Wait until `tokens` units of capacity are available, then consume them.
+    async fn acquire(&self, tokens: u32) -> Result<()>;
+}
+
+/// Token bucket rate limiter backed by `governor`.
+pub struct TokenBucketRateLimiter {
+    /// Maximum burst size.
+    capacity: u32,
+    /// Tokens refilled per second.
+    refill_rate: u32,
+    limiter: DefaultDirectRateLimiter,
+}
+
+impl TokenBucketRateLimiter {
+    /// Create a new token bucket with the given burst capacity and refill rate (per second).
+    ///
+    /// Zero values are clamped to 1.
+    pub fn new(capacity: u32, refill_rate: u32) -> Self {
+        let burst = NonZeroU32::new(capacity).unwrap_or(NonZeroU32::MIN);
+        let rate = NonZeroU32::new(refill_rate).unwrap_or(NonZeroU32::MIN);
+        let quota = Quota::per_second(rate).allow_burst(burst);
+
+        Self {
+            capacity: burst.get(),
+            refill_rate: rate.get(),
+            limiter: governor::RateLimiter::direct(quota),
+        }
+    }
+
+    /// Get the burst capacity.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Get the refill rate (tokens per second).
+    pub fn refill_rate(&self) -> u32 {
+        self.refill_rate
+    }
+}
+
+#[async_trait]
+impl RateLimiter for TokenBucketRateLimiter {
+    async fn acquire(&self, tokens: u32) -> Result<()> {
+        let Some(n) = NonZeroU32::new(tokens) else {
+            return Ok(());
+        };
+
+        self.limiter.until_n_ready(n).await.map_err(|e| {
+            Error::controller(format!(
+                "Rate limiter cannot grant {} tokens (capacity {}): {}",
+                tokens, self.capacity, e
+            ))
+        })
+    }
+}
+
+type SharedLimiters = Mutex<HashMap<(u32, u32), Arc<TokenBucketRateLimiter>>>;
+
+/// Process-wide rate limiters shared across controller instances.
+pub struct GlobalRateLimiter;
+
+impl GlobalRateLimiter {
+    /// Get the shared limiter for the given capacity and rate.
+    ///
+    /// Every call with the same `(capacity, rate)` returns the same underlying
+    /// bucket, so multiple controllers configured this way share one budget.
+    pub fn shared(capacity: u32, rate: u32) -> Arc<dyn RateLimiter> {
+        static LIMITERS: OnceLock<SharedLimiters> = OnceLock::new();
+
+        let mut limiters = LIMITERS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        limiters
+            .entry((capacity, rate))
+            .or_insert_with(|| Arc::new(TokenBucketRateLimiter::new(capacity, rate)))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_burst_is_immediate() {
+        let limiter = TokenBucketRateLimiter::new(5, 1);
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire(1).await.unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_blocks_when_exhausted() {
+        let limiter = TokenBucketRateLimiter::new(1, 20);
+
+        limiter.acquire(1).await.unwrap();
+        let start = Instant::now();
+        limiter.acquire(1).await.unwrap();
+        // 20 tokens/sec refills one token every 50ms
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[tokio::test]
+    async fn test_over_capacity_is_error() {
+        let limiter = TokenBucketRateLimiter::new(2, 1);
+        assert!(limiter.acquire(3).await.is_err());
+    }
+
+    #[test]
+    fn test_global_limiter_is_shared() {
+        let a = GlobalRateLimiter::shared(7, 3);
+        let b = GlobalRateLimiter::shared(7, 3);
+        let c = GlobalRateLimiter::shared(8, 3);
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
+}
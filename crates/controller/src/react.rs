@@ -12,16 +12,37 @@
 //! - Subagent Delegation (allows spawning child agents for subtasks)
 
 use async_trait::async_trait;
-use std::sync::Arc;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 use uuid::Uuid;
 
 use multi_agent_core::{
-    traits::{ChatMessage, Controller, LlmClient, LlmResponse, ToolRegistry, SessionStore},
-    types::{AgentResult, HistoryEntry, Session, SessionStatus, TaskState, TokenUsage, UserIntent, ToolCallInfo},
+    traits::{ArtifactStore, ChatMessage, Controller, LlmClient, LlmResponse, Redactor, ToolRegistry, SessionStore},
+    types::{
+        AgentResult, AgentResultWithExplanation, HistoryEntry, ModelPricing, ModelTier, RefId, Session, SessionStatus, TaskState, TokenUsage,
+        TokenUsageExplanation, UserIntent, ToolCallInfo, ToolDefinition, ToolOutput, REASONING_ROLE,
+    },
     Error, Result,
 };
 
-use crate::capability::AgentCapability;
+use crate::approval::{ApprovalDecision, ApprovalHook};
+use crate::capability::{AgentCapability, PolicyDecision, ReflectionCapability, ReflectionOutcome};
+use crate::context::{CompressionConfig, ContextCompressor, TruncationCompressor};
+use crate::delegation::{DelegationRequest, Delegator};
+use crate::memory::{MemoryCapability, PRIOR_KNOWLEDGE_HEADER};
+use crate::planning::PlanningCapability;
+use crate::rate_limit::RateLimiter;
+use crate::schedule::{next_after, parse_cron, ScheduleEntry, ScheduleStore};
+use crate::stop::StopCondition;
+use crate::tier::{HeuristicTierSelector, TierSelector};
+use multi_agent_governance::{ContentModerator, ModerationDirection};
 
 // v0.3: Security Integration
 // (Guardrail unused in pure Controller struct if verified via capabilities)
@@ -38,6 +59,88 @@ pub struct ReActConfig {
     pub persist_state: bool,
     /// Temperature for LLM calls.
     pub temperature: f32,
+    /// Maximum tool calls (including delegations) across all iterations.
+    pub max_total_tool_calls: usize,
+    /// Preload tool backends when the controller is built.
+    pub preload_tools: bool,
+    /// Verify final answers against the original goal with a secondary LLM call.
+    pub instruction_following_check: bool,
+    /// Calls to a single tool before the agent is nudged towards another approach.
+    pub max_single_tool_frequency: u32,
+    /// Preceding tool calls checked for an identical call (same tool and args); 0 disables.
+    pub repeat_detection_window: usize,
+    /// Maximum sessions run concurrently through a `PooledController`.
+    pub max_concurrent_sessions: usize,
+    /// Tool outputs longer than this (in chars) are summarized before entering history.
+    pub observation_compression_threshold: usize,
+    /// Retries for a tool call that returns an error; 0 disables (unknown tools are never retried).
+    pub max_tool_retries: u32,
+    /// Cap on completion tokens per LLM call, also checked against the remaining budget.
+    pub max_tokens_per_iteration: Option<u64>,
+    /// Delegation depth of this agent (0 = top level, subagents run deeper).
+    pub delegation_depth: usize,
+    /// Prefer the LLM's native tool calls over parsing ACTION/ARGS text.
+    pub use_native_tool_calls: bool,
+    /// Retries for an LLM call failing with a transient error.
+    pub max_retries: u32,
+    /// Delay before the first LLM retry, doubled for each further attempt.
+    pub retry_base_delay_ms: u64,
+    /// Memories recalled into the system prompt of a new mission (0 = disabled).
+    pub memory_recall_top_k: usize,
+    /// Ask the LLM for a plan before the loop and seed `TaskState::pending_actions` with it.
+    pub enable_planning: bool,
+    /// Wall-clock limit for a whole mission (None = unlimited).
+    pub mission_timeout: Option<Duration>,
+    /// Wall-clock limit for a single tool execution (None = unlimited).
+    pub tool_timeout: Option<Duration>,
+    /// Token prices used to log the estimated cost of each mission.
+    pub pricing: Option<ModelPricing>,
+    /// Tier whose prices apply to the controller's LLM.
+    pub pricing_tier: ModelTier,
+    /// Simulate tool calls and delegations instead of running them.
+    pub dry_run: bool,
+    /// Tools that only run once the approval hook approves the call.
+    pub requires_approval: HashSet<String>,
+    /// At `max_iterations`, ask the LLM for a best-effort answer instead of failing.
+    pub answer_on_max_iterations: bool,
+    /// Tools the agent may call; when set, other tools are hidden and refused.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Custom system prompt; `{goal}` and `{tools}` are replaced at session creation.
+    pub system_prompt_template: Option<String>,
+    /// Have `ReflectionCapability` review each final answer before returning it.
+    pub enable_reflection: bool,
+    /// Final answers reflection may reject per mission before one is accepted as is.
+    pub max_reflections: usize,
+    /// When the token budget runs out, return the observations collected so far
+    /// instead of `Error::BudgetExceeded` (session status `BudgetExhausted`).
+    pub graceful_budget_exhaustion: bool,
+    /// Tool outputs longer than this (in bytes) are saved to the artifact store and
+    /// replaced by a reference (None = always inline).
+    pub max_inline_tool_output: Option<usize>,
+    /// After `implicit_final_answer_thoughts` substantial thoughts in a row, ask the
+    /// model whether it is done and finalize the last thought if it says yes.
+    pub detect_implicit_final_answer: bool,
+    /// Consecutive substantial thoughts that trigger the implicit final answer check.
+    pub implicit_final_answer_thoughts: usize,
+    /// Characters kept from the start of a long tool observation (None = no truncation
+    /// unless `observation_tail` is set).
+    pub observation_head: Option<usize>,
+    /// Characters kept from the end of a long tool observation.
+    pub observation_tail: Option<usize>,
+    /// Prune the persisted history to this many entries after each iteration,
+    /// keeping the system prompt and the most recent entries (None = unbounded).
+    pub max_history_entries: Option<usize>,
+    /// Before the loop starts, plan the goal and delegate each step to a subagent
+    /// concurrently; the loop then synthesizes their results. Requires a delegator.
+    pub auto_decompose: bool,
+    /// Subagents a delegation fan-out runs at once (at least 1).
+    pub max_concurrent_delegations: usize,
+    /// Hard cap on the estimated tokens of the messages sent to the LLM; older
+    /// messages are truncated to fit, whatever the remaining budget (None = no cap).
+    pub max_history_tokens: Option<usize>,
+    /// Empty (or whitespace-only) LLM responses in a row that are re-prompted
+    /// before the mission fails.
+    pub max_empty_responses: usize,
 }
 
 impl Default for ReActConfig {
@@ -47,12 +150,233 @@ impl Default for ReActConfig {
             default_budget: 50_000,
             persist_state: true,
             temperature: 0.7,
+            max_total_tool_calls: 50,
+            preload_tools: false,
+            instruction_following_check: false,
+            max_single_tool_frequency: 5,
+            repeat_detection_window: 1,
+            max_concurrent_sessions: 10,
+            observation_compression_threshold: 2000,
+            max_tool_retries: 0,
+            max_tokens_per_iteration: None,
+            delegation_depth: 0,
+            use_native_tool_calls: false,
+            max_retries: 2,
+            retry_base_delay_ms: 200,
+            memory_recall_top_k: 3,
+            enable_planning: false,
+            mission_timeout: None,
+            tool_timeout: None,
+            pricing: None,
+            pricing_tier: ModelTier::Standard,
+            dry_run: false,
+            answer_on_max_iterations: false,
+            requires_approval: HashSet::new(),
+            allowed_tools: None,
+            system_prompt_template: None,
+            enable_reflection: false,
+            max_reflections: 2,
+            graceful_budget_exhaustion: false,
+            max_inline_tool_output: None,
+            detect_implicit_final_answer: false,
+            implicit_final_answer_thoughts: 3,
+            observation_head: None,
+            observation_tail: None,
+            max_history_entries: None,
+            auto_decompose: false,
+            max_concurrent_delegations: 4,
+            max_history_tokens: None,
+            max_empty_responses: 2,
         }
     }
 }
 
 // Use the new parser module
-use crate::parser::ReActAction;
+use crate::parser::{ActionParser, DefaultTextParser, ReActAction};
+
+/// Smallest completion worth requesting when enforcing `max_tokens_per_iteration`.
+const MIN_COMPLETION_TOKENS: u64 = 16;
+
+/// Message injected once the tool call limit is reached.
+const MAX_TOOL_CALLS_MESSAGE: &str =
+    "Maximum tool calls reached. Provide your best FINAL ANSWER with the information gathered so far.";
+
+/// Message injected after an empty LLM response.
+const EMPTY_RESPONSE_MESSAGE: &str =
+    "You returned nothing; please respond with THOUGHT, ACTION, or FINAL ANSWER.";
+
+/// Characters of an offloaded tool output kept inline as a preview.
+const OFFLOADED_OUTPUT_PREVIEW_CHARS: usize = 200;
+
+/// Characters a thought needs to count towards the implicit final answer check.
+const SUBSTANTIAL_THOUGHT_CHARS: usize = 80;
+
+/// Hash identifying a tool call by name and arguments.
+fn tool_call_hash(name: &str, args: &serde_json::Value) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    name.hash(&mut hasher);
+    // Object keys serialize in sorted order, so equal arguments hash equally
+    args.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Replace all but the first `head` and last `tail` characters with a marker.
+fn elide_middle(text: String, head: usize, tail: usize) -> String {
+    let total = text.chars().count();
+    if total <= head + tail {
+        return text;
+    }
+    let head_end = text.char_indices().nth(head).map_or(text.len(), |(i, _)| i);
+    let tail_start = text.char_indices().nth(total - tail).map_or(text.len(), |(i, _)| i);
+    format!(
+        "{}\n... [truncated {} chars] ...\n{}",
+        &text[..head_end],
+        total - head - tail,
+        &text[tail_start..]
+    )
+}
+
+/// Outcome of a tool call made by the loop.
+struct ToolRun {
+    /// Observation recorded in the history.
+    observation: String,
+    retry_count: u32,
+    /// Tool output, if the tool ran without raising an error.
+    output: Option<ToolOutput>,
+}
+
+impl ToolRun {
+    /// A call that was not executed.
+    fn skipped(observation: String) -> Self {
+        Self {
+            observation,
+            retry_count: 0,
+            output: None,
+        }
+    }
+}
+
+/// Tool call parsed but not executed yet, checkpointed in `TaskState::pending_actions`.
+///
+/// Plan steps share that list; entries that do not parse as a call are left alone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PendingToolCall {
+    tool: String,
+    args: serde_json::Value,
+}
+
+impl PendingToolCall {
+    fn parse(action: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(action.clone()).ok()
+    }
+}
+
+/// Summarize a JSON schema's properties as ` (args: name: type, ...)`, marking required ones.
+fn parameter_summary(schema: &serde_json::Value) -> String {
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return String::new();
+    };
+    if properties.is_empty() {
+        return String::new();
+    }
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let args: Vec<String> = properties
+        .iter()
+        .map(|(name, property)| {
+            let ty = property.get("type").and_then(|t| t.as_str()).unwrap_or("any");
+            if required.contains(&name.as_str()) {
+                format!("{}: {} (required)", name, ty)
+            } else {
+                format!("{}: {}", name, ty)
+            }
+        })
+        .collect();
+    format!(" (args: {})", args.join(", "))
+}
+
+/// Prompt for the final summary call made with `answer_on_max_iterations`.
+const MAX_ITERATIONS_MESSAGE: &str =
+    "Maximum iterations reached. Do not call any more tools. Summarize your best answer \
+     to the task based on the observations so far.";
+
+/// Progress event emitted by `ReActController::execute_streaming`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ReActEvent {
+    /// Agent reasoning (a thought or the model's reasoning trace).
+    Thought(String),
+    /// A tool call is about to run.
+    ToolCallStarted {
+        name: String,
+        args: serde_json::Value,
+    },
+    /// Output chunk of a streaming tool, before its full observation is recorded.
+    ToolOutputChunk {
+        name: String,
+        chunk: String,
+    },
+    /// Observation produced by a tool call.
+    ToolResult {
+        name: String,
+        output: String,
+    },
+    /// The task was delegated to a subagent.
+    Delegation {
+        objective: String,
+        context: String,
+    },
+    /// Estimated prompt tokens of the LLM call about to be made.
+    TokenEstimate(u64),
+    /// Execution finished with this result.
+    FinalAnswer(AgentResult),
+    /// Execution failed.
+    Error(String),
+}
+
+/// Execution counters for a single tool, see `ReActController::tool_metrics`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolMetrics {
+    /// Number of executions.
+    pub calls: u64,
+    /// Executions that returned an error (including timeouts) or an unsuccessful output.
+    pub failures: u64,
+    /// Total time spent executing the tool.
+    pub total_duration: Duration,
+}
+
+/// Per-run state carried across ReAct iterations.
+#[derive(Debug, Default)]
+struct LoopState {
+    /// Tool calls (including delegations) executed so far.
+    total_tool_calls: usize,
+    /// Whether the tool call limit message has been injected.
+    tool_limit_reached: bool,
+    /// Final answers rejected by reflection so far.
+    reflections: usize,
+    /// Substantial thoughts in a row since the last other action.
+    consecutive_thoughts: usize,
+    /// Empty LLM responses in a row.
+    empty_responses: usize,
+    /// Sink for streamed progress events.
+    events: Option<mpsc::UnboundedSender<ReActEvent>>,
+}
+
+impl LoopState {
+    /// Emit a progress event if the run is streaming.
+    fn emit(&self, event: ReActEvent) {
+        if let Some(ref events) = self.events {
+            // The receiver may have been dropped; the run continues regardless
+            let _ = events.send(event);
+        }
+    }
+}
 
 /// ReAct controller for executing complex tasks.
 pub struct ReActController {
@@ -66,6 +390,48 @@ pub struct ReActController {
     pub(crate) session_store: Option<Arc<dyn SessionStore>>,
     /// Agent capabilities (Unification of Compression, Delegation, MCP, Security).
     pub(crate) capabilities: Vec<Arc<dyn AgentCapability>>,
+    /// Rate limiter applied before each LLM call.
+    pub(crate) rate_limiter: Option<Arc<dyn RateLimiter>>,
+    /// Content moderator for user input and final answers.
+    pub(crate) moderator: Option<Arc<dyn ContentModerator>>,
+    /// Controller used when the LLM provider is unavailable.
+    pub(crate) fallback: Option<Arc<dyn Controller>>,
+    /// Number of executions handed to the fallback controller.
+    pub(crate) fallback_invocations: AtomicU64,
+    /// Compressor used to summarize long tool outputs.
+    pub(crate) observation_compressor: Option<Arc<dyn ContextCompressor>>,
+    /// Artifact store holding full tool outputs that were summarized.
+    pub(crate) artifact_store: Option<Arc<dyn ArtifactStore>>,
+    /// Cancellation tokens of sessions with a running loop.
+    pub(crate) active_sessions: Mutex<HashMap<String, CancellationToken>>,
+    /// Long-term memory queried for prior knowledge when a mission starts.
+    pub(crate) memory: Option<Arc<MemoryCapability>>,
+    /// Execution counters keyed by tool name.
+    pub(crate) tool_metrics: Mutex<HashMap<String, ToolMetrics>>,
+    /// Tool definitions fetched at the start of a mission, listed in the system prompt.
+    pub(crate) tool_definitions: Mutex<Vec<ToolDefinition>>,
+    /// Compiled parameter schemas keyed by tool name, `None` for tools without a usable schema.
+    pub(crate) tool_validators: Mutex<HashMap<String, Option<Arc<jsonschema::Validator>>>>,
+    /// LLM clients per model tier, chosen each iteration by `tier_selector`.
+    pub(crate) tier_llms: HashMap<ModelTier, Arc<dyn LlmClient>>,
+    /// Picks the tier for each iteration when `tier_llms` is set.
+    pub(crate) tier_selector: Arc<dyn TierSelector>,
+    /// Approves calls to tools listed in `ReActConfig::requires_approval`.
+    pub(crate) approval_hook: Option<Arc<dyn ApprovalHook>>,
+    /// Masks secrets in the persisted copy of each session.
+    pub(crate) redactor: Option<Arc<dyn Redactor>>,
+    /// Conditions ending the mission after a tool call, checked in order.
+    pub(crate) stop_conditions: Vec<Arc<dyn StopCondition>>,
+    /// Reviews final answers when `ReActConfig::enable_reflection` is set.
+    pub(crate) reflection: Option<Arc<ReflectionCapability>>,
+    /// Turns LLM responses into actions.
+    pub(crate) action_parser: Arc<dyn ActionParser>,
+    /// Runs the subtasks of a decomposed goal when `ReActConfig::auto_decompose` is set.
+    pub(crate) delegator: Option<Arc<dyn Delegator>>,
+    /// Schedules registered by `UserIntent::ScheduledMission`.
+    pub(crate) schedule_store: Option<Arc<dyn ScheduleStore>>,
+    /// Set once tool preloading succeeded, shared with the background preload from `build()`.
+    pub(crate) tools_preloaded: Arc<tokio::sync::OnceCell<()>>,
 }
 
 impl ReActController {
@@ -74,6 +440,11 @@ impl ReActController {
         crate::builder::ReActBuilder::new()
     }
 
+    /// Create a controller configured entirely from `REACT_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        crate::builder::ReActBuilder::from_env()?.build()
+    }
+
     /// Create a new ReAct controller with default config (legacy support).
     pub fn new(config: ReActConfig) -> Self {
         Self {
@@ -82,36 +453,187 @@ impl ReActController {
             tools: None,
             session_store: None,
             capabilities: Vec::new(),
+            rate_limiter: None,
+            moderator: None,
+            fallback: None,
+            fallback_invocations: AtomicU64::new(0),
+            observation_compressor: None,
+            artifact_store: None,
+            active_sessions: Mutex::new(HashMap::new()),
+            memory: None,
+            tool_metrics: Mutex::new(HashMap::new()),
+            tool_definitions: Mutex::new(Vec::new()),
+            tool_validators: Mutex::new(HashMap::new()),
+            tier_llms: HashMap::new(),
+            tier_selector: Arc::new(HeuristicTierSelector::default()),
+            approval_hook: None,
+            redactor: None,
+            stop_conditions: Vec::new(),
+            reflection: None,
+            action_parser: Arc::new(DefaultTextParser::new(Vec::new())),
+            delegator: None,
+            schedule_store: None,
+            tools_preloaded: Default::default(),
         }
     }
 
+    /// Get the number of executions handed to the fallback controller.
+    pub fn fallback_invocations(&self) -> u64 {
+        self.fallback_invocations.load(Ordering::Relaxed)
+    }
+
     /// Create a new session.
     fn create_session(&self, goal: &str) -> Session {
-        Session {
-            id: Uuid::new_v4().to_string(),
-            status: SessionStatus::Running,
-            history: vec![HistoryEntry {
-                role: "system".to_string(),
-                content: Arc::new(self.build_system_prompt(goal)),
-                tool_call: None,
-                timestamp: chrono_timestamp(),
-            }],
-            task_state: Some(TaskState {
-                iteration: 0,
-                goal: goal.to_string(),
-                observations: Vec::new(),
-                pending_actions: Vec::new(),
-            }),
-            token_usage: TokenUsage::with_budget(self.config.default_budget),
-            created_at: chrono_timestamp(),
-            updated_at: chrono_timestamp(),
+        let mut session = Session::new(Uuid::new_v4().to_string());
+        session.history.push(HistoryEntry {
+            role: "system".to_string(),
+            content: Arc::new(self.build_system_prompt(goal)),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
+        session.task_state = Some(TaskState::new(goal));
+        session.token_usage = TokenUsage::with_budget(self.config.default_budget);
+        session
+    }
+
+    /// Create a mission session, prepending recalled memories to the system prompt
+    /// and adding an upfront plan when `enable_planning` is set.
+    ///
+    /// `trace_id` and `user_id` link the session to the originating request.
+    async fn create_mission_session(
+        &self,
+        goal: &str,
+        trace_id: Option<String>,
+        user_id: Option<String>,
+    ) -> Session {
+        let mut session = self.create_session(goal);
+        session.trace_id = trace_id;
+        session.metadata.user_id = user_id;
+        if self.config.enable_planning {
+            self.seed_plan(&mut session, goal).await;
+        }
+        let Some(ref memory) = self.memory else {
+            return session;
+        };
+        if self.config.memory_recall_top_k == 0 {
+            return session;
+        }
+
+        match memory.recall(goal, self.config.memory_recall_top_k).await {
+            Ok(hits) if !hits.is_empty() => {
+                let mut prompt = format!("{}:\n", PRIOR_KNOWLEDGE_HEADER);
+                for hit in &hits {
+                    prompt.push_str(&format!("- {}\n", hit.content));
+                }
+                prompt.push('\n');
+                prompt.push_str(&session.history[0].content);
+                session.history[0].content = Arc::new(prompt);
+                tracing::info!(memories = hits.len(), "Added prior knowledge to system prompt");
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to recall prior knowledge"),
         }
+        session
+    }
+
+    /// Plan the mission, storing the steps as pending actions and listing them in the system prompt.
+    async fn seed_plan(&self, session: &mut Session, goal: &str) {
+        let Some(ref llm) = self.llm else {
+            return;
+        };
+        let steps = match PlanningCapability::new(llm.clone()).plan(goal).await {
+            Ok(steps) => steps,
+            Err(e) => {
+                tracing::warn!(error = %e, "Planning failed, continuing without a plan");
+                return;
+            }
+        };
+
+        let prompt = format!("{}\n\n{}", session.history[0].content, PlanningCapability::format_plan(&steps));
+        session.history[0].content = Arc::new(prompt);
+        if let Some(ref mut state) = session.task_state {
+            state.pending_actions = steps
+                .iter()
+                .filter_map(|step| serde_json::to_value(step).ok())
+                .collect();
+        }
+        tracing::info!(steps = steps.len(), "Seeded mission plan");
+    }
+
+    /// Plan the goal and run each step in a subagent concurrently, recording the
+    /// merged results as an observation for the loop to synthesize.
+    ///
+    /// Goals planned as a single step are left to the loop.
+    async fn decompose_and_delegate(&self, session: &mut Session, goal: &str) {
+        let (Some(llm), Some(delegator)) = (&self.llm, &self.delegator) else {
+            return;
+        };
+        let steps = match PlanningCapability::new(llm.clone()).plan(goal).await {
+            Ok(steps) if steps.len() > 1 => steps,
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!(error = %e, "Goal decomposition failed, continuing without subagents");
+                return;
+            }
+        };
+        tracing::info!(session_id = %session.id, subtasks = steps.len(), "Delegating decomposed goal");
+
+        let requests = steps.iter().map(|step| {
+            DelegationRequest::new(&step.description)
+                .with_context(format!("Part of the goal: {}", goal))
+                .with_depth(self.config.delegation_depth + 1)
+                .with_parent_session(session.id.clone())
+        });
+        // Bound the fan-out so a large plan does not flood the LLM provider
+        let permits = Semaphore::new(self.config.max_concurrent_delegations.max(1));
+        let results = futures::future::join_all(requests.map(|request| async {
+            let _permit = permits.acquire().await.map_err(|e| Error::internal(e.to_string()))?;
+            delegator.delegate(request).await
+        }))
+        .await;
+
+        let mut observation = String::from("OBSERVATION: Subtask results:");
+        for (step, result) in steps.iter().zip(results) {
+            let outcome = match result {
+                Ok(result) => {
+                    session
+                        .token_usage
+                        .add(result.token_usage.prompt_tokens, result.token_usage.completion_tokens);
+                    if result.success {
+                        result.result
+                    } else {
+                        format!("Failed: {}", result.error.unwrap_or_default())
+                    }
+                }
+                Err(e) => format!("Failed: {}", e),
+            };
+            observation.push_str(&format!("\n{}. {}\n{}", step.id, step.description, outcome));
+        }
+        observation.push_str("\nCombine these results into your FINAL ANSWER.");
+
+        session.history.push(HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new(observation),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
     }
 
     /// Build the system prompt for the agent.
+    ///
+    /// Uses `system_prompt_template` when set.
     fn build_system_prompt(&self, goal: &str) -> String {
         let tools_description = self.get_tools_description();
-        
+        if let Some(ref template) = self.config.system_prompt_template {
+            return template
+                .replace("{goal}", goal)
+                .replace("{tools}", &tools_description);
+        }
+
         format!(
             r#"You are an AI assistant that uses the ReAct (Reasoning + Acting) pattern.
 
@@ -146,21 +668,110 @@ Always think before acting. Be concise and focused on the goal."#
 
 
     /// Get description of available tools (for system prompt building).
+    ///
+    /// Uses the definitions cached by `refresh_tool_definitions`.
     fn get_tools_description(&self) -> String {
-        // For the system prompt, we return a placeholder since we can't call async here.
-        // The actual tools list is fetched async when executing.
-        "Tools will be loaded when execution starts.".to_string()
+        let definitions = self.tool_definitions.lock().unwrap_or_else(|e| e.into_inner());
+        if definitions.is_empty() {
+            return "No tools available.".to_string();
+        }
+        definitions
+            .iter()
+            .map(|def| format!("- {}: {}{}", def.name, def.description, parameter_summary(&def.parameters)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Whether `allowed_tools` permits calling `name`.
+    fn is_tool_allowed(&self, name: &str) -> bool {
+        match self.config.allowed_tools {
+            Some(ref allowed) => allowed.iter().any(|tool| tool == name),
+            None => true,
+        }
+    }
+
+    /// Fetch the registry's tool definitions for the system prompt.
+    async fn refresh_tool_definitions(&self) {
+        let Some(ref tools) = self.tools else {
+            return;
+        };
+        match tools.list().await {
+            Ok(mut definitions) => {
+                definitions.retain(|def| self.is_tool_allowed(&def.name));
+                definitions.sort_by(|a, b| a.name.cmp(&b.name));
+                *self.tool_definitions.lock().unwrap_or_else(|e| e.into_inner()) = definitions;
+                // Schemas may have changed
+                self.tool_validators.lock().unwrap_or_else(|e| e.into_inner()).clear();
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to list tools for the system prompt"),
+        }
+    }
+
+    /// Cached definition of tool `name`, see `refresh_tool_definitions`.
+    fn tool_definition(&self, name: &str) -> Option<ToolDefinition> {
+        self.tool_definitions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|def| def.name == name)
+            .cloned()
+    }
+
+    /// Check whether the cached definition of `name` declares streaming output.
+    fn supports_streaming(&self, name: &str) -> bool {
+        self.tool_definition(name).is_some_and(|def| def.supports_streaming)
+    }
+
+    /// Compiled parameter schema of tool `name`, compiled once per tool definition.
+    fn tool_validator(&self, name: &str) -> Option<Arc<jsonschema::Validator>> {
+        if let Some(validator) = self.tool_validators.lock().unwrap_or_else(|e| e.into_inner()).get(name) {
+            return validator.clone();
+        }
+        let definition = self.tool_definition(name)?;
+        let validator = if definition.parameters.is_null() {
+            None
+        } else {
+            match jsonschema::validator_for(&definition.parameters) {
+                Ok(validator) => Some(Arc::new(validator)),
+                Err(e) => {
+                    tracing::warn!(tool = %name, error = %e, "Tool has an invalid parameter schema");
+                    None
+                }
+            }
+        };
+        self.tool_validators
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), validator.clone());
+        validator
     }
 
     /// Build chat messages from session history (static version for capabilities).
+    ///
+    /// Tool observations become `tool` role messages.
     pub fn build_messages_static(session: &Session) -> Vec<ChatMessage> {
         session
             .history
             .iter()
-            .map(|entry| ChatMessage {
-                role: entry.role.clone(),
-                content: entry.content.to_string(),
-                tool_calls: None,
+            // Reasoning traces are kept for inspection but never fed back to the LLM
+            .filter(|entry| entry.role != REASONING_ROLE)
+            .map(|entry| match entry.tool_call {
+                Some(_) => ChatMessage {
+                    role: "tool".to_string(),
+                    content: entry
+                        .content
+                        .strip_prefix("OBSERVATION: ")
+                        .unwrap_or(&entry.content)
+                        .to_string(),
+                    tool_calls: None,
+                    images: Vec::new(),
+                },
+                None => ChatMessage {
+                    role: entry.role.clone(),
+                    content: entry.content.to_string(),
+                    tool_calls: None,
+                    images: entry.images.clone(),
+                },
             })
             .collect()
     }
@@ -170,27 +781,196 @@ Always think before acting. Be concise and focused on the goal."#
         Self::build_messages_static(session)
     }
 
+    /// Register a scheduled mission in the schedule store.
+    async fn schedule_mission(&self, goal: String, cron: String, context_summary: String) -> Result<AgentResult> {
+        let store = self.schedule_store.as_ref().ok_or_else(|| {
+            Error::invalid_request("Scheduled missions require a schedule store")
+        })?;
+        let next_run_at = next_after(&parse_cron(&cron)?, chrono::Utc::now())
+            .ok_or_else(|| Error::invalid_request(format!("Cron expression '{}' never fires", cron)))?;
+
+        let entry = ScheduleEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            cron_expr: cron,
+            intent: UserIntent::ComplexMission {
+                goal,
+                context_summary,
+                visual_refs: Vec::new(),
+                trace_id: None,
+                user_id: None,
+            },
+            max_runs: None,
+            run_count: 0,
+            last_run_at: None,
+            next_run_at: Some(next_run_at),
+        };
+        store.save(&entry).await?;
+
+        tracing::info!(schedule_id = %entry.id, next_run_at = next_run_at, "Mission scheduled");
+        Ok(AgentResult::Data(serde_json::json!({
+            "schedule_id": entry.id,
+            "next_run_at": next_run_at,
+        })))
+    }
+
+    /// Run every schedule due at `now`, returning their results in run order.
+    ///
+    /// Each run gets a fresh session. The schedule then moves to the next cron
+    /// time after `now`, or is exhausted if the cron never fires again.
+    pub async fn tick(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<Result<AgentResult>>> {
+        let Some(ref store) = self.schedule_store else {
+            return Ok(Vec::new());
+        };
+
+        let mut due: Vec<ScheduleEntry> = store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|entry| entry.next_run_at.is_some_and(|t| t <= now.timestamp_millis()))
+            .collect();
+        due.sort_by_key(|entry| entry.next_run_at);
+
+        let mut results = Vec::with_capacity(due.len());
+        for mut entry in due {
+            // Advance the schedule before running so a slow run is not started twice
+            entry.record_run(now)?;
+            store.save(&entry).await?;
+
+            tracing::info!(schedule_id = %entry.id, run = entry.run_count, "Running scheduled mission");
+            results.push(self.execute(entry.intent).await);
+        }
+        Ok(results)
+    }
+
+    /// Estimate the token count of the session's current context without a completion call.
+    pub async fn live_token_estimate(&self, session: &Session) -> Result<u64> {
+        let llm = self.llm.as_ref().ok_or_else(|| {
+            Error::llm("LLM client not configured")
+        })?;
+        llm.count_tokens(&self.build_messages(session)).await
+    }
+
+    /// Explain where a persisted session's tokens went, e.g. after `Error::BudgetExceeded`.
+    pub async fn explain_token_usage(&self, session_id: &str) -> Result<TokenUsageExplanation> {
+        let store = self.session_store.as_ref().ok_or_else(|| {
+            Error::controller("State persistence not configured (session_store is None)")
+        })?;
+        let session = store
+            .load(session_id)
+            .await?
+            .ok_or_else(|| Error::controller(format!("Session {} not found", session_id)))?;
+        Ok(session.explain_token_usage())
+    }
+
+    /// Attach the reasoning recorded in a persisted session to its `result`.
+    pub async fn explain_result(&self, session_id: &str, result: AgentResult) -> Result<AgentResultWithExplanation> {
+        let store = self.session_store.as_ref().ok_or_else(|| {
+            Error::controller("State persistence not configured (session_store is None)")
+        })?;
+        let session = store
+            .load(session_id)
+            .await?
+            .ok_or_else(|| Error::controller(format!("Session {} not found", session_id)))?;
+        Ok(AgentResultWithExplanation::from_session(result, &session))
+    }
+
+    /// Re-run the tool calls recorded in a persisted session, in order, without the LLM.
+    ///
+    /// Each call runs against the current tool implementations, so comparing the
+    /// outputs with the recorded results shows whether a tool's behavior changed.
+    /// A call that raises an error yields a failed output and the replay goes on.
+    pub async fn replay(&self, session_id: &str) -> Result<Vec<ToolOutput>> {
+        let store = self.session_store.as_ref().ok_or_else(|| {
+            Error::controller("State persistence not configured (session_store is None)")
+        })?;
+        let tools = self
+            .tools
+            .as_ref()
+            .ok_or_else(|| Error::controller("No tool registry configured to replay tool calls"))?;
+        let session = store
+            .load(session_id)
+            .await?
+            .ok_or_else(|| Error::controller(format!("Session {} not found", session_id)))?;
+        self.refresh_tool_definitions().await;
+
+        let mut outputs = Vec::new();
+        for call in session.history.iter().filter_map(|entry| entry.tool_call.as_ref()) {
+            tracing::info!(session_id = %session_id, tool = %call.name, "Replaying tool call");
+            let output = self
+                .execute_tool(tools.as_ref(), &call.name, call.arguments.clone(), None)
+                .await
+                .unwrap_or_else(|e| ToolOutput::error(e.to_string()));
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+
+    /// Reconfigure a capability at runtime. Takes effect from the next iteration.
+    ///
+    /// `config` is validated against the capability's `config_schema` first.
+    pub async fn reconfigure_capability(&self, name: &str, config: serde_json::Value) -> Result<()> {
+        let cap = self
+            .capabilities
+            .iter()
+            .find(|cap| cap.name() == name)
+            .ok_or_else(|| Error::controller(format!("Capability '{}' not found", name)))?;
+
+        let schema = cap.config_schema().ok_or_else(|| {
+            Error::controller(format!("Capability '{}' is not configurable", name))
+        })?;
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| Error::controller(format!("Invalid config schema for '{}': {}", name, e)))?;
+        validator
+            .validate(&config)
+            .map_err(|e| Error::invalid_request(format!("Invalid config for '{}': {}", name, e)))?;
+
+        cap.configure(config).await?;
+        tracing::info!(name = cap.name(), "capability_reconfigured");
+        Ok(())
+    }
+
+    /// Truncate `messages` to `max_history_tokens`, keeping the system prompt and the latest message.
+    async fn fit_context_window(&self, messages: Vec<ChatMessage>) -> Result<Vec<ChatMessage>> {
+        let Some(max_tokens) = self.config.max_history_tokens else {
+            return Ok(messages);
+        };
+        let compressor = TruncationCompressor::new();
+        let tokens = compressor.estimate_tokens(&messages);
+        if tokens <= max_tokens {
+            return Ok(messages);
+        }
+
+        let config = CompressionConfig {
+            max_tokens,
+            target_ratio: 1.0,
+            preserve_recent: 1,
+            ..Default::default()
+        };
+        let result = compressor.compress(messages, &config).await?;
+        tracing::info!(
+            tokens = tokens,
+            max_history_tokens = max_tokens,
+            estimated_tokens = result.estimated_tokens,
+            dropped = result.messages_compressed,
+            "Truncated prompt to the history token cap"
+        );
+        Ok(result.messages)
+    }
+
     /// Parse the LLM response to extract action.
     fn parse_action(&self, response: &str) -> ReActAction {
-        crate::parser::ActionParser::new(self.capabilities.clone()).parse(response)
+        self.action_parser.parse(response)
     }
 
     /// Execute a single ReAct iteration with LLM.
     async fn execute_iteration_with_llm(
         &self,
         session: &mut Session,
-        iteration: usize,
+        state: &mut LoopState,
     ) -> Result<Option<AgentResult>> {
-        let llm = self.llm.as_ref().ok_or_else(|| {
-            Error::controller("LLM client not configured")
-        })?;
+        let llm = self.select_llm(session)?;
 
-        tracing::info!(
-            session_id = %session.id,
-            iteration = iteration,
-            history_len = session.history.len(),
-            "Executing ReAct iteration"
-        );
+        tracing::info!(history_len = session.history.len(), "Executing ReAct iteration");
 
         // v0.3: Capabilities On-Pre-Reasoning Hook (Compression, Security, etc.)
         for cap in &self.capabilities {
@@ -198,15 +978,18 @@ Always think before acting. Be concise and focused on the goal."#
         }
 
         let messages = self.build_messages(session); // Rebuild messages after potential compression
+        let messages = self.fit_context_window(messages).await?;
 
-        // Call LLM with (possibly compressed) messages
-        let response: LlmResponse = llm.chat(&messages).await?;
+        if state.events.is_some() {
+            // The estimate is informational, a tokenizer failure must not fail the mission
+            match llm.count_tokens(&messages).await {
+                Ok(tokens) => state.emit(ReActEvent::TokenEstimate(tokens)),
+                Err(e) => tracing::warn!(error = %e, "Failed to estimate prompt tokens, skipping the estimate"),
+            }
+        }
 
-        // Update token usage
-        session.token_usage.add(
-            response.usage.prompt_tokens,
-            response.usage.completion_tokens,
-        );
+        let response = self.call_llm(llm.as_ref(), session, &messages).await?;
+        tracing::Span::current().record("tokens_used", session.token_usage.total_tokens);
 
         tracing::debug!(
             response_len = response.content.len(),
@@ -214,41 +997,144 @@ Always think before acting. Be concise and focused on the goal."#
             "LLM response received"
         );
 
+        if let Some(reasoning) = response.reasoning {
+            state.emit(ReActEvent::Thought(reasoning.clone()));
+            session.history.push(HistoryEntry {
+                role: REASONING_ROLE.to_string(),
+                content: Arc::new(reasoning),
+                tool_call: None,
+                timestamp: chrono_timestamp(),
+                compressed_from: None,
+                images: Vec::new(),
+            });
+        }
+
+        let no_tool_calls = response.tool_calls.as_ref().is_none_or(|calls| calls.is_empty());
+        if response.content.trim().is_empty() && no_tool_calls {
+            state.empty_responses += 1;
+            if state.empty_responses > self.config.max_empty_responses {
+                return Err(Error::llm(format!(
+                    "LLM returned {} empty responses in a row",
+                    state.empty_responses
+                )));
+            }
+            tracing::warn!(
+                session_id = %session.id,
+                empty_responses = state.empty_responses,
+                "Empty LLM response, re-prompting"
+            );
+            session.history.push(HistoryEntry {
+                role: "user".to_string(),
+                content: Arc::new(EMPTY_RESPONSE_MESSAGE.to_string()),
+                tool_call: None,
+                timestamp: chrono_timestamp(),
+                compressed_from: None,
+                images: Vec::new(),
+            });
+            return Ok(None);
+        }
+        state.empty_responses = 0;
+
         // Add assistant response to history
         session.history.push(HistoryEntry {
             role: "assistant".to_string(),
             content: Arc::new(response.content.clone()),
             tool_call: None,
             timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
         });
 
-        // Parse and execute action
-        let action = self.parse_action(&response.content);
+        // Parse and execute action, preferring a native tool call when enabled
+        let native_call = match response.tool_calls {
+            Some(ref calls) if self.config.use_native_tool_calls => {
+                let parser = crate::parser::DefaultTextParser::new(vec![]);
+                let mut actions: Vec<ReActAction> =
+                    calls.iter().filter_map(|call| parser.extract_tool_call(call)).collect();
+                if actions.len() > 1 {
+                    let calls = actions
+                        .into_iter()
+                        .filter_map(|action| match action {
+                            ReActAction::ToolCall { name, args } => Some((name, args)),
+                            _ => None,
+                        })
+                        .collect();
+                    Some(ReActAction::ParallelToolCalls(calls))
+                } else {
+                    actions.pop()
+                }
+            }
+            _ => None,
+        };
+        let action = match native_call {
+            Some(action) => action,
+            None => self.parse_action(&response.content),
+        };
+
+        let tool_calls = match action {
+            ReActAction::ToolCall { .. } | ReActAction::Delegate { .. } => 1,
+            ReActAction::ParallelToolCalls(ref calls) => calls.len(),
+            _ => 0,
+        };
+        if !matches!(action, ReActAction::Think(_)) {
+            state.consecutive_thoughts = 0;
+        }
+        if tool_calls > 0 {
+            if state.tool_limit_reached {
+                return Err(Error::MaxToolCallsExceeded(self.config.max_total_tool_calls));
+            }
+            state.total_tool_calls += tool_calls;
+        }
 
         match action {
             ReActAction::FinalAnswer(ref answer) => {
-                // Check capabilities on execution (Security Output check)
-                for cap in &self.capabilities {
-                    if let Some(result) = cap.on_execute(&action, session).await? {
-                         // If a capability interrupts/handles FinalAnswer (e.g., blocks it), return that result
-                         // Standard security cap returns Err on violation, keeping this flow simple.
-                         match result {
-                             AgentResult::Error { .. } => return Ok(Some(result)),
-                             _ => {} // Ignore other results for FinalAnswer
-                         }
-                    }
+                let result = self.accept_final_answer(&action, answer, session, state).await?;
+                if result.is_some() {
+                    tracing::info!(answer_len = answer.len(), "Task completed with final answer");
                 }
-                
-                tracing::info!(answer_len = answer.len(), "Task completed with final answer");
-                Ok(Some(AgentResult::Text(answer.clone())))
+                Ok(result)
             }
 
             ReActAction::ToolCall { name, args } => {
-                self.handle_tool_call(session, name, args).await
+                state.emit(ReActEvent::ToolCallStarted {
+                    name: name.clone(),
+                    args: args.clone(),
+                });
+                self.handle_tool_call(session, name, args, state).await
+            }
+
+            ReActAction::ParallelToolCalls(calls) => {
+                for (name, args) in &calls {
+                    state.emit(ReActEvent::ToolCallStarted {
+                        name: name.clone(),
+                        args: args.clone(),
+                    });
+                }
+                self.handle_parallel_tool_calls(session, calls, state).await
             }
 
             ReActAction::Think(thought) => {
                 tracing::debug!(thought_len = thought.len(), "Agent thinking");
+                state.consecutive_thoughts = if thought.len() >= SUBSTANTIAL_THOUGHT_CHARS {
+                    state.consecutive_thoughts + 1
+                } else {
+                    0
+                };
+                if self.config.detect_implicit_final_answer
+                    && state.consecutive_thoughts >= self.config.implicit_final_answer_thoughts.max(1)
+                {
+                    state.consecutive_thoughts = 0;
+                    let answer = thought.strip_prefix("THOUGHT:").unwrap_or(&thought).trim();
+                    if self.is_implicit_final_answer(session, answer).await? {
+                        let action = ReActAction::FinalAnswer(answer.to_string());
+                        let result = self.accept_final_answer(&action, answer, session, state).await?;
+                        if result.is_some() {
+                            tracing::info!(answer_len = answer.len(), "Task completed with implicit final answer");
+                        }
+                        return Ok(result);
+                    }
+                }
+                state.emit(ReActEvent::Thought(thought));
                 
                 // Ask the agent to take an action
                 session.history.push(HistoryEntry {
@@ -256,6 +1142,8 @@ Always think before acting. Be concise and focused on the goal."#
                     content: Arc::new("Please take an action using a tool, or provide your FINAL ANSWER if the task is complete.".to_string()),
                     tool_call: None,
                     timestamp: chrono_timestamp(),
+                    compressed_from: None,
+                    images: Vec::new(),
                 });
 
                 // v0.4: Post-Execute Hook
@@ -269,393 +1157,4676 @@ Always think before acting. Be concise and focused on the goal."#
 
             // Fallback: Check custom capability actions
             _ => {
-                for cap in &self.capabilities {
-                     if let Some(result) = cap.on_execute(&action, session).await? {
-                         // Add observation to history if returned
-                         if let AgentResult::Text(observation) = &result {
-                             session.history.push(HistoryEntry {
-                                role: "user".to_string(),
-                                content: Arc::new(format!("OBSERVATION: {}", observation)),
-                                tool_call: None,
-                                timestamp: chrono_timestamp(),
-                            });
-                             // Update task state
-                            if let Some(ref mut task_state) = session.task_state {
-                                task_state.observations.push(Arc::new(observation.clone()));
-                            }
-                         }
-                         
-                        // v0.4: Post-Execute Hook
-                        for cap in &self.capabilities {
-                            cap.on_post_execute(session).await.map_err(|e| Error::controller(e.to_string()))?;
-                        }
+                if let ReActAction::Delegate { ref objective, ref context } = action {
+                    state.emit(ReActEvent::Delegation {
+                        objective: objective.clone(),
+                        context: context.clone(),
+                    });
+                    if self.config.dry_run {
+                        tracing::info!(objective = %objective, "Dry run: skipping delegation");
+                        session.history.push(HistoryEntry {
+                            role: "user".to_string(),
+                            content: Arc::new(format!(
+                                "OBSERVATION: [DRY RUN] would delegate to a subagent: {}",
+                                objective
+                            )),
+                            tool_call: None,
+                            timestamp: chrono_timestamp(),
+                            compressed_from: None,
+                            images: Vec::new(),
+                        });
+                        return Ok(None);
+                    }
 
-                         return Ok(None); // Action handled, continue loop
-                     }
+                    let span = tracing::info_span!(
+                        "delegation",
+                        objective = %objective,
+                        duration_ms = tracing::field::Empty,
+                    );
+                    let started = Instant::now();
+                    let result = self
+                        .run_capabilities(&action, session)
+                        .instrument(span.clone())
+                        .await;
+                    span.record("duration_ms", started.elapsed().as_millis() as u64);
+                    result?;
+                    return Ok(None);
                 }
-                 // If no capability handled it, default behavior (shouldn't happen if parsed correctly)
-                 Ok(None)
+                self.run_capabilities(&action, session).await?;
+                Ok(None)
             }
         }
     }
 
-    /// Execute iteration (mock if no LLM, real if LLM configured).
-    async fn execute_iteration(
+    /// Run a final answer through the output checks, the instruction check and reflection.
+    ///
+    /// Returns `None` when a repair prompt or reflection feedback was injected and
+    /// the loop should continue.
+    async fn accept_final_answer(
         &self,
+        action: &ReActAction,
+        answer: &str,
         session: &mut Session,
-        iteration: usize,
+        state: &mut LoopState,
     ) -> Result<Option<AgentResult>> {
-        if self.llm.is_some() {
-            self.execute_iteration_with_llm(session, iteration).await
-        } else {
-            // Mock implementation for testing without LLM
-            tracing::info!(
-                session_id = %session.id,
-                iteration = iteration,
-                "Executing ReAct iteration (mock - no LLM)"
-            );
+        if let Some(blocked) = self.check_final_answer(action, answer, session).await? {
+            return Ok(Some(blocked));
+        }
 
-            Ok(Some(AgentResult::Text(format!(
-                "Mock ReAct execution. Goal: {}. Configure LLM client for real execution.",
-                session
-                    .task_state
-                    .as_ref()
-                    .map(|t| t.goal.as_str())
-                    .unwrap_or("unknown")
-            ))))
+        if self.config.instruction_following_check
+            && !self.check_instruction_following(session, answer).await?
+        {
+            return Ok(None); // Repair prompt injected, continue loop
         }
-    }
 
-    async fn persist_session(&self, session: &Session) {
-        if self.config.persist_state {
-            if let Some(store) = &self.session_store {
-                if let Err(e) = store.save(session).await {
-                    tracing::warn!(error = %e, "Failed to save session state");
-                }
-            }
+        if self.reject_by_reflection(session, answer, state).await {
+            return Ok(None); // Feedback injected, continue loop
         }
+
+        Ok(Some(AgentResult::Text(answer.to_string())))
     }
 
-    async fn validate_fast_action_security(&self, args: &serde_json::Value) -> Result<()> {
+    /// Run the output checks a final answer must pass before it is returned.
+    ///
+    /// Returns the result of a capability that blocks the answer, and fails on a
+    /// moderation violation.
+    async fn check_final_answer(
+        &self,
+        action: &ReActAction,
+        answer: &str,
+        session: &mut Session,
+    ) -> Result<Option<AgentResult>> {
+        // Check capabilities on execution (Security Output check)
         for cap in &self.capabilities {
-            if cap.name() == "security_guardrails" {
-                let mut temp_session = self.create_session("fast_action_check");
-                temp_session.history.push(HistoryEntry {
-                    role: "user".to_string(),
-                    content: Arc::new(serde_json::to_string(args).unwrap_or_default()),
-                    tool_call: None,
-                    timestamp: chrono_timestamp(),
-                });
-                cap.on_pre_reasoning(&mut temp_session).await.map_err(|e| Error::controller(e.to_string()))?;
+            if let Some(result) = cap.on_execute(action, session).await? {
+                 // If a capability interrupts/handles FinalAnswer (e.g., blocks it), return that result
+                 // Standard security cap returns Err on violation, keeping this flow simple.
+                 match result {
+                     AgentResult::Error { .. } => return Ok(Some(result)),
+                     _ => {} // Ignore other results for FinalAnswer
+                 }
             }
         }
-        Ok(())
-    }
+
+        self.moderate(answer, ModerationDirection::Output).await?;
+        Ok(None)
+    }
+
+    /// Let the first capability handling `action` execute it, recording its observation.
+    async fn run_capabilities(&self, action: &ReActAction, session: &mut Session) -> Result<()> {
+        for cap in &self.capabilities {
+            if let Some(result) = cap.on_execute(action, session).await? {
+                // Add observation to history if returned
+                if let AgentResult::Text(observation) = &result {
+                    session.history.push(HistoryEntry {
+                        role: "user".to_string(),
+                        content: Arc::new(format!("OBSERVATION: {}", observation)),
+                        tool_call: None,
+                        timestamp: chrono_timestamp(),
+                        compressed_from: None,
+                        images: Vec::new(),
+                    });
+                    // Update task state
+                    if let Some(ref mut task_state) = session.task_state {
+                        task_state.observe(None, observation.clone());
+                    }
+                }
+
+                // v0.4: Post-Execute Hook
+                for cap in &self.capabilities {
+                    cap.on_post_execute(session).await.map_err(|e| Error::controller(e.to_string()))?;
+                }
+
+                return Ok(()); // Action handled, continue loop
+            }
+        }
+        // If no capability handled it, default behavior (shouldn't happen if parsed correctly)
+        Ok(())
+    }
+
+    /// Pick the LLM for the next iteration: the selected tier's client if
+    /// configured, otherwise the default LLM.
+    fn select_llm(&self, session: &Session) -> Result<Arc<dyn LlmClient>> {
+        if !self.tier_llms.is_empty() {
+            let tier = self.tier_selector.select(session);
+            if let Some(llm) = self.tier_llms.get(&tier) {
+                tracing::debug!(session_id = %session.id, tier = ?tier, "Selected model tier");
+                return Ok(llm.clone());
+            }
+            tracing::debug!(session_id = %session.id, tier = ?tier, "No LLM for selected tier, using default");
+        }
+        self.llm
+            .clone()
+            .ok_or_else(|| Error::llm("LLM client not configured"))
+    }
+
+    /// Call `llm` and record its token usage on `session`.
+    ///
+    /// Applies the budget pre-check, the rate limiter and transient-error retries,
+    /// so every LLM call of a mission goes through the same path.
+    async fn call_llm(&self, llm: &dyn LlmClient, session: &mut Session, messages: &[ChatMessage]) -> Result<LlmResponse> {
+        let max_tokens = match self.config.max_tokens_per_iteration {
+            Some(cap) => {
+                // Refuse calls the remaining budget cannot cover, instead of overshooting it
+                let prompt_tokens = llm.count_tokens(messages).await?;
+                let remaining = session.token_usage.remaining();
+                if remaining < prompt_tokens + MIN_COMPLETION_TOKENS {
+                    tracing::warn!(
+                        session_id = %session.id,
+                        remaining = remaining,
+                        prompt_tokens = prompt_tokens,
+                        "Insufficient budget for next LLM call"
+                    );
+                    return Err(Error::BudgetExceeded {
+                        used: session.token_usage.total_tokens,
+                        limit: session.token_usage.budget_limit,
+                    });
+                }
+                Some(cap.min(remaining - prompt_tokens))
+            }
+            None => None,
+        };
+
+        // Retry transient failures
+        let mut attempt = 0;
+        let response: LlmResponse = loop {
+            if let Some(ref limiter) = self.rate_limiter {
+                limiter.acquire(1).await?;
+            }
+
+            let result = match max_tokens {
+                Some(max_tokens) => llm.chat_with_max_tokens(messages, max_tokens).await,
+                None => llm.chat(messages).await,
+            };
+            match result {
+                Err(e) if e.is_transient() && attempt < self.config.max_retries => {
+                    let delay = self.config.retry_base_delay_ms.saturating_mul(1 << attempt.min(16));
+                    attempt += 1;
+                    tracing::warn!(
+                        session_id = %session.id,
+                        attempt = attempt,
+                        delay_ms = delay,
+                        error = %e,
+                        "Transient LLM error, retrying"
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                }
+                result => break result?,
+            }
+        };
+
+        session.token_usage.add(
+            response.usage.prompt_tokens,
+            response.usage.completion_tokens,
+        );
+        Ok(response)
+    }
+
+    /// Ask the LLM whether `answer` satisfies the session goal.
+    ///
+    /// On failure, injects a repair prompt with the reason and returns false.
+    async fn check_instruction_following(&self, session: &mut Session, answer: &str) -> Result<bool> {
+        let llm = self.select_llm(session)?;
+        let goal = session
+            .task_state
+            .as_ref()
+            .map(|t| t.goal.clone())
+            .unwrap_or_default();
+
+        let prompt = format!(
+            "Does the following answer satisfy the goal '{}'? Yes or No and brief reason.\n\nANSWER: {}",
+            goal, answer
+        );
+
+        let message = ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+            tool_calls: None,
+            images: Vec::new(),
+        };
+        let response = self.call_llm(llm.as_ref(), session, &[message]).await?;
+
+        let verdict = response.content.trim();
+        let passed = !verdict.to_lowercase().starts_with("no");
+        session.instruction_check_passed = Some(passed);
+
+        if !passed {
+            tracing::info!(session_id = %session.id, verdict = %verdict, "Final answer failed instruction check");
+            session.history.push(HistoryEntry {
+                role: "user".to_string(),
+                content: Arc::new(format!(
+                    "Your answer does not satisfy the goal '{}'. Reviewer: {}\nRevise it and provide a new FINAL ANSWER.",
+                    goal, verdict
+                )),
+                tool_call: None,
+                timestamp: chrono_timestamp(),
+                compressed_from: None,
+                images: Vec::new(),
+            });
+        }
+
+        Ok(passed)
+    }
+
+    /// Ask the LLM whether `answer`, written without the FINAL ANSWER prefix, completes the goal.
+    async fn is_implicit_final_answer(&self, session: &mut Session, answer: &str) -> Result<bool> {
+        let llm = self.select_llm(session)?;
+        let goal = session
+            .task_state
+            .as_ref()
+            .map(|t| t.goal.clone())
+            .unwrap_or_default();
+
+        let prompt = format!(
+            "Is the following response a complete final answer to the goal '{}'? Answer Yes or No.\n\nRESPONSE: {}",
+            goal, answer
+        );
+
+        let message = ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+            tool_calls: None,
+            images: Vec::new(),
+        };
+        let response = self.call_llm(llm.as_ref(), session, &[message]).await?;
+
+        let done = response.content.trim().to_lowercase().starts_with("yes");
+        tracing::debug!(session_id = %session.id, done = done, "Implicit final answer check");
+        Ok(done)
+    }
+
+    /// Review a final answer with the reflection capability.
+    ///
+    /// Returns true if the answer was rejected; the feedback is then recorded as an observation.
+    async fn reject_by_reflection(&self, session: &mut Session, answer: &str, state: &mut LoopState) -> bool {
+        let Some(ref reflection) = self.reflection else {
+            return false;
+        };
+        if state.reflections >= self.config.max_reflections {
+            return false;
+        }
+        let goal = session
+            .task_state
+            .as_ref()
+            .map(|t| t.goal.clone())
+            .unwrap_or_default();
+
+        let ReflectionOutcome::Rejected { feedback } = reflection.reflect(&goal, answer).await else {
+            return false;
+        };
+        state.reflections += 1;
+        tracing::info!(
+            session_id = %session.id,
+            reflections = state.reflections,
+            feedback = %feedback,
+            "Final answer rejected by reflection"
+        );
+
+        let observation = format!(
+            "OBSERVATION: Self-review rejected the answer: {}\nRevise it and provide a new FINAL ANSWER.",
+            feedback
+        );
+        if let Some(ref mut task_state) = session.task_state {
+            task_state.observe(None, observation.clone());
+        }
+        session.history.push(HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new(observation),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
+        true
+    }
+
+    /// Execute iteration (mock if no LLM, real if LLM configured).
+    async fn execute_iteration(
+        &self,
+        session: &mut Session,
+        iteration: usize,
+        state: &mut LoopState,
+    ) -> Result<Option<AgentResult>> {
+        if self.llm.is_some() || !self.tier_llms.is_empty() {
+            let span = tracing::info_span!(
+                "react_iteration",
+                session_id = %session.id,
+                trace_id = session.trace_id.as_deref(),
+                iteration = iteration,
+                tokens_used = session.token_usage.total_tokens,
+            );
+            self.execute_iteration_with_llm(session, state)
+                .instrument(span)
+                .await
+        } else {
+            // Mock implementation for testing without LLM
+            tracing::info!(
+                session_id = %session.id,
+                iteration = iteration,
+                "Executing ReAct iteration (mock - no LLM)"
+            );
+
+            Ok(Some(AgentResult::Text(format!(
+                "Mock ReAct execution. Goal: {}. Configure LLM client for real execution.",
+                session
+                    .task_state
+                    .as_ref()
+                    .map(|t| t.goal.as_str())
+                    .unwrap_or("unknown")
+            ))))
+        }
+    }
+
+    /// Save the session, redacted if a redactor is configured. The in-memory session keeps full content.
+    async fn persist_session(&self, session: &Session) {
+        if self.config.persist_state {
+            if let Some(store) = &self.session_store {
+                let redacted;
+                let session = match self.redactor {
+                    Some(ref redactor) => {
+                        redacted = redactor.redact_session(session);
+                        &redacted
+                    }
+                    None => session,
+                };
+                if let Err(e) = store.save(session).await {
+                    tracing::warn!(error = %e, "Failed to save session state");
+                }
+            }
+        }
+    }
+
+    /// Wait for tool backends to finish preloading, if enabled.
+    async fn ensure_tools_preloaded(&self) -> Result<()> {
+        if self.config.preload_tools {
+            if let Some(ref tools) = self.tools {
+                // Waits for the background preload from `build()` instead of starting a second one
+                self.tools_preloaded.get_or_try_init(|| tools.preload()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run content moderation on `text`, failing on a violation.
+    async fn moderate(&self, text: &str, direction: ModerationDirection) -> Result<()> {
+        if let Some(ref moderator) = self.moderator {
+            let result = moderator.moderate(text, direction).await?;
+            if !result.passed {
+                tracing::warn!(
+                    direction = ?direction,
+                    categories = ?result.categories,
+                    "Content moderation failed"
+                );
+                return Err(Error::ContentViolation {
+                    categories: result.categories,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn validate_fast_action_security(&self, args: &serde_json::Value) -> Result<()> {
+        for cap in &self.capabilities {
+            if cap.name() == "security_guardrails" {
+                let mut temp_session = self.create_session("fast_action_check");
+                temp_session.history.push(HistoryEntry {
+                    role: "user".to_string(),
+                    content: Arc::new(serde_json::to_string(args).unwrap_or_default()),
+                    tool_call: None,
+                    timestamp: chrono_timestamp(),
+                    compressed_from: None,
+                    images: Vec::new(),
+                });
+                cap.on_pre_reasoning(&mut temp_session).await.map_err(|e| Error::controller(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Summarize an observation above the compression threshold.
+    ///
+    /// The full output is saved to the artifact store (if configured) and
+    /// referenced in the summary. Falls back to the full output on failure.
+    async fn compress_observation(&self, observation: String) -> String {
+        if observation.len() <= self.config.observation_compression_threshold {
+            return observation;
+        }
+        let Some(ref compressor) = self.observation_compressor else {
+            return observation;
+        };
+
+        let reference = match self.artifact_store {
+            Some(ref store) => {
+                match store.save_with_type(bytes::Bytes::from(observation.clone()), "text/plain").await {
+                    Ok(ref_id) => Some(ref_id),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to store full observation");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let message = ChatMessage {
+            role: "user".to_string(),
+            content: observation.clone(),
+            tool_calls: None,
+            images: Vec::new(),
+        };
+        let config = CompressionConfig {
+            preserve_recent: 0,
+            ..Default::default()
+        };
+        let summary = match compressor.compress(vec![message], &config).await {
+            Ok(result) => match result.messages.into_iter().next() {
+                Some(summary) => summary.content,
+                None => return observation,
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "Observation compression failed, keeping full output");
+                return observation;
+            }
+        };
+
+        tracing::debug!(
+            original_len = observation.len(),
+            summary_len = summary.len(),
+            "Compressed tool observation"
+        );
+
+        match reference {
+            Some(ref_id) => format!("{}\n[Full output stored as artifact {}]", summary, ref_id),
+            None => summary,
+        }
+    }
+
+    /// Replace a tool output above `max_inline_tool_output` with a reference.
+    ///
+    /// The content is saved to the artifact store; without a store, or if
+    /// saving fails, the output is kept inline.
+    async fn offload_tool_output(&self, name: &str, output: ToolOutput) -> ToolOutput {
+        let Some(limit) = self.config.max_inline_tool_output else {
+            return output;
+        };
+        if output.content.len() <= limit {
+            return output;
+        }
+        let Some(ref store) = self.artifact_store else {
+            return output;
+        };
+
+        let len = output.content.len();
+        match store.save_with_type(bytes::Bytes::from(output.content.clone()), "text/plain").await {
+            Ok(ref_id) => {
+                tracing::debug!(tool = %name, ref_id = %ref_id, len = len, "Offloaded large tool output");
+                let preview: String = output.content.chars().take(OFFLOADED_OUTPUT_PREVIEW_CHARS).collect();
+                let mut offloaded = ToolOutput::reference(
+                    ref_id,
+                    format!("Full output is {} bytes, call fetch_artifact for details. Preview:\n{}", len, preview),
+                );
+                offloaded.success = output.success;
+                offloaded.data = output.data;
+                offloaded.created_refs.extend(output.created_refs);
+                offloaded
+            }
+            Err(e) => {
+                tracing::warn!(tool = %name, error = %e, "Failed to offload tool output, keeping it inline");
+                output
+            }
+        }
+    }
+
+    /// Keep the first `observation_head` and last `observation_tail` characters of an observation.
+    fn truncate_observation(&self, observation: String) -> String {
+        if self.config.observation_head.is_none() && self.config.observation_tail.is_none() {
+            return observation;
+        }
+        elide_middle(
+            observation,
+            self.config.observation_head.unwrap_or(0),
+            self.config.observation_tail.unwrap_or(0),
+        )
+    }
 
     async fn handle_tool_call(
         &self,
         session: &mut Session,
         name: String,
         args: serde_json::Value,
+        state: &LoopState,
+    ) -> Result<Option<AgentResult>> {
+        let run = self.run_tool(&name, &args, state.events.as_ref()).await;
+        let stop = self.check_stop_conditions(&name, run.output.as_ref(), session);
+        self.record_tool_result(session, name, args, run.observation, run.retry_count, state);
+
+        for cap in &self.capabilities {
+            cap.on_post_execute(session).await.map_err(|e| Error::controller(e.to_string()))?;
+        }
+
+        Ok(stop)
+    }
+
+    /// Run several tool calls concurrently, recording observations in request order.
+    async fn handle_parallel_tool_calls(
+        &self,
+        session: &mut Session,
+        calls: Vec<(String, serde_json::Value)>,
+        state: &LoopState,
     ) -> Result<Option<AgentResult>> {
+        tracing::info!(count = calls.len(), "Executing parallel tool calls");
+
+        // Checkpoint the batch so a resumed session runs the calls that did not finish
+        let pending: Vec<PendingToolCall> = calls
+            .iter()
+            .map(|(tool, args)| PendingToolCall {
+                tool: tool.clone(),
+                args: args.clone(),
+            })
+            .collect();
+        if let Some(ref mut task_state) = session.task_state {
+            task_state
+                .pending_actions
+                .extend(pending.iter().filter_map(|call| serde_json::to_value(call).ok()));
+        }
+        self.persist_session(session).await;
+
+        let results = futures::future::join_all(calls.iter().map(|(name, args)| self.run_tool(name, args, state.events.as_ref()))).await;
+        let mut stop = None;
+        for ((name, args), run) in calls.into_iter().zip(results) {
+            if stop.is_none() {
+                stop = self.check_stop_conditions(&name, run.output.as_ref(), session);
+            }
+            self.record_tool_result(session, name, args, run.observation, run.retry_count, state);
+        }
+        if let Some(ref mut task_state) = session.task_state {
+            task_state
+                .pending_actions
+                .retain(|action| PendingToolCall::parse(action).is_none_or(|call| !pending.contains(&call)));
+        }
+
+        for cap in &self.capabilities {
+            cap.on_post_execute(session).await.map_err(|e| Error::controller(e.to_string()))?;
+        }
+
+        Ok(stop)
+    }
+
+    /// Execute the tool calls checkpointed in `TaskState::pending_actions`, in order.
+    ///
+    /// Each call is removed from the list and the session saved once it has run.
+    /// Returns a result if a stop condition ends the mission.
+    async fn run_pending_actions(&self, session: &mut Session, state: &mut LoopState) -> Result<Option<AgentResult>> {
+        loop {
+            let next = session.task_state.as_ref().and_then(|task_state| {
+                task_state
+                    .pending_actions
+                    .iter()
+                    .enumerate()
+                    .find_map(|(i, action)| PendingToolCall::parse(action).map(|call| (i, call)))
+            });
+            let Some((index, call)) = next else {
+                return Ok(None);
+            };
+            tracing::info!(session_id = %session.id, tool = %call.tool, "Running pending tool call");
+
+            state.emit(ReActEvent::ToolCallStarted {
+                name: call.tool.clone(),
+                args: call.args.clone(),
+            });
+            state.total_tool_calls += 1;
+            let stop = self.handle_tool_call(session, call.tool, call.args, state).await?;
+            if let Some(ref mut task_state) = session.task_state {
+                task_state.pending_actions.remove(index);
+            }
+            self.persist_session(session).await;
+            if stop.is_some() {
+                return Ok(stop);
+            }
+        }
+    }
+
+    /// Evaluate the stop conditions for a finished tool call.
+    fn check_stop_conditions(&self, name: &str, output: Option<&ToolOutput>, session: &Session) -> Option<AgentResult> {
+        let result = self
+            .stop_conditions
+            .iter()
+            .find_map(|condition| condition.should_stop(name, output, session))?;
+        tracing::info!(session_id = %session.id, tool = %name, "Stop condition met");
+        Some(result)
+    }
+
+    /// Run a tool call in a `tool_call` span recording the tool name and duration.
+    ///
+    /// Output chunks of streaming tools are forwarded to `events`.
+    async fn run_tool(
+        &self,
+        name: &str,
+        args: &serde_json::Value,
+        events: Option<&mpsc::UnboundedSender<ReActEvent>>,
+    ) -> ToolRun {
+        let span = tracing::info_span!("tool_call", tool = %name, duration_ms = tracing::field::Empty);
+        let started = Instant::now();
+        let result = self.validate_and_execute_tool(name, args, events).instrument(span.clone()).await;
+
+        let duration_ms = started.elapsed().as_millis() as u64;
+        span.record("duration_ms", duration_ms);
+        span.in_scope(|| tracing::debug!(duration_ms = duration_ms, "Tool call finished"));
+        result
+    }
+
+    /// Validate and execute a tool call.
+    async fn validate_and_execute_tool(
+        &self,
+        name: &str,
+        args: &serde_json::Value,
+        events: Option<&mpsc::UnboundedSender<ReActEvent>>,
+    ) -> ToolRun {
+        if !self.is_tool_allowed(name) {
+            tracing::warn!(tool = %name, "Tool call outside the allowlist refused");
+            let allowed = self.config.allowed_tools.as_deref().unwrap_or_default().join(", ");
+            return ToolRun::skipped(format!(
+                "Tool '{}' was not executed, it is not allowed for this mission. Allowed tools: {}",
+                name, allowed
+            ));
+        }
+        if let Some(reason) = self.policy_denial(name, args) {
+            tracing::warn!(tool = %name, reason = %reason, "Tool call denied by security policy");
+            return ToolRun::skipped(format!(
+                "Tool '{}' was not executed, it was denied by the security policy: {}",
+                name, reason
+            ));
+        }
+        if self.config.dry_run {
+            tracing::info!(tool = %name, args = %args, "Dry run: skipping tool call");
+            return ToolRun::skipped(format!("[DRY RUN] would call tool '{}' with args {}", name, args));
+        }
         tracing::info!(tool = %name, "Executing tool call");
 
-        let observation = if let Some(ref tools) = self.tools {
-            match tools.execute(&name, args.clone()).await {
-                Ok(output) => {
-                    if output.success {
-                        format!("Tool '{}' succeeded:\n{}", name, output.content)
-                    } else {
-                        format!("Tool '{}' failed:\n{}", name, output.content)
-                    }
-                }
-                Err(e) => format!("Tool '{}' error: {}", name, e),
-            }
-        } else {
-            format!("Tool '{}' not available (no tools configured)", name)
+        let validation = self.validate_tool_args(name, args).await;
+
+        let mut retry_count = 0;
+        let mut output = None;
+        let observation = if let Err(Error::ToolValidationFailed { ref reason, .. }) = validation {
+            tracing::warn!(tool = %name, problems = %reason, "Invalid tool arguments");
+            format!(
+                "Tool '{}' was not executed, the arguments do not match its schema:\n{}\nFix these fields and call the tool again.",
+                name, reason
+            )
+        } else if let Some(reason) = self.approval_denial(name, args).await {
+            format!("Tool '{}' was not executed, approval was denied: {}", name, reason)
+        } else if let Some(ref tools) = self.tools {
+            let mut result = self.execute_tool(tools.as_ref(), name, args.clone(), events).await;
+            while let Err(ref e) = result {
+                if retry_count >= self.config.max_tool_retries || matches!(e, Error::ToolNotFound(_)) {
+                    break;
+                }
+                retry_count += 1;
+                tracing::warn!(tool = %name, attempt = retry_count, error = %e, "Retrying tool call");
+                result = self.execute_tool(tools.as_ref(), name, args.clone(), events).await;
+            }
+            if retry_count > 0 {
+                multi_agent_governance::track_tool_retries(name, retry_count);
+            }
+
+            match result {
+                Ok(result) => {
+                    let result = self.offload_tool_output(name, result).await;
+                    let observation = if result.success {
+                        format!("Tool '{}' succeeded:\n{}", name, result.content)
+                    } else {
+                        format!("Tool '{}' failed:\n{}", name, result.content)
+                    };
+                    output = Some(result);
+                    observation
+                }
+                Err(Error::ToolExecutionFailed { source, .. }) => format!("Tool '{}' error: {}", name, source),
+                Err(e) => format!("Tool '{}' error: {}", name, e),
+            }
+        } else {
+            format!("Tool '{}' not available (no tools configured)", name)
+        };
+        ToolRun {
+            observation: self.truncate_observation(self.compress_observation(observation).await),
+            retry_count,
+            output,
+        }
+    }
+
+    /// Reason the first capability denying the call gives, if any.
+    fn policy_denial(&self, name: &str, args: &serde_json::Value) -> Option<String> {
+        self.capabilities.iter().find_map(|cap| match cap.check_tool_call(name, args) {
+            PolicyDecision::Allow => None,
+            PolicyDecision::Deny { reason } => Some(reason),
+        })
+    }
+
+    /// Validate `args` against the tool's schema, failing with `Error::ToolValidationFailed`.
+    async fn validate_tool_args(&self, name: &str, args: &serde_json::Value) -> Result<()> {
+        let Some(validator) = self.tool_validator(name) else {
+            return Ok(());
+        };
+        match invalid_tool_args(&validator, args) {
+            Some(problems) => Err(Error::tool_validation_failed(name, problems)),
+            None => Ok(()),
+        }
+    }
+
+    /// Ask the approval hook about a call to a tool requiring approval.
+    ///
+    /// Returns the denial reason, or None if the call may run.
+    async fn approval_denial(&self, name: &str, args: &serde_json::Value) -> Option<String> {
+        if !self.config.requires_approval.contains(name) {
+            return None;
+        }
+        let decision = match self.approval_hook {
+            Some(ref hook) => hook.approve(name, args).await,
+            None => ApprovalDecision::deny("no approval hook is configured"),
+        };
+        match decision {
+            ApprovalDecision::Approve => {
+                tracing::info!(tool = %name, "Tool call approved");
+                None
+            }
+            ApprovalDecision::Deny { reason } => {
+                tracing::warn!(tool = %name, reason = %reason, "Tool call denied");
+                Some(reason)
+            }
+        }
+    }
+
+    /// Execute a tool, enforcing `tool_timeout` if configured.
+    ///
+    /// Tools that support streaming run through `ToolRegistry::execute_streaming`.
+    async fn execute_tool(
+        &self,
+        tools: &dyn ToolRegistry,
+        name: &str,
+        args: serde_json::Value,
+        events: Option<&mpsc::UnboundedSender<ReActEvent>>,
+    ) -> Result<ToolOutput> {
+        let start = Instant::now();
+        let run = async {
+            if self.supports_streaming(name) {
+                execute_streamed(tools, name, args, events).await
+            } else {
+                tools.execute(name, args).await
+            }
+        };
+        let result = match self.config.tool_timeout {
+            Some(limit) => tokio::time::timeout(limit, run)
+                .await
+                .unwrap_or_else(|_| Err(Error::Timeout(format!("Tool '{}' exceeded {:?}", name, limit)))),
+            None => run.await,
+        };
+        // Keep errors the caller reacts to specifically, attribute the rest to the tool
+        let result = result.map_err(|e| match e {
+            Error::ToolNotFound(_) | Error::Timeout(_) | Error::ToolExecutionFailed { .. } => e,
+            e => Error::tool_execution_failed(name, e),
+        });
+
+        let mut metrics = self.tool_metrics.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = metrics.entry(name.to_string()).or_default();
+        entry.calls += 1;
+        entry.failures += u64::from(!matches!(result, Ok(ref output) if output.success));
+        entry.total_duration += start.elapsed();
+
+        result
+    }
+
+    /// Execution counters per tool name, accumulated across all runs of this controller.
+    pub fn tool_metrics(&self) -> HashMap<String, ToolMetrics> {
+        self.tool_metrics.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Whether the same call was among the last `repeat_detection_window` tool calls.
+    fn is_repeated_tool_call(&self, session: &Session, name: &str, args: &serde_json::Value) -> bool {
+        let hash = tool_call_hash(name, args);
+        session
+            .history
+            .iter()
+            .rev()
+            .filter_map(|entry| entry.tool_call.as_ref())
+            .take(self.config.repeat_detection_window)
+            .any(|call| tool_call_hash(&call.name, &call.arguments) == hash)
+    }
+
+    /// Append a tool observation to the session and update tool usage tracking.
+    fn record_tool_result(
+        &self,
+        session: &mut Session,
+        name: String,
+        args: serde_json::Value,
+        observation: String,
+        retry_count: u32,
+        state: &LoopState,
+    ) {
+        state.emit(ReActEvent::ToolResult {
+            name: name.clone(),
+            output: observation.clone(),
+        });
+
+        let repeated = self.is_repeated_tool_call(session, &name, &args);
+        session.history.push(HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new(format!("OBSERVATION: {}", observation)),
+            tool_call: Some(ToolCallInfo {
+                name: name.clone(),
+                arguments: args,
+                result: Some(Arc::new(observation.clone())),
+                retry_count,
+            }),
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
+
+        let mut overuse = None;
+        if let Some(ref mut task_state) = session.task_state {
+            task_state.observe(Some(&name), observation);
+
+            let frequency = task_state.tool_call_frequency.entry(name.clone()).or_insert(0);
+            *frequency += 1;
+            if *frequency > self.config.max_single_tool_frequency {
+                overuse = Some(*frequency);
+            }
+        }
+
+        if repeated {
+            tracing::warn!(tool = %name, "repeated_tool_call_detected");
+            session.history.push(HistoryEntry {
+                role: "user".to_string(),
+                content: Arc::new(format!(
+                    "You just called '{}' with the same arguments and got the result above. Repeating the call will not help; try something else, or provide your FINAL ANSWER.",
+                    name
+                )),
+                tool_call: None,
+                timestamp: chrono_timestamp(),
+                compressed_from: None,
+                images: Vec::new(),
+            });
+        }
+
+        if let Some(n) = overuse {
+            tracing::warn!(tool = %name, frequency = n, "tool_overuse_detected");
+            session.history.push(HistoryEntry {
+                role: "user".to_string(),
+                content: Arc::new(format!(
+                    "You have called '{}' {} times. Try a different tool or approach, or provide your FINAL ANSWER if the task cannot be completed.",
+                    name, n
+                )),
+                tool_call: None,
+                timestamp: chrono_timestamp(),
+                compressed_from: None,
+                images: Vec::new(),
+            });
+        }
+    }
+
+    /// IDs of sessions with a running ReAct loop.
+    pub fn active_sessions(&self) -> Vec<String> {
+        self.lock_active_sessions().keys().cloned().collect()
+    }
+
+    fn lock_active_sessions(&self) -> std::sync::MutexGuard<'_, HashMap<String, CancellationToken>> {
+        self.active_sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run the ReAct loop for a session, cancellable via `Controller::cancel`.
+    async fn run_loop(
+        &self,
+        session: &mut Session,
+        start_iteration: usize,
+        events: Option<mpsc::UnboundedSender<ReActEvent>>,
+    ) -> Result<AgentResult> {
+        let token = CancellationToken::new();
+        self.lock_active_sessions().insert(session.id.clone(), token.clone());
+
+        let result = match self.config.mission_timeout {
+            Some(limit) => {
+                let started = Instant::now();
+                let run = self.run_iterations(session, start_iteration, &token, events);
+                match tokio::time::timeout(limit, run).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        let elapsed = started.elapsed();
+                        tracing::warn!(session_id = %session.id, elapsed = ?elapsed, "Mission timed out");
+                        session.status = SessionStatus::Failed;
+                        session.updated_at = chrono_timestamp();
+                        self.persist_session(session).await;
+                        Err(Error::MissionTimeout { elapsed })
+                    }
+                }
+            }
+            None => self.run_iterations(session, start_iteration, &token, events).await,
+        };
+
+        self.lock_active_sessions().remove(&session.id);
+        tracing::info!(
+            session_id = %session.id,
+            duration_secs = session.duration_secs(),
+            iterations = session.iteration_durations_ms.len(),
+            iteration_ms = ?session.iteration_durations_ms,
+            slowest_iteration = ?session.slowest_iteration(),
+            "Mission timing"
+        );
+        if let Some(cost) = self.estimated_cost_usd(&session.token_usage) {
+            tracing::info!(
+                session_id = %session.id,
+                tokens_used = session.token_usage.total_tokens,
+                cost_usd = cost,
+                "Estimated mission cost"
+            );
+        }
+        result
+    }
+
+    /// Estimate the cost of `usage` from the configured pricing (None if unpriced).
+    fn estimated_cost_usd(&self, usage: &TokenUsage) -> Option<f64> {
+        self.config.pricing.as_ref()?.cost_usd(self.config.pricing_tier, usage)
+    }
+
+    /// Run ReAct iterations for a session, starting at `start_iteration`.
+    async fn run_iterations(
+        &self,
+        session: &mut Session,
+        start_iteration: usize,
+        token: &CancellationToken,
+        events: Option<mpsc::UnboundedSender<ReActEvent>>,
+    ) -> Result<AgentResult> {
+        tracing::info!(
+            session_id = %session.id,
+            trace_id = session.trace_id.as_deref(),
+            start_iteration = start_iteration,
+            "Starting/Resuming ReAct loop"
+        );
+
+        // Restore counters from history when resuming
+        let mut state = LoopState {
+            total_tool_calls: session.history.iter().filter(|e| e.tool_call.is_some()).count(),
+            tool_limit_reached: session
+                .history
+                .iter()
+                .any(|e| e.role == "user" && e.content.as_str() == MAX_TOOL_CALLS_MESSAGE),
+            reflections: 0,
+            consecutive_thoughts: 0,
+            empty_responses: 0,
+            events,
+        };
+
+        // Calls parsed before an interruption run before the LLM is asked again
+        if let Some(result) = self.run_pending_actions(session, &mut state).await? {
+            session.updated_at = chrono_timestamp();
+            session.status = SessionStatus::Completed;
+            self.persist_session(session).await;
+            return Ok(result);
+        }
+
+        for iteration in start_iteration..self.config.max_iterations {
+            if token.is_cancelled() {
+                tracing::info!(session_id = %session.id, iteration = iteration, "Session cancelled");
+                session.status = SessionStatus::Cancelled;
+                session.updated_at = chrono_timestamp();
+                self.persist_session(session).await;
+                return Err(Error::Cancelled);
+            }
+
+            if let Some(ref mut task_state) = session.task_state {
+                task_state.iteration = iteration;
+            }
+
+            let started = Instant::now();
+            let outcome = self.execute_iteration(session, iteration, &mut state).await;
+            session
+                .iteration_durations_ms
+                .push(started.elapsed().as_millis() as u64);
+
+            let outcome = match outcome {
+                Err(Error::BudgetExceeded { .. }) if self.config.graceful_budget_exhaustion => {
+                    return self.land_on_budget(session).await;
+                }
+                Err(e @ Error::BudgetExceeded { .. }) => {
+                    session.status = SessionStatus::Failed;
+                    self.persist_session(session).await;
+                    return Err(e);
+                }
+                outcome => outcome?,
+            };
+
+            match outcome {
+                Some(result) => {
+                    session.updated_at = chrono_timestamp();
+                    session.status = SessionStatus::Completed;
+                    self.persist_session(session).await;
+                    return Ok(result);
+                }
+                None => {
+                    if !state.tool_limit_reached
+                        && state.total_tool_calls >= self.config.max_total_tool_calls
+                    {
+                        tracing::warn!(
+                            session_id = %session.id,
+                            total_tool_calls = state.total_tool_calls,
+                            "Maximum tool calls reached"
+                        );
+                        session.history.push(HistoryEntry {
+                            role: "user".to_string(),
+                            content: Arc::new(MAX_TOOL_CALLS_MESSAGE.to_string()),
+                            tool_call: None,
+                            timestamp: chrono_timestamp(),
+                            compressed_from: None,
+                            images: Vec::new(),
+                        });
+                        state.tool_limit_reached = true;
+                    }
+
+                    if let Some(max_entries) = self.config.max_history_entries {
+                        let dropped = session.prune_history(max_entries);
+                        if dropped > 0 {
+                            tracing::debug!(session_id = %session.id, dropped = dropped, "Pruned session history");
+                        }
+                    }
+
+                    session.updated_at = chrono_timestamp();
+                    self.persist_session(session).await;
+
+                    if session.token_usage.is_exceeded() {
+                        if self.config.graceful_budget_exhaustion {
+                            return self.land_on_budget(session).await;
+                        }
+                        session.status = SessionStatus::Failed;
+                        // Persist failure state
+                        self.persist_session(session).await;
+                        return Err(Error::BudgetExceeded {
+                            used: session.token_usage.total_tokens,
+                            limit: session.token_usage.budget_limit,
+                        });
+                    }
+                    continue;
+                }
+            }
+        }
+
+        if self.config.answer_on_max_iterations {
+            match self.summarize_at_max_iterations(session).await {
+                Ok(result) => {
+                    session.updated_at = chrono_timestamp();
+                    session.status = SessionStatus::Completed;
+                    self.persist_session(session).await;
+                    return Ok(result);
+                }
+                Err(e @ Error::ContentViolation { .. }) => {
+                    session.status = SessionStatus::Failed;
+                    self.persist_session(session).await;
+                    return Err(e);
+                }
+                Err(e) => {
+                    tracing::warn!(session_id = %session.id, error = %e, "Best-effort answer failed");
+                }
+            }
+        }
+
+        session.status = SessionStatus::Failed;
+        self.persist_session(session).await;
+        Err(Error::MaxIterationsExceeded(self.config.max_iterations))
+    }
+
+    /// End a mission whose budget ran out with the observations collected so far.
+    ///
+    /// Makes no LLM call; the observations are listed as they were recorded,
+    /// each with the tool and iteration that produced it. The text goes through
+    /// the same output checks as any other final answer.
+    async fn land_on_budget(&self, session: &mut Session) -> Result<AgentResult> {
+        let observations: Vec<String> = session
+            .task_state
+            .iter()
+            .flat_map(|state| &state.observations)
+            .enumerate()
+            .map(|(i, observation)| format!("{}. {}", i + 1, observation))
+            .collect();
+        tracing::warn!(
+            session_id = %session.id,
+            used = session.token_usage.total_tokens,
+            limit = session.token_usage.budget_limit,
+            observations = observations.len(),
+            "Token budget exhausted, returning partial results"
+        );
+
+        let text = if observations.is_empty() {
+            "Token budget exhausted before the task was completed. No results were collected.".to_string()
+        } else {
+            format!(
+                "Token budget exhausted before the task was completed. Partial results:\n{}",
+                observations.join("\n")
+            )
+        };
+        let action = ReActAction::FinalAnswer(text.clone());
+        let checked = self.check_final_answer(&action, &text, session).await;
+
+        session.updated_at = chrono_timestamp();
+        session.status = match checked {
+            Ok(_) => SessionStatus::BudgetExhausted,
+            Err(_) => SessionStatus::Failed,
+        };
+        self.persist_session(session).await;
+
+        Ok(checked?.unwrap_or(AgentResult::Text(text)))
+    }
+
+    /// Make one final LLM call asking for the best answer given the observations so far.
+    ///
+    /// The answer goes through the same output checks as a regular final answer.
+    async fn summarize_at_max_iterations(&self, session: &mut Session) -> Result<AgentResult> {
+        let llm = self.select_llm(session)?;
+        tracing::info!(session_id = %session.id, "Max iterations reached, requesting best-effort answer");
+
+        session.history.push(HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new(MAX_ITERATIONS_MESSAGE.to_string()),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
+
+        let messages = self.build_messages(session);
+        let response = self.call_llm(llm.as_ref(), session, &messages).await?;
+
+        let answer = response.content.trim();
+        let answer = answer.strip_prefix("FINAL ANSWER:").unwrap_or(answer).trim().to_string();
+        session.history.push(HistoryEntry {
+            role: "assistant".to_string(),
+            content: Arc::new(response.content),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
+
+        let action = ReActAction::FinalAnswer(answer.clone());
+        if let Some(blocked) = self.check_final_answer(&action, &answer, session).await? {
+            return Ok(blocked);
+        }
+        Ok(AgentResult::Text(answer))
+    }
+}
+
+impl ReActController {
+    /// Execute an intent, yielding progress events as the ReAct loop runs.
+    ///
+    /// The stream ends with `ReActEvent::FinalAnswer` or `ReActEvent::Error`.
+    /// Streaming runs do not fail over to the fallback controller.
+    pub fn execute_streaming(&self, intent: UserIntent) -> impl Stream<Item = ReActEvent> + Send + '_ {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let run = async move {
+            let last = match self.execute_primary(intent, Some(tx.clone())).await {
+                Ok(result) => ReActEvent::FinalAnswer(result),
+                Err(e) => ReActEvent::Error(e.to_string()),
+            };
+            let _ = tx.send(last);
+        };
+
+        // Drive the run alongside the receiver; the stream ends once the run
+        // finishes and all of its events have been yielded
+        let events = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        });
+        futures::stream::select(events, futures::stream::once(run).filter_map(|()| async { None }))
+    }
+
+    /// Execute an intent on this controller, without failover.
+    async fn execute_primary(
+        &self,
+        intent: UserIntent,
+        events: Option<mpsc::UnboundedSender<ReActEvent>>,
+    ) -> Result<AgentResult> {
+        self.ensure_tools_preloaded().await?;
+
+        match intent {
+            UserIntent::FastAction { tool_name, args } => {
+                self.refresh_tool_definitions().await;
+                self.validate_fast_action_security(&args).await?;
+                self.validate_tool_args(&tool_name, &args).await?;
+
+                // Fast path: direct tool execution
+                tracing::info!(tool = %tool_name, "Fast path execution");
+
+                if self.config.dry_run {
+                    Ok(AgentResult::Text(format!(
+                        "[DRY RUN] would call tool '{}' with args {}",
+                        tool_name, args
+                    )))
+                } else if let Some(reason) = self.policy_denial(&tool_name, &args) {
+                    tracing::warn!(tool = %tool_name, reason = %reason, "Tool call denied by security policy");
+                    Ok(AgentResult::Error {
+                        message: format!(
+                            "Tool '{}' was not executed, it was denied by the security policy: {}",
+                            tool_name, reason
+                        ),
+                        code: "POLICY_DENIED".to_string(),
+                    })
+                } else if let Some(reason) = self.approval_denial(&tool_name, &args).await {
+                    Ok(AgentResult::Error {
+                        message: format!("Tool '{}' was not executed, approval was denied: {}", tool_name, reason),
+                        code: "APPROVAL_DENIED".to_string(),
+                    })
+                } else if let Some(ref tools) = self.tools {
+                    match self.execute_tool(tools.as_ref(), &tool_name, args, None).await {
+                        Ok(output) => {
+                            if output.success {
+                                Ok(AgentResult::Text(output.content))
+                            } else {
+                                Ok(AgentResult::Error {
+                                    message: output.content,
+                                    code: "TOOL_ERROR".to_string(),
+                                })
+                            }
+                        }
+                        Err(e) => Ok(AgentResult::Error {
+                            code: match e {
+                                Error::ToolExecutionFailed { .. } => "TOOL_ERROR",
+                                _ => "TOOL_NOT_FOUND",
+                            }
+                            .to_string(),
+                            message: e.to_string(),
+                        }),
+                    }
+                } else {
+                    Ok(AgentResult::Text(format!(
+                        "Fast path: would execute tool '{}'. Tools not configured.",
+                        tool_name
+                    )))
+                }
+            }
+
+            UserIntent::ComplexMission {
+                goal,
+                context_summary,
+                visual_refs,
+                trace_id,
+                user_id,
+            } => {
+                self.refresh_tool_definitions().await;
+                let mut session = self.create_mission_session(&goal, trace_id, user_id).await;
+                
+                // v0.3: Capability On-Start Hook
+                for cap in &self.capabilities {
+                    cap.on_start(&mut session).await.map_err(|e| Error::controller(e.to_string()))?;
+                }
+
+                // Add user context to history, with visual refs as structured image content
+                let user_message = context_summary.clone();
+                self.moderate(&user_message, ModerationDirection::Input).await?;
+
+                session.history.push(HistoryEntry {
+                    role: "user".to_string(),
+                    content: Arc::new(user_message),
+                    tool_call: None,
+                    timestamp: chrono_timestamp(),
+                    compressed_from: None,
+                    images: visual_refs.iter().map(RefId::from_string).collect(),
+                });
+                
+                for cap in &self.capabilities {
+                     cap.on_pre_reasoning(&mut session).await.map_err(|e| Error::controller(e.to_string()))?;
+                }
+
+                if self.config.auto_decompose {
+                    self.decompose_and_delegate(&mut session, &goal).await;
+                }
+
+                tracing::info!(
+                    goal = %goal,
+                    context_len = context_summary.len(),
+                    refs_count = visual_refs.len(),
+                    "Starting ReAct loop"
+                );
+
+                self.run_loop(&mut session, 0, events).await
+            }
+
+            UserIntent::Schedule { .. } => Err(Error::invalid_request(
+                "Schedule intents must be registered with a ScheduledController",
+            )),
+
+            UserIntent::ScheduledMission { goal, cron, context_summary } => {
+                self.schedule_mission(goal, cron, context_summary).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Controller for ReActController {
+
+    async fn execute(&self, intent: UserIntent) -> Result<AgentResult> {
+        let Some(ref fallback) = self.fallback else {
+            return self.execute_primary(intent, None).await;
+        };
+
+        match self.execute_primary(intent.clone(), None).await {
+            Err(e) if is_provider_unavailable(&e) => {
+                tracing::warn!(error = %e, "failing_over_to_fallback");
+                self.fallback_invocations.fetch_add(1, Ordering::Relaxed);
+                fallback.execute(intent).await
+            }
+            result => result,
+        }
+    }
+
+
+
+    async fn resume(&self, session_id: &str) -> Result<AgentResult> {
+        let session_store = self.session_store.as_ref().ok_or_else(|| {
+             Error::controller("State persistence not configured (session_store is None)")
+        })?;
+
+        // Load session
+        let mut session = session_store.load(session_id).await?
+            .ok_or_else(|| Error::controller(format!("Session {} not found", session_id)))?;
+
+        tracing::info!(session_id = %session_id, status = ?session.status, "Resuming session");
+
+        match session.status {
+            SessionStatus::Completed => {
+                // Return the last assistant answer
+                let answer = session
+                    .history
+                    .iter()
+                    .rev()
+                    .find(|h| h.role == "assistant")
+                    .map(|h| {
+                        let content = h.content.trim();
+                        content.strip_prefix("FINAL ANSWER:").unwrap_or(content).trim().to_string()
+                    })
+                    .unwrap_or_else(|| "Task completed (no content)".to_string());
+
+                Ok(AgentResult::Text(answer))
+            }
+            SessionStatus::Failed => {
+                Err(Error::controller("Cannot resume failed session"))
+            }
+            SessionStatus::Cancelled => {
+                Err(Error::controller("Cannot resume cancelled session"))
+            }
+            SessionStatus::BudgetExhausted => {
+                Err(Error::controller("Cannot resume session that exhausted its token budget"))
+            }
+            SessionStatus::Running | SessionStatus::Paused => {
+                // Sessions are persisted after each completed iteration, so continue
+                // with the next one once the LLM has responded at least once
+                let iteration = session.task_state.as_ref().map(|t| t.iteration).unwrap_or(0);
+                let started = session.history.iter().any(|h| h.role == "assistant");
+                let start_iteration = if started { iteration + 1 } else { iteration };
+
+                session.status = SessionStatus::Running;
+                self.run_loop(&mut session, start_iteration, None).await
+            }
+        }
+    }
+
+    async fn cancel(&self, session_id: &str) -> Result<()> {
+        tracing::info!(session_id = session_id, "Cancel requested");
+
+        // The loop stops at the start of its next iteration
+        let token = self
+            .lock_active_sessions()
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| Error::controller(format!("Session {} is not running", session_id)))?;
+        token.cancel();
+        Ok(())
+    }
+}
+
+/// Get current timestamp.
+pub fn chrono_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Run a streaming tool, forwarding each chunk to `events` and returning the accumulated output.
+async fn execute_streamed(
+    tools: &dyn ToolRegistry,
+    name: &str,
+    args: serde_json::Value,
+    events: Option<&mpsc::UnboundedSender<ReActEvent>>,
+) -> Result<ToolOutput> {
+    let mut chunks = tools.execute_streaming(name, args).await?;
+    let mut content = String::new();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        if let Some(events) = events {
+            // The receiver may have been dropped; the run continues regardless
+            let _ = events.send(ReActEvent::ToolOutputChunk {
+                name: name.to_string(),
+                chunk: chunk.clone(),
+            });
+        }
+        content.push_str(&chunk);
+    }
+    Ok(ToolOutput::text(content))
+}
+
+/// Validate `args` against a tool's parameter schema, describing any violations.
+///
+/// Returns `None` when the arguments are valid.
+fn invalid_tool_args(validator: &jsonschema::Validator, args: &serde_json::Value) -> Option<String> {
+    let problems: Vec<String> = validator
+        .iter_errors(args)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                format!("- {}", e)
+            } else {
+                format!("- {}: {}", path, e)
+            }
+        })
+        .collect();
+
+    (!problems.is_empty()).then(|| problems.join("\n"))
+}
+
+/// Check if an error means the LLM provider could not be reached.
+fn is_provider_unavailable(error: &Error) -> bool {
+    matches!(error, Error::ModelProvider(_) | Error::AllProvidersUnavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_final_answer() {
+        let controller = ReActController::new(ReActConfig::default());
+        
+        let response = "FINAL ANSWER: The result is 42.";
+        let action = controller.parse_action(response);
+        
+        match action {
+            ReActAction::FinalAnswer(answer) => {
+                assert_eq!(answer, "The result is 42.");
+            }
+            _ => panic!("Expected FinalAnswer"),
+        }
+    }
+
+    #[test]
+    fn test_parse_tool_call() {
+        let controller = ReActController::new(ReActConfig::default());
+        
+        let response = r#"THOUGHT: I need to calculate something.
+ACTION: calculator
+ARGS: {"operation": "add", "a": 5, "b": 3}"#;
+        
+        let action = controller.parse_action(response);
+        
+        match action {
+            ReActAction::ToolCall { name, args } => {
+                assert_eq!(name, "calculator");
+                assert_eq!(args["operation"], "add");
+            }
+            _ => panic!("Expected ToolCall, got {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_parse_thought() {
+        let controller = ReActController::new(ReActConfig::default());
+        
+        let response = "THOUGHT: I need to think about this more.";
+        let action = controller.parse_action(response);
+        
+        match action {
+            ReActAction::Think(thought) => {
+                assert!(thought.contains("think about"));
+            }
+            _ => panic!("Expected Think"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fast_action() {
+        let controller = ReActController::new(ReActConfig::default());
+
+        let intent = UserIntent::FastAction {
+            tool_name: "test_tool".to_string(),
+            args: serde_json::json!({"query": "test"}),
+        };
+
+        let result = controller.execute(intent).await.unwrap();
+        match result {
+            AgentResult::Text(text) => {
+                assert!(text.contains("test_tool"));
+            }
+            _ => panic!("Expected Text result"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_complex_mission_mock() {
+        let controller = ReActController::new(ReActConfig::default());
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: "Test context".to_string(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        let result = controller.execute(intent).await.unwrap();
+        match result {
+            AgentResult::Text(text) => {
+                assert!(text.contains("Mock ReAct"));
+            }
+            _ => panic!("Expected Text result"),
+        }
+    }
+
+    struct CountingLimiter(std::sync::atomic::AtomicU32);
+
+    #[async_trait]
+    impl RateLimiter for CountingLimiter {
+        async fn acquire(&self, tokens: u32) -> Result<()> {
+            self.0.fetch_add(tokens, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_acquired_per_llm_call() {
+        let limiter = Arc::new(CountingLimiter(std::sync::atomic::AtomicU32::new(0)));
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            "THOUGHT: Let me think.".to_string(),
+            "FINAL ANSWER: Done".to_string(),
+        ]));
+
+        let controller = ReActController::builder()
+            .with_llm(llm)
+            .with_rate_limiter(limiter.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        controller.execute(intent).await.unwrap();
+        assert_eq!(limiter.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_max_total_tool_calls_exceeded() {
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            r#"ACTION: search
+ARGS: {"q": "1"}"#.to_string(),
+            r#"ACTION: search
+ARGS: {"q": "2"}"#.to_string(),
+            r#"ACTION: search
+ARGS: {"q": "3"}"#.to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_total_tool_calls: 2,
+                persist_state: false,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        match controller.execute(intent).await {
+            Err(Error::MaxToolCallsExceeded(max)) => assert_eq!(max, 2),
+            other => panic!("Expected MaxToolCallsExceeded, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_total_tool_calls_allows_final_answer() {
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            r#"ACTION: search
+ARGS: {"q": "1"}"#.to_string(),
+            "FINAL ANSWER: Best effort".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_total_tool_calls: 1,
+                persist_state: false,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        match controller.execute(intent).await.unwrap() {
+            AgentResult::Text(text) => assert_eq!(text, "Best effort"),
+            other => panic!("Expected Text result, got {:?}", other),
+        }
+    }
+
+    struct ReasoningLlm;
+
+    #[async_trait]
+    impl LlmClient for ReasoningLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            Ok(LlmResponse {
+                content: "FINAL ANSWER: 4".to_string(),
+                finish_reason: "stop".to_string(),
+                usage: Default::default(),
+                tool_calls: None,
+                reasoning: Some("2 + 2 is 4".to_string()),
+            })
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_entry_is_hidden_but_retrievable() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(ReasoningLlm))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Add numbers".to_string(),
+            context_summary: "What is 2 + 2?".to_string(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let result = controller.execute(intent).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        let reasoning = session.reasoning_entries();
+        assert_eq!(reasoning.len(), 1);
+        assert_eq!(reasoning[0].content.as_str(), "2 + 2 is 4");
+
+        let explained = controller.explain_result(&session.id, result).await.unwrap();
+        assert_eq!(explained.reasoning_trace, vec!["2 + 2 is 4".to_string()]);
+
+        let messages = ReActController::build_messages_static(&session);
+        assert!(messages.iter().all(|m| m.role != REASONING_ROLE));
+        assert!(messages.iter().all(|m| !m.content.contains("2 + 2 is 4")));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_trace_id_is_persisted_with_the_session() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::constant("FINAL ANSWER: done")))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Summarize".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: Some("trace-123".to_string()),
+            user_id: Some("user-7".to_string()),
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.trace_id.as_deref(), Some("trace-123"));
+        assert_eq!(session.metadata.user_id.as_deref(), Some("user-7"));
+        assert!(logs_contain("trace_id=\"trace-123\""));
+    }
+
+    #[tokio::test]
+    async fn test_stop_condition_ends_mission_on_tool_result() {
+        use multi_agent_core::mocks::{MockLlm, MockToolRegistry, RecordingTool};
+
+        let llm = Arc::new(MockLlm::new(vec![
+            "ACTION: search\nARGS: {}".to_string(),
+            "ACTION: finish\nARGS: {\"report\": \"done\"}".to_string(),
+            "ACTION: search\nARGS: {}".to_string(),
+        ]));
+        let search = Arc::new(RecordingTool::new("search", "Searches", "results"));
+        let finish = Arc::new(RecordingTool::new("finish", "Submits the report", "report submitted"));
+        let controller = ReActController::builder()
+            .with_llm(llm.clone())
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![search.clone(), finish.clone()])))
+            .with_stop_condition(Arc::new(crate::stop::ToolResultStop::new("finish")))
+            .build().unwrap();
+
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "report submitted"));
+        assert_eq!(llm.call_count(), 2);
+        assert_eq!(search.calls().len(), 1);
+        assert_eq!(finish.calls().len(), 1);
+    }
+
+    async fn run_repeated_lookups(window: usize) -> Session {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                repeat_detection_window: window,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: lookup\nARGS: {\"id\": 1, \"full\": true}".to_string(),
+                "ACTION: lookup\nARGS: {\"full\": true, \"id\": 1}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .with_session_store(store.clone())
+            .build().unwrap();
+        controller.execute(budget_intent()).await.unwrap();
+        store.sessions().pop().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_identical_consecutive_tool_calls_are_flagged() {
+        let is_warning = |e: &HistoryEntry| e.content.contains("with the same arguments");
+
+        let session = run_repeated_lookups(1).await;
+        let warnings: Vec<_> = session.history.iter().filter(|e| is_warning(e)).collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].content.contains("'lookup'"));
+        // The warning follows the repeated call's observation
+        let position = session.history.iter().position(is_warning).unwrap();
+        assert!(session.history[position - 1].tool_call.is_some());
+
+        let session = run_repeated_lookups(0).await;
+        assert!(!session.history.iter().any(is_warning));
+    }
+
+    #[tokio::test]
+    async fn test_visual_refs_become_image_content() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::constant("FINAL ANSWER: a cat")))
+            .with_session_store(store.clone())
+            .build()
+            .unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Describe the image".to_string(),
+            context_summary: "What is in this picture?".to_string(),
+            visual_refs: vec!["image_123".to_string()],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        let messages = ReActController::build_messages_static(&session);
+        let user = messages.iter().find(|m| m.role == "user").unwrap();
+        assert_eq!(user.content, "What is in this picture?");
+        assert_eq!(user.images, vec![RefId::from_string("image_123")]);
+        assert!(messages.iter().all(|m| !m.content.contains("image_123")));
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_template_override() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let store = Arc::new(MockSessionStore::new());
+        let tool = Arc::new(RecordingTool::new("lookup", "Looks up a record", "found"));
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::constant("FINAL ANSWER: arr")))
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![tool])))
+            .with_session_store(store.clone())
+            .with_system_prompt_override("You are a pirate. Mission: {goal}\nTools:\n{tools}")
+            .build()
+            .unwrap();
+
+        controller.execute(budget_intent()).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.history[0].role, "system");
+        assert_eq!(
+            session.history[0].content.as_str(),
+            "You are a pirate. Mission: Summarize\nTools:\n- lookup: Looks up a record"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_lists_registered_tools() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let store = Arc::new(MockSessionStore::new());
+        let tool = Arc::new(RecordingTool::new("lookup", "Looks up a record", "found"));
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::constant("FINAL ANSWER: done")))
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![tool])))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        controller.execute(budget_intent()).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        let prompt = &session.history[0].content;
+        assert!(prompt.contains("- lookup: Looks up a record"), "{}", prompt);
+        assert!(!prompt.contains("Tools will be loaded"));
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_tool_call_is_refused() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let store = Arc::new(MockSessionStore::new());
+        let search = Arc::new(RecordingTool::new("search", "Searches the web", "results"));
+        let delete = Arc::new(RecordingTool::new("delete_file", "Deletes a file", "deleted"));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                allowed_tools: Some(vec!["search".to_string()]),
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: delete_file\nARGS: {\"path\": \"a.txt\"}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![search.clone(), delete.clone()])))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        controller.execute(budget_intent()).await.unwrap();
+        assert!(delete.calls().is_empty());
+
+        let session = store.sessions().pop().unwrap();
+        let prompt = &session.history[0].content;
+        assert!(prompt.contains("- search: Searches the web"));
+        assert!(!prompt.contains("delete_file"));
+
+        let observation = session.history.iter().find(|e| e.tool_call.is_some()).unwrap();
+        assert!(
+            observation.content.contains("Tool 'delete_file' was not executed, it is not allowed"),
+            "{}",
+            observation.content
+        );
+    }
+
+    #[test]
+    fn test_parameter_summary() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer"},
+                "verbose": {"type": "boolean"}
+            },
+            "required": ["id"]
+        });
+        assert_eq!(parameter_summary(&schema), " (args: id: integer (required), verbose: boolean)");
+        assert_eq!(parameter_summary(&serde_json::json!({"type": "object", "properties": {}})), "");
+    }
+
+    #[tokio::test]
+    async fn test_tool_observations_become_tool_messages() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: lookup\nARGS: {\"id\": 1}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        controller.execute(budget_intent()).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        let messages = ReActController::build_messages_static(&session);
+        let tool_messages: Vec<_> = messages.iter().filter(|m| m.role == "tool").collect();
+        assert_eq!(tool_messages.len(), 1);
+        assert!(tool_messages[0].content.starts_with("Tool 'lookup' succeeded"), "{}", tool_messages[0].content);
+        assert!(messages
+            .iter()
+            .filter(|m| m.role == "user")
+            .all(|m| !m.content.starts_with("OBSERVATION:")));
+    }
+
+    #[tokio::test]
+    async fn test_explain_token_usage() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(ReasoningLlm))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Add numbers".to_string(),
+            context_summary: "What is 2 + 2?".to_string(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        let explanation = controller.explain_token_usage(&session.id).await.unwrap();
+
+        let expected: u64 = session.history.iter().map(|e| (e.content.len() / 4) as u64).sum();
+        assert_eq!(explanation.total_tokens, expected);
+        assert_eq!(explanation.by_role.values().sum::<u64>(), expected);
+        assert_eq!(explanation.by_iteration, vec![expected]);
+        assert_eq!(explanation.compression_events, 0);
+        assert!(explanation.top_entries.len() <= 10);
+        assert!(explanation.top_entries.windows(2).all(|w| w[0].1 >= w[1].1));
+        // The system prompt dominates this short session
+        assert_eq!(explanation.top_entries[0].0, 0);
+
+        assert!(controller.explain_token_usage("missing").await.is_err());
+    }
+
+    #[test]
+    fn test_estimated_cost_uses_pricing_tier() {
+        let mut usage = TokenUsage::default();
+        usage.add(3000, 1000);
+
+        assert!(ReActController::new(ReActConfig::default()).estimated_cost_usd(&usage).is_none());
+
+        let controller = ReActController::new(ReActConfig {
+            pricing: Some(
+                ModelPricing::new()
+                    .with_tier(ModelTier::Fast, 0.001, 0.002)
+                    .with_tier(ModelTier::Premium, 0.01, 0.03),
+            ),
+            pricing_tier: ModelTier::Premium,
+            ..Default::default()
+        });
+        // 3 * 0.01 + 1 * 0.03
+        let cost = controller.estimated_cost_usd(&usage).unwrap();
+        assert!((cost - 0.06).abs() < 1e-9);
+    }
+
+    /// Reports prompt usage as a tokenizer would: over the concatenated prompt.
+    struct UsageReportingLlm;
+
+    #[async_trait]
+    impl LlmClient for UsageReportingLlm {
+        async fn complete(&self, prompt: &str) -> Result<LlmResponse> {
+            Ok(LlmResponse {
+                content: "FINAL ANSWER: Done".to_string(),
+                finish_reason: "stop".to_string(),
+                usage: multi_agent_core::traits::LlmUsage {
+                    prompt_tokens: (prompt.len() / 4) as u64,
+                    completion_tokens: 5,
+                    total_tokens: (prompt.len() / 4) as u64 + 5,
+                },
+                tool_calls: None,
+                reasoning: None,
+            })
+        }
+
+        async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse> {
+            let prompt: String = messages.iter().map(|m| m.content.as_str()).collect();
+            self.complete(&prompt).await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_token_estimate_matches_next_call() {
+        let llm = Arc::new(UsageReportingLlm);
+        let controller = ReActController::builder().with_llm(llm.clone()).build().unwrap();
+
+        let mut session = controller.create_session("Summarize the quarterly report");
+        session.history.push(HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new("Revenue grew 12% while costs stayed flat. ".repeat(20)),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        });
+
+        let estimate = controller.live_token_estimate(&session).await.unwrap();
+        let actual = llm
+            .chat(&controller.build_messages(&session))
+            .await
+            .unwrap()
+            .usage
+            .prompt_tokens;
+
+        let diff = estimate.abs_diff(actual) as f64;
+        assert!(diff <= actual as f64 * 0.1, "estimate {} vs actual {}", estimate, actual);
+    }
+
+    #[test]
+    fn test_token_headroom() {
+        let controller = ReActController::new(ReActConfig::default());
+        let mut session = controller.create_session("goal");
+        session.history.push(HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new("x".repeat(4000)),
+            tool_call: None,
+            timestamp: 0,
+            compressed_from: None,
+            images: Vec::new(),
+        });
+
+        let used = session.estimated_context_tokens();
+        assert!(used >= 1000);
+        assert_eq!(session.token_headroom(used + 10), 10);
+        assert!(session.token_headroom(500) < 0);
+    }
+
+    /// Flags any text containing "forbidden".
+    struct KeywordModerator;
+
+    #[async_trait]
+    impl ContentModerator for KeywordModerator {
+        async fn moderate(
+            &self,
+            text: &str,
+            _direction: ModerationDirection,
+        ) -> Result<multi_agent_governance::ModerationResult> {
+            if text.contains("forbidden") {
+                Ok(multi_agent_governance::ModerationResult::flagged(vec!["violence".to_string()], 0.9))
+            } else {
+                Ok(multi_agent_governance::ModerationResult::pass())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_content_moderation_blocks_input() {
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            "FINAL ANSWER: Done".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_llm(llm)
+            .with_content_moderation(Arc::new(KeywordModerator))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: "something forbidden".to_string(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        match controller.execute(intent).await {
+            Err(Error::ContentViolation { categories }) => assert_eq!(categories, vec!["violence"]),
+            other => panic!("Expected ContentViolation, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_content_moderation_blocks_final_answer() {
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            "FINAL ANSWER: a forbidden answer".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_llm(llm)
+            .with_content_moderation(Arc::new(KeywordModerator))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: "hello".to_string(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        let result = controller.execute(intent).await;
+        assert!(matches!(result, Err(Error::ContentViolation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_long_observation_is_summarized() {
+        use crate::context::SummarizationCompressor;
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+        use multi_agent_core::types::RefId;
+
+        let full_output = "row,value\n".repeat(500);
+        let tools = Arc::new(MockToolRegistry::with_tools(vec![Arc::new(RecordingTool::new(
+            "export_table",
+            "Exports a table",
+            &full_output,
+        ))]));
+        let artifacts = Arc::new(multi_agent_store::InMemoryStore::new());
+        let sessions = Arc::new(MockSessionStore::new());
+        let summarizer = SummarizationCompressor::new(MockLlm::constant("A table of 500 rows."));
+
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: export_table\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(tools)
+            .with_store(artifacts.clone())
+            .with_session_store(sessions.clone())
+            .with_compressor(Arc::new(summarizer))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Export the table".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = sessions.sessions().pop().unwrap();
+        let entry = session
+            .history
+            .iter()
+            .find(|e| e.tool_call.is_some())
+            .unwrap();
+        assert!(entry.content.len() < full_output.len());
+        assert!(entry.content.contains("A table of 500 rows."));
+
+        // The full output is retrievable from the referenced artifact
+        let ref_id = entry.content.rsplit("artifact ").next().unwrap().trim_end_matches(']');
+        let stored = artifacts.load(&RefId::from_string(ref_id)).await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&stored).contains(&full_output));
+    }
+
+    #[tokio::test]
+    async fn test_large_tool_output_is_offloaded() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let full_output = "log line\n".repeat(1000);
+        let tools = Arc::new(MockToolRegistry::with_tools(vec![Arc::new(RecordingTool::new(
+            "read_logs",
+            "Reads the logs",
+            &full_output,
+        ))]));
+        let artifacts = Arc::new(multi_agent_store::InMemoryStore::new());
+        let sessions = Arc::new(MockSessionStore::new());
+
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_inline_tool_output: Some(1000),
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: read_logs\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(tools)
+            .with_store(artifacts.clone())
+            .with_session_store(sessions.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Read the logs".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = sessions.sessions().pop().unwrap();
+        let entry = session.history.iter().find(|e| e.tool_call.is_some()).unwrap();
+        assert!(entry.content.len() < 1000);
+        assert!(entry.content.contains("Output saved as RefID: "));
+
+        let ref_id = entry.content.split("RefID: ").nth(1).unwrap().split('.').next().unwrap();
+        let stored = artifacts.load(&RefId::from_string(ref_id)).await.unwrap().unwrap();
+        assert_eq!(String::from_utf8_lossy(&stored), full_output);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_artifact_tool_reads_stored_output() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore};
+
+        let artifacts = Arc::new(multi_agent_store::InMemoryStore::new());
+        let ref_id = artifacts.save(bytes::Bytes::from("stored report")).await.unwrap();
+        let sessions = Arc::new(MockSessionStore::new());
+
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::new(vec![
+                format!("ACTION: fetch_artifact\nARGS: {{\"ref_id\": \"{}\"}}", ref_id),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_store(artifacts)
+            .with_session_store(sessions.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Read the report".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = sessions.sessions().pop().unwrap();
+        assert!(session.history[0].content.contains("- fetch_artifact:"));
+        let entry = session.history.iter().find(|e| e.tool_call.is_some()).unwrap();
+        assert!(entry.content.ends_with("Tool 'fetch_artifact' succeeded:\nstored report"));
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_capability_applies_to_next_run() {
+        let looping = || {
+            Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: search\nARGS: {\"q\": \"x\"}".to_string(),
+                "ACTION: search\nARGS: {\"q\": \"x\"}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ]))
+        };
+        let intent = UserIntent::ComplexMission {
+            goal: "Search".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let warned = |session: &Session| {
+            session.history.iter().any(|e| e.content.starts_with("CRITICAL WARNING"))
+        };
+
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(looping())
+            .with_session_store(store.clone())
+            .with_reflection(5)
+            .build().unwrap();
+        controller.execute(intent.clone()).await.unwrap();
+        assert!(!warned(&store.sessions().pop().unwrap()));
+
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(looping())
+            .with_session_store(store.clone())
+            .with_reflection(5)
+            .build().unwrap();
+        controller
+            .reconfigure_capability("reflection_self_correction", serde_json::json!({"threshold": 2}))
+            .await
+            .unwrap();
+        controller.execute(intent).await.unwrap();
+        assert!(warned(&store.sessions().pop().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_capability_validates_config() {
+        let controller = ReActController::builder()
+            .with_reflection(3)
+            .with_compressor(Arc::new(crate::context::TruncationCompressor::new()))
+            .build().unwrap();
+
+        let invalid = controller
+            .reconfigure_capability("reflection_self_correction", serde_json::json!({"threshold": 0}))
+            .await;
+        assert!(matches!(invalid, Err(Error::InvalidRequest(_))));
+
+        let unknown_field = controller
+            .reconfigure_capability("context_compression", serde_json::json!({"token_threshold": 10}))
+            .await;
+        assert!(matches!(unknown_field, Err(Error::InvalidRequest(_))));
+
+        controller
+            .reconfigure_capability("context_compression", serde_json::json!({"max_tokens": 1000}))
+            .await
+            .unwrap();
+
+        assert!(controller
+            .reconfigure_capability("missing", serde_json::json!({}))
+            .await
+            .is_err());
+    }
+
+    /// Tool registry that fails a fixed number of times before succeeding.
+    struct FlakyRegistry {
+        failures_left: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl ToolRegistry for FlakyRegistry {
+        async fn register(&self, _tool: Box<dyn multi_agent_core::traits::Tool>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn multi_agent_core::traits::Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<multi_agent_core::types::ToolDefinition>> {
+            Ok(vec![])
+        }
+
+        async fn execute(&self, _name: &str, _args: serde_json::Value) -> Result<multi_agent_core::types::ToolOutput> {
+            let remaining = self.failures_left.load(std::sync::atomic::Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures_left.store(remaining - 1, std::sync::atomic::Ordering::SeqCst);
+                return Err(Error::tool_execution("connection reset"));
+            }
+            Ok(multi_agent_core::types::ToolOutput::text("42"))
+        }
+    }
+
+    async fn run_flaky_tool(failures: u32, max_tool_retries: u32) -> Session {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_tool_retries,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: lookup\nARGS: {}".to_string(),
+                "FINAL ANSWER: 42".to_string(),
+            ])))
+            .with_tools(Arc::new(FlakyRegistry {
+                failures_left: std::sync::atomic::AtomicU32::new(failures),
+            }))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Look it up".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+        store.sessions().pop().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tool_retry_count_recorded() {
+        let session = run_flaky_tool(2, 2).await;
+        let call = session
+            .history
+            .iter()
+            .find_map(|e| e.tool_call.as_ref())
+            .unwrap();
+
+        assert_eq!(call.retry_count, 2);
+        assert!(call.result.as_ref().unwrap().contains("succeeded"));
+        assert_eq!(session.total_tool_retries(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_tool_retries_exhausted() {
+        let session = run_flaky_tool(5, 2).await;
+        let call = session
+            .history
+            .iter()
+            .find_map(|e| e.tool_call.as_ref())
+            .unwrap();
+
+        assert_eq!(call.retry_count, 2);
+        assert!(call.result.as_ref().unwrap().contains("connection reset"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_not_retried_by_default() {
+        let session = run_flaky_tool(1, ReActConfig::default().max_tool_retries).await;
+        let call = session
+            .history
+            .iter()
+            .find_map(|e| e.tool_call.as_ref())
+            .unwrap();
+
+        assert_eq!(call.retry_count, 0);
+        assert!(call.result.as_ref().unwrap().contains("connection reset"));
+    }
+
+    /// Keeps calling a tool, slowly, and counts its calls.
+    #[derive(Default)]
+    struct LoopingLlm {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmClient for LoopingLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Ok(LlmResponse {
+                content: "ACTION: search\nARGS: {}".to_string(),
+                finish_reason: "stop".to_string(),
+                usage: Default::default(),
+                tool_calls: None,
+                reasoning: None,
+            })
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_session() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let llm = Arc::new(LoopingLlm::default());
+        let controller = Arc::new(
+            ReActController::builder()
+                .with_config(ReActConfig {
+                    max_iterations: 100,
+                    max_total_tool_calls: 100,
+                    max_single_tool_frequency: 100,
+                    ..Default::default()
+                })
+                .with_llm(llm.clone())
+                .with_tools(Arc::new(multi_agent_core::mocks::MockToolRegistry::new()))
+                .with_session_store(store.clone())
+                .build().unwrap(),
+        );
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Search forever".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let handle = tokio::spawn({
+            let controller = controller.clone();
+            async move { controller.execute(intent).await }
+        });
+
+        // Wait until the loop is between iterations
+        while llm.calls.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        let session_id = controller.active_sessions().pop().unwrap();
+        controller.cancel(&session_id).await.unwrap();
+
+        let result = handle.await.unwrap();
+        assert!(matches!(result, Err(Error::Cancelled)), "got {:?}", result);
+        assert!(llm.calls.load(Ordering::SeqCst) < 5);
+        assert!(controller.active_sessions().is_empty());
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.status, SessionStatus::Cancelled);
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.status, SessionStatus::Cancelled);
+        assert!(controller.resume(&session_id).await.is_err());
+
+        // The session is no longer running
+        assert!(controller.cancel(&session_id).await.is_err());
+    }
+
+    /// Records the completion cap of each call; prompts always count as 100 tokens.
+    #[derive(Default)]
+    struct CapRecordingLlm {
+        caps: std::sync::Mutex<Vec<Option<u64>>>,
+    }
+
+    impl CapRecordingLlm {
+        fn respond(&self, cap: Option<u64>) -> Result<LlmResponse> {
+            self.caps.lock().unwrap().push(cap);
+            Ok(LlmResponse {
+                content: "FINAL ANSWER: done".to_string(),
+                finish_reason: "stop".to_string(),
+                usage: Default::default(),
+                tool_calls: None,
+                reasoning: None,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl LlmClient for CapRecordingLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            self.respond(None)
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.respond(None)
+        }
+
+        async fn chat_with_max_tokens(&self, _messages: &[ChatMessage], max_tokens: u64) -> Result<LlmResponse> {
+            self.respond(Some(max_tokens))
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+
+        async fn count_tokens(&self, _messages: &[ChatMessage]) -> Result<u64> {
+            Ok(100)
+        }
+    }
+
+    fn capped_controller(
+        llm: Arc<CapRecordingLlm>,
+        default_budget: u64,
+        max_tokens_per_iteration: Option<u64>,
+        store: Arc<multi_agent_core::mocks::MockSessionStore>,
+    ) -> ReActController {
+        ReActController::builder()
+            .with_config(ReActConfig {
+                default_budget,
+                max_tokens_per_iteration,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .with_session_store(store)
+            .build().unwrap()
+    }
+
+    fn budget_intent() -> UserIntent {
+        UserIntent::ComplexMission {
+            goal: "Summarize".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_iteration_cap_passed_to_llm() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+
+        let llm = Arc::new(CapRecordingLlm::default());
+        capped_controller(llm.clone(), 50_000, Some(64), store.clone())
+            .execute(budget_intent())
+            .await
+            .unwrap();
+        assert_eq!(*llm.caps.lock().unwrap(), vec![Some(64)]);
+
+        // The cap shrinks to what the budget can still cover after the prompt
+        let llm = Arc::new(CapRecordingLlm::default());
+        capped_controller(llm.clone(), 150, Some(500), store.clone())
+            .execute(budget_intent())
+            .await
+            .unwrap();
+        assert_eq!(*llm.caps.lock().unwrap(), vec![Some(50)]);
+
+        // Without a cap the plain chat call is used
+        let llm = Arc::new(CapRecordingLlm::default());
+        capped_controller(llm.clone(), 150, None, store)
+            .execute(budget_intent())
+            .await
+            .unwrap();
+        assert_eq!(*llm.caps.lock().unwrap(), vec![None]);
+    }
+
+    #[tokio::test]
+    async fn test_pre_call_budget_check_short_circuits() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let llm = Arc::new(CapRecordingLlm::default());
+
+        // 100 prompt tokens + the minimum completion do not fit in 110
+        let result = capped_controller(llm.clone(), 110, Some(500), store.clone())
+            .execute(budget_intent())
+            .await;
+
+        assert!(matches!(result, Err(Error::BudgetExceeded { used: 0, limit: 110 })), "got {:?}", result);
+        assert!(llm.caps.lock().unwrap().is_empty());
+        assert_eq!(store.sessions().pop().unwrap().status, SessionStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_yields_events_in_order() {
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "Let me think about where to look.".to_string(),
+                "ACTION: search\nARGS: {\"q\": \"rust\"}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(multi_agent_core::mocks::MockToolRegistry::new()))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Research rust".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let events: Vec<ReActEvent> = controller.execute_streaming(intent).collect().await;
+
+        assert_eq!(events.len(), 7, "events: {:?}", events);
+        assert!(matches!(&events[0], ReActEvent::TokenEstimate(n) if *n > 0));
+        assert!(matches!(&events[1], ReActEvent::Thought(t) if t.contains("think")));
+        assert!(matches!(&events[2], ReActEvent::TokenEstimate(_)));
+        assert!(matches!(&events[3], ReActEvent::ToolCallStarted { name, args } if name == "search" && args["q"] == "rust"));
+        assert!(matches!(&events[4], ReActEvent::ToolResult { name, .. } if name == "search"));
+        assert!(matches!(&events[5], ReActEvent::TokenEstimate(_)));
+        assert!(matches!(&events[6], ReActEvent::FinalAnswer(AgentResult::Text(t)) if t == "done"));
+    }
+
+    /// Answers like `MockLlm` but cannot count tokens.
+    struct UncountableLlm(multi_agent_core::mocks::MockLlm);
+
+    #[async_trait]
+    impl LlmClient for UncountableLlm {
+        async fn complete(&self, prompt: &str) -> Result<LlmResponse> {
+            self.0.complete(prompt).await
+        }
+
+        async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.0.chat(messages).await
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.0.embed(text).await
+        }
+
+        async fn count_tokens(&self, _messages: &[ChatMessage]) -> Result<u64> {
+            Err(Error::ModelProvider("tokenizer unavailable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_skips_failed_token_estimate() {
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(UncountableLlm(multi_agent_core::mocks::MockLlm::constant(
+                "FINAL ANSWER: done",
+            ))))
+            .build().unwrap();
+
+        let events: Vec<ReActEvent> = controller.execute_streaming(budget_intent()).collect().await;
+
+        assert_eq!(events.len(), 1, "events: {:?}", events);
+        assert!(matches!(&events[0], ReActEvent::FinalAnswer(AgentResult::Text(t)) if t == "done"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_ends_with_error() {
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(UnavailableLlm))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Anything".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let events: Vec<ReActEvent> = controller.execute_streaming(intent).collect().await;
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], ReActEvent::TokenEstimate(_)));
+        assert!(matches!(&events[1], ReActEvent::Error(e) if e.contains("connection refused")));
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_is_incremental() {
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_iterations: 100,
+                max_total_tool_calls: 100,
+                max_single_tool_frequency: 100,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(LoopingLlm::default()))
+            .with_tools(Arc::new(multi_agent_core::mocks::MockToolRegistry::new()))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Search forever".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        // Events arrive while the loop is still running
+        let first: Vec<ReActEvent> = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            controller
+                .execute_streaming(intent)
+                .filter(|e| futures::future::ready(!matches!(e, ReActEvent::TokenEstimate(_))))
+                .take(2)
+                .collect(),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(first[0], ReActEvent::ToolCallStarted { .. }));
+        assert!(matches!(first[1], ReActEvent::ToolResult { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_replay_reruns_recorded_tool_calls() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let weather = Arc::new(RecordingTool::new("weather", "Looks up the weather", "sunny"));
+        let news = Arc::new(RecordingTool::new("news", "Fetches headlines", "all quiet"));
+        let sessions = Arc::new(MockSessionStore::new());
+        let llm = Arc::new(MockLlm::new(vec![
+            "ACTION: weather\nARGS: {\"city\": \"Paris\"}".to_string(),
+            "ACTION: news\nARGS: {\"topic\": \"rust\"}".to_string(),
+            "FINAL ANSWER: done".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_llm(llm.clone())
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![weather.clone(), news.clone()])))
+            .with_session_store(sessions.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Brief me".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+        let session_id = sessions.sessions().pop().unwrap().id;
+        let llm_calls = llm.call_count();
+
+        let outputs = controller.replay(&session_id).await.unwrap();
+
+        let contents: Vec<&str> = outputs.iter().map(|o| o.content.as_str()).collect();
+        assert_eq!(contents, vec!["sunny", "all quiet"]);
+        assert_eq!(weather.calls().len(), 2);
+        assert_eq!(news.calls()[1], serde_json::json!({ "topic": "rust" }));
+        // The LLM is not consulted
+        assert_eq!(llm.call_count(), llm_calls);
+    }
+
+    #[tokio::test]
+    async fn test_history_capped_before_llm_call() {
+        use crate::context::TruncationCompressor;
+        use crate::testing::ScriptedLlmClient;
+
+        let llm = Arc::new(ScriptedLlmClient::new(["FINAL ANSWER: done"]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_history_tokens: Some(8_000),
+                ..Default::default()
+            })
+            .with_llm(llm.clone())
+            .build().unwrap();
+
+        // 32 entries of ~1000 tokens each (4 chars per token)
+        let mut session = controller.create_session("Read the logs");
+        for i in 0..32 {
+            session.history.push(HistoryEntry {
+                role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
+                content: Arc::new(format!("entry {} {}", i, "x".repeat(4_000))),
+                tool_call: None,
+                timestamp: 0,
+                compressed_from: None,
+                images: Vec::new(),
+            });
+        }
+        let estimator = TruncationCompressor::new();
+        assert!(estimator.estimate_tokens(&controller.build_messages(&session)) > 32_000);
+
+        controller
+            .execute_iteration_with_llm(&mut session, &mut LoopState::default())
+            .await
+            .unwrap();
+
+        let sent = llm.requests().pop().unwrap();
+        assert!(estimator.estimate_tokens(&sent) <= 8_000);
+        assert_eq!(sent[0].content, session.history[0].content.as_str());
+        assert!(sent.last().unwrap().content.starts_with("entry 31 "));
+        // Only the prompt is truncated, the session keeps its history
+        assert!(session.history[1].content.starts_with("entry 0 "));
+    }
+
+    /// Registry with a single `tail_log` tool streaming three chunks.
+    struct ChunkedRegistry;
+
+    #[async_trait]
+    impl ToolRegistry for ChunkedRegistry {
+        async fn register(&self, _tool: Box<dyn multi_agent_core::traits::Tool>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn multi_agent_core::traits::Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<multi_agent_core::types::ToolDefinition>> {
+            Ok(vec![multi_agent_core::types::ToolDefinition {
+                name: "tail_log".to_string(),
+                description: "Streams the log".to_string(),
+                parameters: serde_json::Value::Null,
+                supports_streaming: true,
+            }])
+        }
+
+        async fn execute(&self, _name: &str, _args: serde_json::Value) -> Result<ToolOutput> {
+            Err(Error::internal("tail_log only streams"))
+        }
+
+        async fn execute_streaming(
+            &self,
+            _name: &str,
+            _args: serde_json::Value,
+        ) -> Result<multi_agent_core::traits::ToolOutputStream> {
+            let chunks = ["line 1\n", "line 2\n", "line 3\n"].map(|c| Ok(c.to_string()));
+            Ok(Box::pin(futures::stream::iter(chunks)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_tool_forwards_chunks() {
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: tail_log\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(ChunkedRegistry))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Read the log".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let events: Vec<ReActEvent> = controller.execute_streaming(intent).collect().await;
+
+        let chunks: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match e {
+                ReActEvent::ToolOutputChunk { name, chunk } if name == "tail_log" => Some(chunk.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(chunks, vec!["line 1\n", "line 2\n", "line 3\n"]);
+        assert!(matches!(&events[0], ReActEvent::TokenEstimate(_)));
+        assert!(matches!(&events[1], ReActEvent::ToolCallStarted { .. }));
+        // The observation holds the accumulated output
+        assert!(matches!(&events[5], ReActEvent::ToolResult { output, .. } if output.contains("line 1\nline 2\nline 3")));
+    }
+
+    /// Runs a parent that delegates once, at the given delegation depth.
+    /// Returns the parent session and the number of child LLM calls.
+    async fn delegate_at_depth(delegation_depth: usize) -> (Session, usize) {
+        use crate::delegation::DelegationManager;
+        use multi_agent_core::mocks::MockLlm;
+
+        let child = Arc::new(MockLlm::constant("child result"));
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                delegation_depth,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "DELEGATE: research the topic".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_delegator(Arc::new(DelegationManager::new(child.clone())))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Research".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        (store.sessions().pop().unwrap(), child.call_count())
+    }
+
+    #[tokio::test]
+    async fn test_subagent_at_max_depth_cannot_delegate() {
+        let (session, child_calls) = delegate_at_depth(crate::delegation::DEFAULT_MAX_DELEGATION_DEPTH).await;
+        assert_eq!(child_calls, 0);
+        assert!(session
+            .history
+            .iter()
+            .any(|e| e.content.starts_with("OBSERVATION: Subagent failed") && e.content.contains("exceeds max_depth 3")));
+
+        let (session, child_calls) = delegate_at_depth(crate::delegation::DEFAULT_MAX_DELEGATION_DEPTH - 1).await;
+        assert_eq!(child_calls, 1);
+        assert!(session
+            .history
+            .iter()
+            .any(|e| e.content.contains("Subagent completed: child result")));
+    }
+
+    #[tokio::test]
+    async fn test_delegation_depth_set_after_delegator() {
+        use crate::delegation::DelegationManager;
+        use multi_agent_core::mocks::MockLlm;
+
+        let child = Arc::new(MockLlm::constant("child result"));
+        let controller = ReActController::builder()
+            .with_delegator(Arc::new(DelegationManager::new(child.clone())))
+            .with_config(ReActConfig {
+                delegation_depth: crate::delegation::DEFAULT_MAX_DELEGATION_DEPTH,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "DELEGATE: research the topic".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .build().unwrap();
+        controller.execute(budget_intent()).await.unwrap();
+
+        assert_eq!(child.call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_delegated_session_references_parent() {
+        use crate::delegation::DelegationManager;
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore};
+
+        let store = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "DELEGATE: research the topic".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_delegator(Arc::new(
+                DelegationManager::new(MockLlm::constant("child result")).with_session_store(store.clone()),
+            ))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Research".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let sessions = store.sessions();
+        let parent = sessions.iter().find(|s| s.parent_session_id.is_none()).unwrap();
+        let child = sessions.iter().find(|s| s.parent_session_id.is_some()).unwrap();
+        assert_eq!(child.parent_session_id.as_deref(), Some(parent.id.as_str()));
+        assert_eq!(child.status, SessionStatus::Completed);
+        assert_eq!(child.task_state.as_ref().unwrap().goal, "research the topic");
+    }
+
+    /// Delegator answering each objective with a fixed result, recording the requests.
+    #[derive(Default)]
+    struct RecordingDelegator {
+        requests: std::sync::Mutex<Vec<crate::delegation::DelegationRequest>>,
+    }
+
+    #[async_trait]
+    impl crate::delegation::Delegator for RecordingDelegator {
+        async fn delegate(&self, request: crate::delegation::DelegationRequest) -> Result<crate::delegation::DelegationResult> {
+            self.requests.lock().unwrap().push(request.clone());
+            let result = format!("findings on {}", request.objective.to_lowercase());
+            Ok(crate::delegation::DelegationResult::success(request.id, result, 1))
+        }
+
+        async fn check_delegation(&self, _id: &str) -> Result<Option<crate::delegation::DelegationResult>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_decompose_fans_out_to_subagents() {
+        use crate::testing::ScriptedLlmClient;
+
+        let llm = Arc::new(ScriptedLlmClient::new([
+            "1. Research pricing\n2. Research competitors",
+            "FINAL ANSWER: merged report",
+        ]));
+        let delegator = Arc::new(RecordingDelegator::default());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                auto_decompose: true,
+                ..Default::default()
+            })
+            .with_llm(llm.clone())
+            .with_delegator(delegator.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Write a market report".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let result = controller.execute(intent).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "merged report"));
+
+        let objectives: Vec<String> = delegator.requests.lock().unwrap().iter().map(|r| r.objective.clone()).collect();
+        assert_eq!(objectives.len(), 2);
+        assert!(objectives.contains(&"Research pricing".to_string()));
+        assert!(objectives.contains(&"Research competitors".to_string()));
+
+        // The synthesizing call saw both subtask results
+        let last = llm.requests().pop().unwrap();
+        let observation = last.iter().find(|m| m.content.contains("Subtask results")).unwrap();
+        assert!(observation.content.contains("findings on research pricing"));
+        assert!(observation.content.contains("findings on research competitors"));
+    }
+
+    /// Delegator tracking how many subagents run at the same time.
+    #[derive(Default)]
+    struct ConcurrencyDelegator {
+        running: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl crate::delegation::Delegator for ConcurrencyDelegator {
+        async fn delegate(&self, request: crate::delegation::DelegationRequest) -> Result<crate::delegation::DelegationResult> {
+            use std::sync::atomic::Ordering;
+
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(crate::delegation::DelegationResult::success(request.id, "ok".to_string(), 1))
+        }
+
+        async fn check_delegation(&self, _id: &str) -> Result<Option<crate::delegation::DelegationResult>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delegation_fan_out_is_bounded() {
+        use crate::testing::ScriptedLlmClient;
+
+        let delegator = Arc::new(ConcurrencyDelegator::default());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                auto_decompose: true,
+                max_concurrent_delegations: 2,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(ScriptedLlmClient::new([
+                "1. Part one\n2. Part two\n3. Part three\n4. Part four",
+                "FINAL ANSWER: done",
+            ])))
+            .with_delegator(delegator.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Big job".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        assert_eq!(delegator.peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    /// Delegator whose subagents report a fixed token usage.
+    struct MeteredDelegator;
+
+    #[async_trait]
+    impl crate::delegation::Delegator for MeteredDelegator {
+        async fn delegate(&self, request: crate::delegation::DelegationRequest) -> Result<crate::delegation::DelegationResult> {
+            let mut usage = TokenUsage::default();
+            usage.add(400, 100);
+            Ok(crate::delegation::DelegationResult::success(request.id, "child result".to_string(), 1)
+                .with_token_usage(usage))
+        }
+
+        async fn check_delegation(&self, _id: &str) -> Result<Option<crate::delegation::DelegationResult>> {
+            Ok(None)
+        }
+    }
+
+    fn metered_controller(default_budget: u64, store: Arc<multi_agent_core::mocks::MockSessionStore>) -> ReActController {
+        ReActController::builder()
+            .with_config(ReActConfig {
+                default_budget,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "DELEGATE: research the topic".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_delegator(Arc::new(MeteredDelegator))
+            .with_session_store(store)
+            .build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_subagent_tokens_count_towards_parent() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        metered_controller(50_000, store.clone()).execute(budget_intent()).await.unwrap();
+
+        let usage = store.sessions().pop().unwrap().token_usage;
+        // Two parent LLM calls (30 tokens each) plus the subagent's 500
+        assert_eq!(usage.total_tokens, 2 * 30 + 500);
+        assert_eq!(usage.prompt_tokens, 2 * 10 + 400);
+        assert_eq!(usage.completion_tokens, 2 * 20 + 100);
+    }
+
+    #[tokio::test]
+    async fn test_graceful_budget_exhaustion_returns_partial_results() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            "ACTION: search\nARGS: {\"q\": \"rust\"}".to_string(),
+            "ACTION: fetch\nARGS: {\"url\": \"rust-lang.org\"}".to_string(),
+            "FINAL ANSWER: done".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                default_budget: 50,
+                graceful_budget_exhaustion: true,
+                ..Default::default()
+            })
+            .with_llm(llm.clone())
+            .with_tools(Arc::new(EchoRegistry))
+            .with_session_store(store.clone())
+            .build()
+            .unwrap();
+
+        let result = controller.execute(budget_intent()).await.unwrap();
+        let AgentResult::Text(text) = result else {
+            panic!("Expected partial text result");
+        };
+        assert!(text.starts_with("Token budget exhausted"), "{}", text);
+        assert!(text.contains("1. [search, iteration 0] Tool 'search' succeeded"), "{}", text);
+        assert!(text.contains("2. [fetch, iteration 1] Tool 'fetch' succeeded"), "{}", text);
+        // Two 30-token calls exhaust the budget of 50; no summary call is made
+        assert_eq!(llm.call_count(), 2);
+        assert_eq!(store.sessions().pop().unwrap().status, SessionStatus::BudgetExhausted);
+    }
+
+    #[tokio::test]
+    async fn test_graceful_budget_exhaustion_moderates_partial_results() {
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                default_budget: 50,
+                graceful_budget_exhaustion: true,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: search\nARGS: {\"q\": \"forbidden\"}".to_string(),
+                "ACTION: fetch\nARGS: {\"url\": \"rust-lang.org\"}".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .with_content_moderation(Arc::new(KeywordModerator))
+            .build()
+            .unwrap();
+
+        let result = controller.execute(budget_intent()).await;
+        assert!(matches!(result, Err(Error::ContentViolation { .. })), "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_graceful_budget_exhaustion_passes_output_security() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                default_budget: 50,
+                graceful_budget_exhaustion: true,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: search\nARGS: {\"q\": \"rust\"}".to_string(),
+                "ACTION: fetch\nARGS: {\"contact\": \"jane.doe@corp.com\"}".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .with_security(Arc::new(multi_agent_governance::PiiScanner::new()))
+            .with_session_store(store.clone())
+            .build()
+            .unwrap();
+
+        match controller.execute(budget_intent()).await {
+            Err(Error::Controller(message)) => assert!(message.contains("Output security violation"), "{}", message),
+            other => panic!("Expected output security violation, got {:?}", other),
+        }
+        assert_eq!(store.sessions().pop().unwrap().status, SessionStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_subagent_tokens_trigger_budget_exceeded() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let result = metered_controller(300, store.clone()).execute(budget_intent()).await;
+
+        assert!(matches!(result, Err(Error::BudgetExceeded { used: 530, limit: 300 })), "got {:?}", result);
+    }
+
+    /// Answers with a native tool call first (with misleading text), then a final answer.
+    #[derive(Default)]
+    struct NativeToolLlm {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmClient for NativeToolLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            let response = if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                LlmResponse {
+                    content: "FINAL ANSWER: premature".to_string(),
+                    finish_reason: "tool_calls".to_string(),
+                    usage: Default::default(),
+                    tool_calls: Some(vec![serde_json::json!({
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "search", "arguments": "{\"q\": \"rust\"}" }
+                    })]),
+                    reasoning: None,
+                }
+            } else {
+                LlmResponse {
+                    content: "FINAL ANSWER: found it".to_string(),
+                    finish_reason: "stop".to_string(),
+                    usage: Default::default(),
+                    tool_calls: None,
+                    reasoning: None,
+                }
+            };
+            Ok(response)
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    async fn run_native_tool_llm(use_native_tool_calls: bool) -> (AgentResult, Session) {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                use_native_tool_calls,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(NativeToolLlm::default()))
+            .with_tools(Arc::new(multi_agent_core::mocks::MockToolRegistry::new()))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let result = controller.execute(budget_intent()).await.unwrap();
+        (result, store.sessions().pop().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_native_tool_call_bypasses_text_parsing() {
+        let (result, session) = run_native_tool_llm(true).await;
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "found it"));
+
+        let call = session
+            .history
+            .iter()
+            .find_map(|e| e.tool_call.as_ref())
+            .expect("native tool call executed");
+        assert_eq!(call.name, "search");
+        assert_eq!(call.arguments["q"], "rust");
+
+        // Disabled: the text is parsed instead
+        let (result, session) = run_native_tool_llm(false).await;
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "premature"));
+        assert!(session.history.iter().all(|e| e.tool_call.is_none()));
+    }
+
+    /// Registry with a single `search` tool that has a parameter schema.
+    #[derive(Default)]
+    struct SchemaRegistry {
+        executed: std::sync::atomic::AtomicUsize,
+        listed: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ToolRegistry for SchemaRegistry {
+        async fn register(&self, _tool: Box<dyn multi_agent_core::traits::Tool>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn multi_agent_core::traits::Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<multi_agent_core::types::ToolDefinition>> {
+            self.listed.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![multi_agent_core::types::ToolDefinition {
+                name: "search".to_string(),
+                description: "Search the web".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "q": { "type": "string" },
+                        "limit": { "type": "integer" }
+                    },
+                    "required": ["q"]
+                }),
+                supports_streaming: false,
+            }])
+        }
+
+        async fn execute(&self, _name: &str, _args: serde_json::Value) -> Result<multi_agent_core::types::ToolOutput> {
+            self.executed.fetch_add(1, Ordering::SeqCst);
+            Ok(multi_agent_core::types::ToolOutput::text("results"))
+        }
+    }
+
+    /// Runs one `search` call with `args`, returning the observation and execution count.
+    async fn run_search_with_args(args: &str) -> (String, usize) {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let registry = Arc::new(SchemaRegistry::default());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                format!("ACTION: search\nARGS: {}", args),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(registry.clone())
+            .with_session_store(store.clone())
+            .build().unwrap();
+        controller.execute(budget_intent()).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        let observation = session
+            .history
+            .iter()
+            .find(|e| e.tool_call.is_some())
+            .map(|e| e.content.to_string())
+            .unwrap();
+        (observation, registry.executed.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn test_tool_definitions_are_listed_once_per_mission() {
+        let registry = Arc::new(SchemaRegistry::default());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: search\nARGS: {\"q\": \"rust\"}".to_string(),
+                "ACTION: search\nARGS: {\"q\": \"tokio\"}".to_string(),
+                "ACTION: search\nARGS: {\"limit\": 5}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(registry.clone())
+            .build().unwrap();
+        controller.execute(budget_intent()).await.unwrap();
+
+        assert_eq!(registry.executed.load(Ordering::SeqCst), 2);
+        assert_eq!(registry.listed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_args_missing_required_field() {
+        let (observation, executed) = run_search_with_args(r#"{"limit": 5}"#).await;
+        assert_eq!(executed, 0);
+        assert!(observation.contains("was not executed"));
+        assert!(observation.contains("\"q\" is a required property"), "{}", observation);
+    }
+
+    #[tokio::test]
+    async fn test_tool_args_type_mismatch() {
+        let (observation, executed) = run_search_with_args(r#"{"q": "rust", "limit": "five"}"#).await;
+        assert_eq!(executed, 0);
+        assert!(observation.contains("/limit"), "{}", observation);
+        assert!(observation.contains("integer"), "{}", observation);
+    }
+
+    #[tokio::test]
+    async fn test_fast_action_with_invalid_args_fails_validation() {
+        let controller = ReActController::builder()
+            .with_tools(Arc::new(SchemaRegistry::default()))
+            .build().unwrap();
+
+        let result = controller
+            .execute(UserIntent::FastAction {
+                tool_name: "search".to_string(),
+                args: serde_json::json!({"limit": 5}),
+            })
+            .await;
+        match result {
+            Err(Error::ToolValidationFailed { tool, reason }) => {
+                assert_eq!(tool, "search");
+                assert!(reason.contains("\"q\" is a required property"), "{}", reason);
+            }
+            other => panic!("Expected ToolValidationFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_errors_are_attributed_to_the_tool() {
+        let controller = ReActController::new(ReActConfig::default());
+        let registry = FlakyRegistry {
+            failures_left: std::sync::atomic::AtomicU32::new(1),
+        };
+
+        let err = controller
+            .execute_tool(&registry, "lookup", serde_json::json!({}), None)
+            .await
+            .unwrap_err();
+        match err {
+            Error::ToolExecutionFailed { tool, source } => {
+                assert_eq!(tool, "lookup");
+                assert!(matches!(*source, Error::ToolExecution(_)));
+            }
+            other => panic!("Expected ToolExecutionFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_estimate_without_llm_is_an_llm_error() {
+        let controller = ReActController::new(ReActConfig::default());
+        let result = controller.live_token_estimate(&controller.create_session("goal")).await;
+        assert!(matches!(result, Err(Error::LlmError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_tool_args_valid_are_executed() {
+        let (observation, executed) = run_search_with_args(r#"{"q": "rust", "limit": 5}"#).await;
+        assert_eq!(executed, 1);
+        assert!(observation.contains("succeeded"));
+    }
+
+    /// Fails `failures` times with `error` before answering.
+    struct FlakyLlm {
+        failures: usize,
+        error: &'static str,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl FlakyLlm {
+        fn new(failures: usize, error: &'static str) -> Self {
+            Self {
+                failures,
+                error,
+                calls: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LlmClient for FlakyLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(Error::ModelProvider(self.error.to_string()));
+            }
+            Ok(LlmResponse {
+                content: "FINAL ANSWER: recovered".to_string(),
+                finish_reason: "stop".to_string(),
+                usage: Default::default(),
+                tool_calls: None,
+                reasoning: None,
+            })
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    async fn run_flaky_llm(llm: Arc<FlakyLlm>, max_retries: u32) -> Result<AgentResult> {
+        ReActController::builder()
+            .with_config(ReActConfig {
+                max_retries,
+                retry_base_delay_ms: 1,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .build().unwrap()
+            .execute(budget_intent())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_transient_llm_errors_are_retried() {
+        let llm = Arc::new(FlakyLlm::new(2, "503 Service Unavailable"));
+        let result = run_flaky_llm(llm.clone(), 2).await.unwrap();
+
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "recovered"));
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_transient_llm_errors_exhaust_retries() {
+        let llm = Arc::new(FlakyLlm::new(2, "503 Service Unavailable"));
+        let result = run_flaky_llm(llm.clone(), 1).await;
+
+        assert!(matches!(result, Err(Error::ModelProvider(_))));
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_llm_errors_are_not_retried() {
+        let llm = Arc::new(FlakyLlm::new(2, "401 Unauthorized"));
+        let result = run_flaky_llm(llm.clone(), 2).await;
+
+        assert!(matches!(result, Err(Error::ModelProvider(_))));
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 1);
+    }
+
+    struct UnavailableLlm;
+
+    #[async_trait]
+    impl LlmClient for UnavailableLlm {
+        async fn complete(&self, _prompt: &str) -> Result<LlmResponse> {
+            Err(Error::ModelProvider("connection refused".to_string()))
+        }
+
+        async fn chat(&self, _messages: &[ChatMessage]) -> Result<LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Err(Error::ModelProvider("connection refused".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_controller_on_provider_failure() {
+        let fallback = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::constant(
+                "FINAL ANSWER: from fallback",
+            )))
+            .build().unwrap();
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(UnavailableLlm))
+            .with_fallback_controller(Arc::new(fallback))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Test goal".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+
+        for expected in 1..=2 {
+            match controller.execute(intent.clone()).await.unwrap() {
+                AgentResult::Text(text) => assert_eq!(text, "from fallback"),
+                other => panic!("Expected Text result, got {:?}", other),
+            }
+            assert_eq!(controller.fallback_invocations(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_not_used_for_other_errors() {
+        let fallback = Arc::new(ReActController::builder().build().unwrap());
+        let controller = ReActController::builder()
+            .with_fallback_controller(fallback)
+            .build().unwrap();
+
+        let intent = UserIntent::Schedule {
+            cron_expr: "* * * * * *".to_string(),
+            intent: Box::new(UserIntent::FastAction {
+                tool_name: "echo".to_string(),
+                args: serde_json::json!({}),
+            }),
+            max_runs: None,
+        };
+
+        assert!(matches!(controller.execute(intent).await, Err(Error::InvalidRequest(_))));
+        assert_eq!(controller.fallback_invocations(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_thoughts_trigger_done_check() {
+        use crate::testing::ScriptedLlmClient;
+
+        let thought = |n: usize| format!("Step {}: {}", n, "the capital of France is Paris, a city on the Seine. ".repeat(2));
+        let llm = Arc::new(ScriptedLlmClient::new([thought(1), thought(2), thought(3), "Yes".to_string()]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                detect_implicit_final_answer: true,
+                ..Default::default()
+            })
+            .with_llm(llm.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Name the capital of France".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let result = controller.execute(intent).await.unwrap();
+
+        assert!(matches!(result, AgentResult::Text(ref answer) if answer == thought(3).trim()));
+        assert_eq!(llm.call_count(), 4);
+        let check = llm.requests().pop().unwrap();
+        assert!(check[0].content.starts_with("Is the following response a complete final answer"));
+    }
+
+    #[tokio::test]
+    async fn test_implicit_final_answer_passes_output_security() {
+        use crate::testing::ScriptedLlmClient;
+
+        let thought = |n: usize| format!("Step {}: {}", n, "write to mayor@paris.fr, the office of the capital of France. ".repeat(2));
+        let llm = Arc::new(ScriptedLlmClient::new([thought(1), thought(2), thought(3), "Yes".to_string()]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                detect_implicit_final_answer: true,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .with_security(Arc::new(multi_agent_governance::PiiScanner::new()))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Who should I write to in Paris".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        match controller.execute(intent).await {
+            Err(Error::Controller(message)) => assert!(message.contains("Output security violation"), "{}", message),
+            other => panic!("Expected output security violation, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_done_check_uses_the_selected_tier() {
+        use crate::testing::ScriptedLlmClient;
+
+        let thought = |n: usize| format!("Step {}: {}", n, "the capital of France is Paris, a city on the Seine. ".repeat(2));
+        let fast = Arc::new(ScriptedLlmClient::new([thought(1), thought(2), thought(3), "Yes".to_string()]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                detect_implicit_final_answer: true,
+                ..Default::default()
+            })
+            .with_tier_llms(HashMap::from([(ModelTier::Fast, fast.clone() as Arc<dyn LlmClient>)]))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Name the capital of France".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+        assert_eq!(fast.call_count(), 4);
+    }
+
+    #[test]
+    fn test_elide_middle() {
+        assert_eq!(elide_middle("short".to_string(), 3, 3), "short");
+        assert_eq!(elide_middle("héllo wörld".to_string(), 2, 3), "hé\n... [truncated 6 chars] ...\nrld");
+        assert_eq!(elide_middle("abcdef".to_string(), 0, 2), "\n... [truncated 4 chars] ...\nef");
+    }
+
+    #[tokio::test]
+    async fn test_long_observation_keeps_head_and_tail() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let output = format!("HEADER{}FOOTER", "x".repeat(5000));
+        let tools = Arc::new(MockToolRegistry::with_tools(vec![Arc::new(RecordingTool::new(
+            "dump",
+            "Dumps a table",
+            &output,
+        ))]));
+        let sessions = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                observation_head: Some(30),
+                observation_tail: Some(6),
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: dump\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(tools)
+            .with_session_store(sessions.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Dump the table".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = sessions.sessions().pop().unwrap();
+        let entry = session.history.iter().find(|e| e.tool_call.is_some()).unwrap();
+        assert!(entry.content.contains("Tool 'dump' succeeded:\nHEADER"));
+        assert!(entry.content.contains("... [truncated 4999 chars] ..."));
+        assert!(entry.content.ends_with("\nFOOTER"));
+        assert!(entry.content.len() < 200);
+    }
+
+    #[tokio::test]
+    async fn test_history_pruned_each_iteration() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore};
+
+        let sessions = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_history_entries: Some(3),
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "THOUGHT: first idea".to_string(),
+                "THOUGHT: second idea".to_string(),
+                "THOUGHT: third idea".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_session_store(sessions.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Think it over".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = sessions.sessions().pop().unwrap();
+        assert_eq!(session.history[0].role, "system");
+        assert!(session.history.iter().any(|e| e.content.contains("third idea")));
+        assert!(!session.history.iter().any(|e| e.content.contains("first idea")));
+    }
+
+    #[tokio::test]
+    async fn test_empty_response_is_reprompted() {
+        use crate::testing::ScriptedLlmClient;
+
+        let llm = Arc::new(ScriptedLlmClient::new(["", "FINAL ANSWER: 42"]));
+        let controller = ReActController::builder()
+            .with_llm(llm.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Answer the question".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let result = controller.execute(intent).await.unwrap();
+
+        assert!(matches!(result, AgentResult::Text(ref text) if text == "42"));
+        let retry = llm.requests().pop().unwrap();
+        assert_eq!(retry.last().unwrap().content, EMPTY_RESPONSE_MESSAGE);
+        assert!(!retry.iter().any(|m| m.role == "assistant"));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_empty_responses_fail() {
+        use crate::testing::ScriptedLlmClient;
+
+        let llm = Arc::new(ScriptedLlmClient::new(["", "  \n", "FINAL ANSWER: too late"]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_empty_responses: 1,
+                ..Default::default()
+            })
+            .with_llm(llm.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Answer the question".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let err = controller.execute(intent).await.unwrap_err();
+
+        assert!(matches!(err, Error::LlmError(_)));
+        assert_eq!(llm.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_iteration_timings_recorded() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore};
+
+        let sessions = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "THOUGHT: first idea".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_session_store(sessions.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Think it over".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = sessions.sessions().pop().unwrap();
+        assert!(session.updated_at >= session.created_at);
+        assert!(session.duration_secs() < 60);
+        assert_eq!(session.iteration_durations_ms.len(), 2);
+        assert_eq!(session.total_iteration_ms(), session.iteration_durations_ms.iter().sum::<u64>());
+        assert!(session.slowest_iteration().is_some_and(|(index, _)| index < 2));
+    }
+
+    #[tokio::test]
+    async fn test_json_action_parser_drives_loop() {
+        use crate::parser::JsonActionParser;
+        use crate::testing::ScriptedLlmClient;
+        use multi_agent_core::mocks::{MockToolRegistry, RecordingTool};
+
+        let llm = Arc::new(ScriptedLlmClient::new([
+            r#"{"action": "lookup", "args": {"id": 7}}"#,
+            r#"{"action": "final_answer", "args": {"answer": "Order 7 shipped"}}"#,
+        ]));
+        let tool = Arc::new(RecordingTool::new("lookup", "Looks up an order", "shipped"));
+        let controller = ReActController::builder()
+            .with_llm(llm)
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![tool.clone()])))
+            .with_action_parser(Arc::new(JsonActionParser::new()))
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Check order 7".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let result = controller.execute(intent).await.unwrap();
+
+        assert!(matches!(result, AgentResult::Text(ref answer) if answer == "Order 7 shipped"));
+        assert_eq!(tool.calls(), vec![serde_json::json!({ "id": 7 })]);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_mission_fires_on_tick() {
+        use crate::schedule::InMemoryScheduleStore;
+        use crate::testing::ScriptedLlmClient;
+
+        let schedules = Arc::new(InMemoryScheduleStore::new());
+        let llm = Arc::new(ScriptedLlmClient::new(["FINAL ANSWER: report sent"]));
+        let controller = ReActController::builder()
+            .with_llm(llm.clone())
+            .with_schedule_store(schedules.clone())
+            .build().unwrap();
+
+        let registered = controller
+            .execute(UserIntent::ScheduledMission {
+                goal: "Send the daily report".to_string(),
+                cron: "0 0 9 * * *".to_string(),
+                context_summary: "Sales figures".to_string(),
+            })
+            .await
+            .unwrap();
+        let AgentResult::Data(data) = registered else {
+            panic!("Expected schedule data, got {:?}", registered);
+        };
+        let schedule_id = data["schedule_id"].as_str().unwrap().to_string();
+        assert_eq!(llm.call_count(), 0);
+
+        // A day later the scheduled time is in the past
+        let now = chrono::Utc::now() + chrono::Duration::days(1);
+        let results = controller.tick(now).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Ok(AgentResult::Text(ref answer)) if answer == "report sent"));
+        assert!(llm.requests()[0].iter().any(|m| m.content == "Sales figures"));
+
+        // Rescheduled after the tick, so the same tick does not fire it again
+        let entry = schedules.load(&schedule_id).await.unwrap().unwrap();
+        assert_eq!(entry.run_count, 1);
+        assert!(entry.next_run_at.unwrap() > now.timestamp_millis());
+        assert!(controller.tick(now).await.unwrap().is_empty());
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: std::collections::HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_from_env_config() {
+        let controller = crate::builder::ReActBuilder::from_env_lookup(env(&[
+            ("REACT_MAX_ITERATIONS", "7"),
+            ("REACT_DEFAULT_BUDGET", "1000"),
+            ("REACT_TEMPERATURE", "0.2"),
+            ("REACT_MODEL_TIER", "Premium"),
+            ("REACT_LLM_PROVIDER", "openai"),
+            ("REACT_LLM_API_KEY", "sk-test"),
+            ("REACT_SESSION_STORE", "redis"),
+            ("REACT_REDIS_URL", "redis://localhost:6379"),
+        ]))
+        .unwrap()
+        .build().unwrap();
+
+        assert_eq!(controller.config.max_iterations, 7);
+        assert_eq!(controller.config.default_budget, 1000);
+        assert!((controller.config.temperature - 0.2).abs() < f32::EPSILON);
+        assert_eq!(controller.config.pricing_tier, ModelTier::Premium);
+        assert!(controller.llm.is_some());
+        assert!(controller.session_store.is_some());
+
+        // Ollama runs locally and needs no API key
+        assert!(crate::builder::ReActBuilder::from_env_lookup(env(&[("REACT_LLM_PROVIDER", "ollama")])).is_ok());
+    }
+
+    #[test]
+    fn test_from_env_missing_vars() {
+        let missing = |vars: &[(&str, &str)]| match crate::builder::ReActBuilder::from_env_lookup(env(vars)) {
+            Err(Error::MissingEnvVar(name)) => name,
+            Err(e) => panic!("Expected MissingEnvVar, got {}", e),
+            Ok(_) => panic!("Expected MissingEnvVar"),
+        };
+
+        assert_eq!(missing(&[]), "REACT_LLM_PROVIDER");
+        assert_eq!(missing(&[("REACT_LLM_PROVIDER", "anthropic")]), "REACT_LLM_API_KEY");
+        assert_eq!(
+            missing(&[("REACT_LLM_PROVIDER", "ollama"), ("REACT_SESSION_STORE", "redis")]),
+            "REACT_REDIS_URL"
+        );
+
+        let invalid = crate::builder::ReActBuilder::from_env_lookup(env(&[
+            ("REACT_LLM_PROVIDER", "ollama"),
+            ("REACT_MAX_ITERATIONS", "many"),
+        ]));
+        assert!(matches!(invalid, Err(Error::InvalidRequest(_))));
+
+        let invalid_tier = crate::builder::ReActBuilder::from_env_lookup(env(&[
+            ("REACT_LLM_PROVIDER", "ollama"),
+            ("REACT_MODEL_TIER", "gold"),
+        ]));
+        assert!(matches!(invalid_tier, Err(Error::InvalidRequest(_))));
+    }
+
+    fn haiku_controller(
+        responses: Vec<&str>,
+        store: Arc<multi_agent_core::mocks::MockSessionStore>,
+    ) -> ReActController {
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(
+            responses.into_iter().map(String::from).collect(),
+        ));
+        ReActController::builder()
+            .with_config(ReActConfig {
+                instruction_following_check: true,
+                max_iterations: 3,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .with_session_store(store)
+            .build().unwrap()
+    }
+
+    fn haiku_intent() -> UserIntent {
+        UserIntent::ComplexMission {
+            goal: "Write a haiku".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_overuse_message_injected_at_threshold() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            r#"ACTION: search
+ARGS: {"q": "1"}"#.to_string(),
+            r#"ACTION: search
+ARGS: {"q": "2"}"#.to_string(),
+            r#"ACTION: search
+ARGS: {"q": "3"}"#.to_string(),
+            "FINAL ANSWER: Gave up searching".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_single_tool_frequency: 2,
+                ..Default::default()
+            })
+            .with_llm(llm)
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "Find it".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        controller.execute(intent).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.task_state.unwrap().tool_call_frequency.get("search"), Some(&3));
+
+        let warnings: Vec<_> = session
+            .history
+            .iter()
+            .filter(|e| e.content.starts_with("You have called 'search'"))
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].content.contains("3 times"));
+    }
+
+    #[tokio::test]
+    async fn test_instruction_check_continues_on_no() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = haiku_controller(
+            vec![
+                "FINAL ANSWER: A long paragraph about autumn leaves.",
+                "No, this is a paragraph, not a haiku.",
+                "FINAL ANSWER: Leaves drift / on cold wind / autumn ends",
+                "Yes, it is a haiku.",
+            ],
+            store.clone(),
+        );
+
+        match controller.execute(haiku_intent()).await.unwrap() {
+            AgentResult::Text(text) => assert!(text.starts_with("Leaves drift")),
+            other => panic!("Expected Text result, got {:?}", other),
+        }
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.instruction_check_passed, Some(true));
+        assert!(session
+            .history
+            .iter()
+            .any(|e| e.role == "user" && e.content.contains("not a haiku")));
+        assert_eq!(session.task_state.unwrap().iteration, 1);
+    }
+
+    #[tokio::test]
+    async fn test_instruction_check_returns_on_yes() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = haiku_controller(
+            vec![
+                "FINAL ANSWER: Leaves drift / on cold wind / autumn ends",
+                "Yes, it is a haiku.",
+            ],
+            store.clone(),
+        );
+
+        controller.execute(haiku_intent()).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.instruction_check_passed, Some(true));
+        assert_eq!(session.task_state.unwrap().iteration, 0);
+    }
+
+    #[tokio::test]
+    async fn test_instruction_check_counts_toward_max_iterations() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = haiku_controller(
+            vec!["FINAL ANSWER: A paragraph.", "No, not a haiku."],
+            store.clone(),
+        );
+
+        let result = controller.execute(haiku_intent()).await;
+        assert!(matches!(result, Err(Error::MaxIterationsExceeded(3))));
+        assert_eq!(store.sessions().pop().unwrap().instruction_check_passed, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_instruction_check_uses_the_selected_tier() {
+        use crate::testing::ScriptedLlmClient;
+
+        let fast = Arc::new(ScriptedLlmClient::new([
+            "FINAL ANSWER: Leaves drift / on cold wind / autumn ends",
+            "Yes, it is a haiku.",
+        ]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                instruction_following_check: true,
+                ..Default::default()
+            })
+            .with_tier_llms(HashMap::from([(ModelTier::Fast, fast.clone() as Arc<dyn LlmClient>)]))
+            .build().unwrap();
+
+        controller.execute(haiku_intent()).await.unwrap();
+        assert_eq!(fast.call_count(), 2);
+        assert!(fast.requests()[1][0].content.starts_with("Does the following answer satisfy"));
+    }
+
+    /// Registry that echoes the tool name, with `search` finishing last.
+    struct EchoRegistry;
+
+    #[async_trait]
+    impl ToolRegistry for EchoRegistry {
+        async fn register(&self, _tool: Box<dyn multi_agent_core::traits::Tool>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn multi_agent_core::traits::Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<multi_agent_core::types::ToolDefinition>> {
+            Ok(vec![])
+        }
+
+        async fn execute(&self, name: &str, args: serde_json::Value) -> Result<multi_agent_core::types::ToolOutput> {
+            if name == "search" {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            Ok(multi_agent_core::types::ToolOutput::text(format!("{} -> {}", name, args)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parallel_tool_calls_keep_order() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: search\nARGS: {\"q\": \"a\"}\nACTION: lookup\nARGS: {\"id\": 1}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let start = std::time::Instant::now();
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "done"));
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+
+        let session = store.sessions().pop().unwrap();
+        let observations: Vec<_> = session
+            .history
+            .iter()
+            .filter_map(|e| e.tool_call.as_ref().map(|call| (call.name.as_str(), e.content.to_string())))
+            .collect();
+        assert_eq!(observations.len(), 2);
+        assert_eq!(observations[0].0, "search");
+        assert!(observations[0].1.contains("search -> {\"q\":\"a\"}"), "{}", observations[0].1);
+        assert_eq!(observations[1].0, "lookup");
+        assert!(observations[1].1.contains("lookup -> {\"id\":1}"), "{}", observations[1].1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_metrics_count_calls() {
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: search\nARGS: {\"q\": \"a\"}".to_string(),
+                "ACTION: lookup\nARGS: {\"id\": 1}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .build().unwrap();
+        assert!(controller.tool_metrics().is_empty());
+
+        controller.execute(budget_intent()).await.unwrap();
+
+        let metrics = controller.tool_metrics();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics["search"].calls, 1);
+        assert_eq!(metrics["search"].failures, 0);
+        assert!(metrics["search"].total_duration >= Duration::from_millis(50));
+        assert_eq!(metrics["lookup"].calls, 1);
+        assert_eq!(metrics["lookup"].failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tool_metrics_count_failures() {
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_tool_retries: 1,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: missing\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(multi_agent_core::mocks::MockToolRegistry::new()))
+            .build().unwrap();
+
+        controller.execute(budget_intent()).await.unwrap();
+
+        // The failed call is retried once
+        let metrics = &controller.tool_metrics()["missing"];
+        assert_eq!(metrics.calls, 2);
+        assert_eq!(metrics.failures, 2);
+    }
+
+    #[tokio::test]
+    async fn test_tier_escalates_to_premium_after_tool_failure() {
+        let fast = Arc::new(multi_agent_core::mocks::MockLlm::constant("ACTION: missing\nARGS: {}"));
+        let premium = Arc::new(multi_agent_core::mocks::MockLlm::constant("FINAL ANSWER: premium"));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_tool_retries: 0,
+                ..Default::default()
+            })
+            .with_tier_llms(HashMap::from([
+                (ModelTier::Fast, fast.clone() as Arc<dyn LlmClient>),
+                (ModelTier::Premium, premium.clone() as Arc<dyn LlmClient>),
+            ]))
+            .with_tools(Arc::new(multi_agent_core::mocks::MockToolRegistry::new()))
+            .build().unwrap();
+
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "premium"));
+        assert_eq!(fast.call_count(), 1);
+        assert_eq!(premium.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_execute_tools() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
+
+        let tool = Arc::new(RecordingTool::new("delete_file", "Deletes a file", "deleted"));
+        let store = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                dry_run: true,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: delete_file\nARGS: {\"path\": \"/tmp/report.txt\"}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![tool.clone()])))
+            .with_session_store(store.clone())
+            .build().unwrap();
+
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "done"));
+        assert!(tool.calls().is_empty());
+        assert!(controller.tool_metrics().is_empty());
+
+        let session = store.sessions().pop().unwrap();
+        let observation = session.history.iter().find(|e| e.tool_call.is_some()).unwrap();
+        assert!(
+            observation
+                .content
+                .contains("[DRY RUN] would call tool 'delete_file' with args {\"path\":\"/tmp/report.txt\"}"),
+            "{}",
+            observation.content
+        );
+
+        let fast = controller
+            .execute(UserIntent::FastAction {
+                tool_name: "delete_file".to_string(),
+                args: serde_json::json!({}),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(fast, AgentResult::Text(ref t) if t.starts_with("[DRY RUN]")));
+        assert!(tool.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_max_iterations_yields_best_effort_answer() {
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            "ACTION: lookup\nARGS: {}".to_string(),
+            "ACTION: lookup\nARGS: {}".to_string(),
+            "Based on the lookups, the answer is 42.".to_string(),
+        ]));
+        let config = ReActConfig {
+            max_iterations: 2,
+            answer_on_max_iterations: true,
+            ..Default::default()
         };
+        let controller = ReActController::builder()
+            .with_config(config.clone())
+            .with_llm(llm.clone())
+            .with_tools(Arc::new(EchoRegistry))
+            .build().unwrap();
 
-        session.history.push(HistoryEntry {
-            role: "user".to_string(),
-            content: Arc::new(format!("OBSERVATION: {}", observation)),
-            tool_call: Some(ToolCallInfo {
-                name: name.clone(),
-                arguments: args,
-                result: Some(Arc::new(observation.clone())),
-            }),
-            timestamp: chrono_timestamp(),
-        });
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "Based on the lookups, the answer is 42."));
+        assert_eq!(llm.call_count(), 3);
 
-        if let Some(ref mut task_state) = session.task_state {
-            task_state.observations.push(Arc::new(observation));
-        }
+        // Off by default: the cap is still an error
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                answer_on_max_iterations: false,
+                ..config
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::constant("ACTION: lookup\nARGS: {}")))
+            .with_tools(Arc::new(EchoRegistry))
+            .build().unwrap();
+        let result = controller.execute(budget_intent()).await;
+        assert!(matches!(result, Err(Error::MaxIterationsExceeded(2))));
+    }
 
-        for cap in &self.capabilities {
-            cap.on_post_execute(session).await.map_err(|e| Error::controller(e.to_string()))?;
-        }
+    #[tokio::test]
+    async fn test_best_effort_answer_is_moderated() {
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                max_iterations: 1,
+                answer_on_max_iterations: true,
+                ..Default::default()
+            })
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: lookup\nARGS: {}".to_string(),
+                "The forbidden answer is 42.".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .with_content_moderation(Arc::new(KeywordModerator))
+            .build().unwrap();
 
-        Ok(None)
+        let result = controller.execute(budget_intent()).await;
+        assert!(matches!(result, Err(Error::ContentViolation { .. })), "{:?}", result);
     }
 
-    /// Run the ReAct loop for a session.
-    async fn run_loop(&self, session: &mut Session) -> Result<AgentResult> {
-        let start_iteration = session.task_state.as_ref().map(|t| t.iteration).unwrap_or(0);
-        
-        tracing::info!(
-            session_id = %session.id, 
-            start_iteration = start_iteration,
-            "Starting/Resuming ReAct loop"
-        );
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_iterations_and_tool_calls_are_traced_in_spans() {
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: lookup\nARGS: {\"id\": 1}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(EchoRegistry))
+            .build().unwrap();
 
-        for iteration in start_iteration..self.config.max_iterations {
-            if let Some(ref mut task_state) = session.task_state {
-                task_state.iteration = iteration;
-            }
+        controller.execute(budget_intent()).await.unwrap();
 
-            match self.execute_iteration(session, iteration).await? {
-                Some(result) => {
-                    session.updated_at = chrono_timestamp();
-                    session.status = SessionStatus::Completed;
-                    self.persist_session(session).await;
-                    return Ok(result);
-                }
-                None => {
-                    session.updated_at = chrono_timestamp();
-                    self.persist_session(session).await;
+        assert!(logs_contain("react_iteration{session_id="));
+        assert!(logs_contain("iteration=0"));
+        assert!(logs_contain("iteration=1"));
+        assert!(logs_contain("tokens_used="));
+        assert!(logs_contain("tool_call{tool=lookup"));
+        assert!(logs_contain("duration_ms="));
+    }
 
-                    if session.token_usage.is_exceeded() {
-                        session.status = SessionStatus::Failed;
-                        // Persist failure state
-                        self.persist_session(session).await;
-                        return Err(Error::BudgetExceeded {
-                            used: session.token_usage.total_tokens,
-                            limit: session.token_usage.budget_limit,
-                        });
-                    }
-                    continue;
-                }
+    /// Approval hook that denies `delete_file` and records every request.
+    #[derive(Default)]
+    struct DenyDeletes {
+        requests: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ApprovalHook for DenyDeletes {
+        async fn approve(&self, tool: &str, _args: &serde_json::Value) -> ApprovalDecision {
+            self.requests.lock().unwrap().push(tool.to_string());
+            if tool == "delete_file" {
+                ApprovalDecision::deny("deleting files is not allowed")
+            } else {
+                ApprovalDecision::Approve
             }
         }
-
-        session.status = SessionStatus::Failed;
-        self.persist_session(session).await;
-        Err(Error::MaxIterationsExceeded(self.config.max_iterations))
     }
-}
 
-#[async_trait]
-impl Controller for ReActController {
+    #[tokio::test]
+    async fn test_approval_hook_denies_tool() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
 
-    async fn execute(&self, intent: UserIntent) -> Result<AgentResult> {
-        match intent {
-            UserIntent::FastAction { tool_name, args } => {
-                self.validate_fast_action_security(&args).await?;
+        let delete = Arc::new(RecordingTool::new("delete_file", "Deletes a file", "deleted"));
+        let read = Arc::new(RecordingTool::new("read_file", "Reads a file", "contents"));
+        let list = Arc::new(RecordingTool::new("list_files", "Lists files", "report.txt"));
+        let hook = Arc::new(DenyDeletes::default());
+        let store = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                requires_approval: HashSet::from(["delete_file".to_string(), "read_file".to_string()]),
+                ..Default::default()
+            })
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: delete_file\nARGS: {}\nACTION: read_file\nARGS: {}\nACTION: list_files\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![
+                delete.clone(),
+                read.clone(),
+                list.clone(),
+            ])))
+            .with_session_store(store.clone())
+            .with_approval_hook(hook.clone())
+            .build().unwrap();
 
-                // Fast path: direct tool execution
-                tracing::info!(tool = %tool_name, "Fast path execution");
+        controller.execute(budget_intent()).await.unwrap();
 
-                if let Some(ref tools) = self.tools {
-                    match tools.execute(&tool_name, args).await {
-                        Ok(output) => {
-                            if output.success {
-                                Ok(AgentResult::Text(output.content))
-                            } else {
-                                Ok(AgentResult::Error {
-                                    message: output.content,
-                                    code: "TOOL_ERROR".to_string(),
-                                })
-                            }
-                        }
-                        Err(e) => Ok(AgentResult::Error {
-                            message: e.to_string(),
-                            code: "TOOL_NOT_FOUND".to_string(),
-                        }),
-                    }
-                } else {
-                    Ok(AgentResult::Text(format!(
-                        "Fast path: would execute tool '{}'. Tools not configured.",
-                        tool_name
-                    )))
-                }
-            }
+        assert!(delete.calls().is_empty());
+        assert_eq!(read.calls().len(), 1);
+        assert_eq!(list.calls().len(), 1);
+        // Tools outside `requires_approval` skip the hook
+        let mut requests = hook.requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(requests, ["delete_file", "read_file"]);
 
-            UserIntent::ComplexMission {
-                goal,
-                context_summary,
-                visual_refs,
-            } => {
-                let mut session = self.create_session(&goal);
-                
-                // v0.3: Capability On-Start Hook
-                for cap in &self.capabilities {
-                    cap.on_start(&mut session).await.map_err(|e| Error::controller(e.to_string()))?;
-                }
+        let session = store.sessions().pop().unwrap();
+        let rejection = session
+            .history
+            .iter()
+            .find(|e| e.tool_call.as_ref().is_some_and(|c| c.name == "delete_file"))
+            .unwrap();
+        assert!(
+            rejection.content.contains("approval was denied: deleting files is not allowed"),
+            "{}",
+            rejection.content
+        );
+    }
 
-                // Add user context to history
-                session.history.push(HistoryEntry {
-                    role: "user".to_string(),
-                    content: Arc::new(if visual_refs.is_empty() {
-                        context_summary.clone()
-                    } else {
-                        format!("{}\n\nReferences: {:?}", context_summary, visual_refs)
-                    }),
-                    tool_call: None,
-                    timestamp: chrono_timestamp(),
-                });
-                
-                for cap in &self.capabilities {
-                     cap.on_pre_reasoning(&mut session).await.map_err(|e| Error::controller(e.to_string()))?;
-                }
+    #[tokio::test]
+    async fn test_approval_required_without_hook_denies() {
+        use multi_agent_core::mocks::{MockToolRegistry, RecordingTool};
 
-                tracing::info!(
-                    goal = %goal,
-                    context_len = context_summary.len(),
-                    refs_count = visual_refs.len(),
-                    "Starting ReAct loop"
-                );
+        let delete = Arc::new(RecordingTool::new("delete_file", "Deletes a file", "deleted"));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                requires_approval: HashSet::from(["delete_file".to_string()]),
+                ..Default::default()
+            })
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![delete.clone()])))
+            .build().unwrap();
 
-                self.run_loop(&mut session).await
-            }
-        }
+        let result = controller
+            .execute(UserIntent::FastAction {
+                tool_name: "delete_file".to_string(),
+                args: serde_json::json!({}),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(result, AgentResult::Error { ref code, .. } if code == "APPROVAL_DENIED"));
+        assert!(delete.calls().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_secrets_redacted_before_persistence() {
+        use multi_agent_core::mocks::{MockLlm, MockSessionStore, MockToolRegistry, RecordingTool};
 
+        let key = "sk-live0123456789abcdefghij";
+        let tool = Arc::new(RecordingTool::new("read_env", "Reads the environment", &format!("OPENAI_KEY={}", key)));
+        let store = Arc::new(MockSessionStore::new());
+        let controller = ReActController::builder()
+            .with_llm(Arc::new(MockLlm::new(vec![
+                "ACTION: read_env\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![tool])))
+            .with_session_store(store.clone())
+            .with_redactor(Arc::new(multi_agent_governance::RegexRedactor::new()))
+            .build().unwrap();
 
-    async fn resume(&self, session_id: &str) -> Result<AgentResult> {
-        let session_store = self.session_store.as_ref().ok_or_else(|| {
-             Error::controller("State persistence not configured (session_store is None)")
-        })?;
+        controller.execute(budget_intent()).await.unwrap();
 
-        // Load session
-        let mut session = session_store.load(session_id).await?
-            .ok_or_else(|| Error::controller(format!("Session {} not found", session_id)))?;
+        let session = store.sessions().pop().unwrap();
+        let saved = serde_json::to_string(&session).unwrap();
+        assert!(!saved.contains(key), "{}", saved);
+        let observation = session.history.iter().find(|e| e.tool_call.is_some()).unwrap();
+        assert!(observation.content.contains("[REDACTED_API_KEY]"), "{}", observation.content);
+        assert!(session.task_state.unwrap().observations[0].content.contains("[REDACTED_API_KEY]"));
+    }
 
-        tracing::info!(session_id = %session_id, status = ?session.status, "Resuming session");
+    /// Registry whose tools take longer than any test timeout.
+    struct SlowToolRegistry;
 
-        match session.status {
-            SessionStatus::Completed => {
-                // If completed, find the final answer in history
-                // We search backwards for the first assistant message that looks like a final answer
-                // OR we can just return the last message content if it was a final answer
-                // Since our `run_loop` returns AgentResult::Text(answer), we try to reconstruct it.
-                // For now, let's just return a generic success message or the last content.
-                let last_content = session.history.last()
-                    .map(|h| h.content.to_string())
-                    .unwrap_or_else(|| "Task completed (no content)".to_string());
-                
-                Ok(AgentResult::Text(last_content))
-            }
-            SessionStatus::Failed => {
-                Err(Error::controller("Cannot resume failed session"))
-            }
-            SessionStatus::Running | SessionStatus::Paused => {
-                // Resume execution
-                self.run_loop(&mut session).await
-            }
+    #[async_trait]
+    impl ToolRegistry for SlowToolRegistry {
+        async fn register(&self, _tool: Box<dyn multi_agent_core::traits::Tool>) -> Result<()> {
+            Ok(())
         }
-    }
 
-    async fn cancel(&self, session_id: &str) -> Result<()> {
-        tracing::info!(session_id = session_id, "Cancel requested");
-        Ok(())
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn multi_agent_core::traits::Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<multi_agent_core::types::ToolDefinition>> {
+            Ok(vec![])
+        }
+
+        async fn execute(&self, _name: &str, _args: serde_json::Value) -> Result<ToolOutput> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(ToolOutput::text("finally"))
+        }
     }
-}
 
-/// Get current timestamp.
-pub fn chrono_timestamp() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
-}
+    fn slow_tool_controller(config: ReActConfig, store: Arc<multi_agent_core::mocks::MockSessionStore>) -> ReActController {
+        ReActController::builder()
+            .with_config(config)
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+                "ACTION: slow\nARGS: {}".to_string(),
+                "FINAL ANSWER: done".to_string(),
+            ])))
+            .with_tools(Arc::new(SlowToolRegistry))
+            .with_session_store(store)
+            .build().unwrap()
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[tokio::test]
+    async fn test_mission_timeout_fails_session() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = slow_tool_controller(
+            ReActConfig {
+                mission_timeout: Some(Duration::from_millis(50)),
+                ..Default::default()
+            },
+            store.clone(),
+        );
 
-    #[test]
-    fn test_parse_final_answer() {
-        let controller = ReActController::new(ReActConfig::default());
-        
-        let response = "FINAL ANSWER: The result is 42.";
-        let action = controller.parse_action(response);
-        
-        match action {
-            ReActAction::FinalAnswer(answer) => {
-                assert_eq!(answer, "The result is 42.");
-            }
-            _ => panic!("Expected FinalAnswer"),
+        let start = Instant::now();
+        let result = controller.execute(budget_intent()).await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+        match result {
+            Err(Error::MissionTimeout { elapsed }) => assert!(elapsed >= Duration::from_millis(50)),
+            other => panic!("Expected MissionTimeout, got {:?}", other),
         }
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.status, SessionStatus::Failed);
+        assert!(controller.active_sessions().is_empty());
     }
 
-    #[test]
-    fn test_parse_tool_call() {
-        let controller = ReActController::new(ReActConfig::default());
-        
-        let response = r#"THOUGHT: I need to calculate something.
-ACTION: calculator
-ARGS: {"operation": "add", "a": 5, "b": 3}"#;
-        
-        let action = controller.parse_action(response);
-        
-        match action {
-            ReActAction::ToolCall { name, args } => {
-                assert_eq!(name, "calculator");
-                assert_eq!(args["operation"], "add");
-            }
-            _ => panic!("Expected ToolCall, got {:?}", action),
-        }
+    #[tokio::test]
+    async fn test_tool_timeout_becomes_observation() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let controller = slow_tool_controller(
+            ReActConfig {
+                tool_timeout: Some(Duration::from_millis(20)),
+                max_tool_retries: 0,
+                ..Default::default()
+            },
+            store.clone(),
+        );
+
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "done"));
+
+        let session = store.sessions().pop().unwrap();
+        let observation = session
+            .history
+            .iter()
+            .find(|e| e.tool_call.is_some())
+            .map(|e| e.content.to_string())
+            .unwrap();
+        assert!(observation.contains("Tool 'slow' exceeded"), "{}", observation);
     }
 
-    #[test]
-    fn test_parse_thought() {
-        let controller = ReActController::new(ReActConfig::default());
-        
-        let response = "THOUGHT: I need to think about this more.";
-        let action = controller.parse_action(response);
-        
-        match action {
-            ReActAction::Think(thought) => {
-                assert!(thought.contains("think about"));
-            }
-            _ => panic!("Expected Think"),
+    async fn mission_system_prompt(memory: Option<Arc<MemoryCapability>>) -> String {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let mut builder = ReActController::builder()
+            .with_llm(Arc::new(multi_agent_core::mocks::MockLlm::new(vec!["FINAL ANSWER: done".to_string()])))
+            .with_session_store(store.clone());
+        if let Some(memory) = memory {
+            builder = builder.with_memory_capability(memory);
         }
+        builder.build().unwrap().execute(budget_intent()).await.unwrap();
+
+        let session = store.sessions().pop().unwrap();
+        assert_eq!(session.history.iter().filter(|e| e.role == "system").count(), 1);
+        session.history[0].content.to_string()
     }
 
     #[tokio::test]
-    async fn test_fast_action() {
-        let controller = ReActController::new(ReActConfig::default());
+    async fn test_recalled_memories_in_system_prompt() {
+        let memory = Arc::new(MemoryCapability::keyword(
+            Arc::new(multi_agent_core::mocks::MockMemoryStore::new()),
+            5,
+        ));
+        memory.store("style", "Summarize using at most three bullet points").await.unwrap();
+        memory.store("unrelated", "The office closes at six").await.unwrap();
 
-        let intent = UserIntent::FastAction {
-            tool_name: "test_tool".to_string(),
-            args: serde_json::json!({"query": "test"}),
-        };
+        let prompt = mission_system_prompt(Some(memory)).await;
+        assert!(prompt.starts_with("RELEVANT PRIOR KNOWLEDGE:\n- Summarize using at most three bullet points\n"), "{}", prompt);
+        assert!(!prompt.contains("office"));
+        assert!(prompt.contains("GOAL: Summarize"));
+    }
 
-        let result = controller.execute(intent).await.unwrap();
-        match result {
-            AgentResult::Text(text) => {
-                assert!(text.contains("test_tool"));
-            }
-            _ => panic!("Expected Text result"),
-        }
+    #[tokio::test]
+    async fn test_system_prompt_unchanged_without_memory() {
+        let prompt = mission_system_prompt(None).await;
+        assert_eq!(prompt, ReActController::new(ReActConfig::default()).build_system_prompt("Summarize"));
     }
 
     #[tokio::test]
-    async fn test_complex_mission_mock() {
-        let controller = ReActController::new(ReActConfig::default());
+    async fn test_planning_seeds_pending_actions() {
+        let store = Arc::new(multi_agent_core::mocks::MockSessionStore::new());
+        let llm = Arc::new(multi_agent_core::mocks::MockLlm::new(vec![
+            "1. Research the topic\n2. Draft the summary\n3. Review the draft".to_string(),
+            "FINAL ANSWER: done".to_string(),
+        ]));
+        let controller = ReActController::builder()
+            .with_config(ReActConfig {
+                enable_planning: true,
+                ..Default::default()
+            })
+            .with_llm(llm.clone())
+            .with_session_store(store.clone())
+            .build().unwrap();
 
-        let intent = UserIntent::ComplexMission {
-            goal: "Test goal".to_string(),
-            context_summary: "Test context".to_string(),
-            visual_refs: vec![],
-        };
+        let result = controller.execute(budget_intent()).await.unwrap();
+        assert!(matches!(result, AgentResult::Text(ref t) if t == "done"));
+        assert_eq!(llm.call_count(), 2);
 
-        let result = controller.execute(intent).await.unwrap();
-        match result {
-            AgentResult::Text(text) => {
-                assert!(text.contains("Mock ReAct"));
-            }
-            _ => panic!("Expected Text result"),
-        }
+        let session = store.sessions().pop().unwrap();
+        let steps: Vec<crate::planning::PlanStep> = session
+            .task_state
+            .unwrap()
+            .pending_actions
+            .into_iter()
+            .map(|action| serde_json::from_value(action).unwrap())
+            .collect();
+        let descriptions: Vec<&str> = steps.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(descriptions, ["Research the topic", "Draft the summary", "Review the draft"]);
+        assert!(session.history[0].content.contains("[>] 1. Research the topic"));
     }
 }
@@ -0,0 +1,412 @@
+//! Time-triggered agent execution.
+//!
+//! Runs `UserIntent::Schedule` intents on a cron schedule. Each run is
+//! submitted to the wrapped controller as a fresh intent, so every run gets
+//! its own session.
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use multi_agent_core::{
+    traits::Controller,
+    types::UserIntent,
+    Error, Result,
+};
+
+/// State of a registered schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// Unique schedule ID.
+    pub id: String,
+    /// Cron expression (sec min hour day-of-month month day-of-week [year]).
+    pub cron_expr: String,
+    /// Intent executed on each run.
+    pub intent: UserIntent,
+    /// Maximum number of runs (None = unlimited).
+    pub max_runs: Option<u32>,
+    /// Number of runs started so far.
+    pub run_count: u32,
+    /// Last run time (unix millis).
+    pub last_run_at: Option<i64>,
+    /// Next scheduled run time (unix millis, None if exhausted).
+    pub next_run_at: Option<i64>,
+}
+
+impl ScheduleEntry {
+    /// Check if the schedule has no runs left.
+    pub fn is_exhausted(&self) -> bool {
+        self.next_run_at.is_none()
+    }
+
+    /// Record a run started at `now` and move `next_run_at` to the following cron time.
+    pub(crate) fn record_run(&mut self, now: DateTime<Utc>) -> Result<()> {
+        self.run_count += 1;
+        self.last_run_at = Some(now.timestamp_millis());
+        self.next_run_at = if self.max_runs.is_some_and(|max| self.run_count >= max) {
+            None
+        } else {
+            next_after(&parse_cron(&self.cron_expr)?, now)
+        };
+        Ok(())
+    }
+}
+
+/// Storage for schedule state.
+#[async_trait::async_trait]
+pub trait ScheduleStore: Send + Sync {
+    /// Save a schedule entry.
+    async fn save(&self, entry: &ScheduleEntry) -> Result<()>;
+
+    /// Load a schedule entry by ID.
+    async fn load(&self, id: &str) -> Result<Option<ScheduleEntry>>;
+
+    /// Delete a schedule entry.
+    async fn delete(&self, id: &str) -> Result<()>;
+
+    /// List all schedule entries.
+    async fn list(&self) -> Result<Vec<ScheduleEntry>>;
+}
+
+/// In-memory schedule store.
+pub struct InMemoryScheduleStore {
+    entries: DashMap<String, ScheduleEntry>,
+}
+
+impl InMemoryScheduleStore {
+    /// Create a new in-memory schedule store.
+    pub fn new() -> Self {
+        Self {
+            entries: DashMap::new(),
+        }
+    }
+}
+
+impl Default for InMemoryScheduleStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ScheduleStore for InMemoryScheduleStore {
+    async fn save(&self, entry: &ScheduleEntry) -> Result<()> {
+        self.entries.insert(entry.id.clone(), entry.clone());
+        Ok(())
+    }
+
+    async fn load(&self, id: &str) -> Result<Option<ScheduleEntry>> {
+        Ok(self.entries.get(id).map(|r| r.clone()))
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.entries.remove(id);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<ScheduleEntry>> {
+        Ok(self.entries.iter().map(|r| r.value().clone()).collect())
+    }
+}
+
+/// Source of the wall-clock time schedules run on, replaceable in tests.
+pub trait ScheduleClock: Send + Sync {
+    /// Current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock backed by `Utc::now`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemScheduleClock;
+
+impl ScheduleClock for SystemScheduleClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Controller wrapper that executes intents on a cron schedule.
+pub struct ScheduledController {
+    store: Arc<dyn ScheduleStore>,
+    clock: Arc<dyn ScheduleClock>,
+    /// Wakes the scheduler loop when schedules change.
+    changed: Arc<Notify>,
+}
+
+impl ScheduledController {
+    /// Create a new scheduled controller backed by the given store.
+    pub fn new(store: Arc<dyn ScheduleStore>) -> Self {
+        Self {
+            store,
+            clock: Arc::new(SystemScheduleClock),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Use a custom clock.
+    pub fn with_clock(mut self, clock: Arc<dyn ScheduleClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Register a `UserIntent::Schedule` and return its schedule ID.
+    pub async fn schedule(&self, intent: UserIntent) -> Result<String> {
+        let UserIntent::Schedule { cron_expr, intent, max_runs } = intent else {
+            return Err(Error::invalid_request("Expected a Schedule intent"));
+        };
+
+        if matches!(*intent, UserIntent::Schedule { .. }) {
+            return Err(Error::invalid_request("Schedule intents cannot be nested"));
+        }
+
+        let schedule = parse_cron(&cron_expr)?;
+        let entry = ScheduleEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            cron_expr,
+            intent: *intent,
+            max_runs,
+            run_count: 0,
+            last_run_at: None,
+            next_run_at: if max_runs == Some(0) { None } else { next_after(&schedule, self.clock.now()) },
+        };
+
+        self.store.save(&entry).await?;
+        self.changed.notify_one();
+
+        tracing::info!(schedule_id = %entry.id, cron = %entry.cron_expr, "Schedule registered");
+        Ok(entry.id)
+    }
+
+    /// List all registered schedules.
+    pub async fn list_schedules(&self) -> Result<Vec<ScheduleEntry>> {
+        self.store.list().await
+    }
+
+    /// Cancel a schedule. Runs already in progress are not interrupted.
+    pub async fn cancel_schedule(&self, id: &str) -> Result<()> {
+        if self.store.load(id).await?.is_none() {
+            return Err(Error::invalid_request(format!("Schedule {} not found", id)));
+        }
+        self.store.delete(id).await?;
+        self.changed.notify_one();
+
+        tracing::info!(schedule_id = %id, "Schedule cancelled");
+        Ok(())
+    }
+
+    /// Start the scheduler loop, submitting due intents to `controller`.
+    ///
+    /// Abort the returned handle to stop scheduling.
+    pub fn run_scheduler(&self, controller: Arc<dyn Controller>) -> JoinHandle<()> {
+        let store = self.store.clone();
+        let clock = self.clock.clone();
+        let changed = self.changed.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let next_wake = match Self::fire_due(&store, &controller, clock.now()).await {
+                    Ok(next_wake) => next_wake,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Scheduler tick failed");
+                        Some(clock.now().timestamp_millis() + 1000)
+                    }
+                };
+
+                match next_wake {
+                    Some(at) => {
+                        let delay = (at - clock.now().timestamp_millis()).max(0) as u64;
+                        tokio::select! {
+                            _ = tokio::time::sleep(std::time::Duration::from_millis(delay)) => {}
+                            _ = changed.notified() => {}
+                        }
+                    }
+                    None => changed.notified().await,
+                }
+            }
+        })
+    }
+
+    /// Fire all schedules due at `now` and return the earliest upcoming run time.
+    async fn fire_due(
+        store: &Arc<dyn ScheduleStore>,
+        controller: &Arc<dyn Controller>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<i64>> {
+        let mut next_wake: Option<i64> = None;
+
+        for mut entry in store.list().await? {
+            let Some(due) = entry.next_run_at else {
+                continue;
+            };
+
+            if due <= now.timestamp_millis() {
+                entry.record_run(now)?;
+                store.save(&entry).await?;
+
+                tracing::info!(
+                    schedule_id = %entry.id,
+                    run = entry.run_count,
+                    "Starting scheduled run"
+                );
+
+                let controller = controller.clone();
+                let intent = entry.intent.clone();
+                let schedule_id = entry.id.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = controller.execute(intent).await {
+                        tracing::warn!(schedule_id = %schedule_id, error = %e, "Scheduled run failed");
+                    }
+                });
+            }
+
+            if let Some(next) = entry.next_run_at {
+                next_wake = Some(next_wake.map_or(next, |w| w.min(next)));
+            }
+        }
+
+        Ok(next_wake)
+    }
+}
+
+pub(crate) fn parse_cron(expr: &str) -> Result<Schedule> {
+    Schedule::from_str(expr)
+        .map_err(|e| Error::invalid_request(format!("Invalid cron expression '{}': {}", expr, e)))
+}
+
+pub(crate) fn next_after(schedule: &Schedule, after: DateTime<Utc>) -> Option<i64> {
+    schedule
+        .after(&after)
+        .next()
+        .map(|t| t.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use multi_agent_core::types::AgentResult;
+    use std::sync::Mutex;
+
+    /// Clock following tokio's (pausable) time from a fixed start.
+    struct TokioClock {
+        start: DateTime<Utc>,
+        origin: tokio::time::Instant,
+    }
+
+    impl TokioClock {
+        fn starting_at(start: DateTime<Utc>) -> Self {
+            Self {
+                start,
+                origin: tokio::time::Instant::now(),
+            }
+        }
+    }
+
+    impl ScheduleClock for TokioClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.start + chrono::Duration::from_std(self.origin.elapsed()).unwrap()
+        }
+    }
+
+    /// Records the clock time (unix millis) of each execution.
+    struct RecordingController {
+        clock: Arc<dyn ScheduleClock>,
+        runs: Mutex<Vec<i64>>,
+    }
+
+    impl RecordingController {
+        fn new(clock: Arc<dyn ScheduleClock>) -> Self {
+            Self {
+                clock,
+                runs: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Controller for RecordingController {
+        async fn execute(&self, _intent: UserIntent) -> Result<AgentResult> {
+            self.runs.lock().unwrap().push(self.clock.now().timestamp_millis());
+            Ok(AgentResult::Text("ok".to_string()))
+        }
+
+        async fn resume(&self, _session_id: &str) -> Result<AgentResult> {
+            Err(Error::controller("RecordingController does not resume sessions"))
+        }
+
+        async fn cancel(&self, _session_id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn every_second(max_runs: Option<u32>) -> UserIntent {
+        UserIntent::Schedule {
+            cron_expr: "* * * * * *".to_string(),
+            intent: Box::new(UserIntent::ComplexMission {
+                goal: "Daily analysis".to_string(),
+                context_summary: String::new(),
+                visual_refs: vec![],
+                trace_id: None,
+                user_id: None,
+            }),
+            max_runs,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_fires_on_time() {
+        let start = DateTime::from_timestamp_millis(1_700_000_000_250).unwrap();
+        let clock: Arc<dyn ScheduleClock> = Arc::new(TokioClock::starting_at(start));
+        let scheduler = ScheduledController::new(Arc::new(InMemoryScheduleStore::new()))
+            .with_clock(clock.clone());
+        let controller = Arc::new(RecordingController::new(clock));
+
+        let id = scheduler.schedule(every_second(Some(2))).await.unwrap();
+        let handle = scheduler.run_scheduler(controller.clone());
+
+        // Paused time auto-advances to each scheduled run
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        handle.abort();
+
+        // Scheduled on whole seconds
+        let runs = controller.runs.lock().unwrap().clone();
+        assert_eq!(runs, vec![1_700_000_001_000, 1_700_000_002_000]);
+
+        let entry = scheduler.list_schedules().await.unwrap().pop().unwrap();
+        assert_eq!(entry.id, id);
+        assert_eq!(entry.run_count, 2);
+        assert!(entry.is_exhausted());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_schedule() {
+        let scheduler = ScheduledController::new(Arc::new(InMemoryScheduleStore::new()));
+
+        let id = scheduler.schedule(every_second(None)).await.unwrap();
+        assert_eq!(scheduler.list_schedules().await.unwrap().len(), 1);
+
+        scheduler.cancel_schedule(&id).await.unwrap();
+        assert!(scheduler.list_schedules().await.unwrap().is_empty());
+        assert!(scheduler.cancel_schedule(&id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_rejects_invalid_cron() {
+        let scheduler = ScheduledController::new(Arc::new(InMemoryScheduleStore::new()));
+
+        let intent = UserIntent::Schedule {
+            cron_expr: "not a cron".to_string(),
+            intent: Box::new(UserIntent::FastAction {
+                tool_name: "echo".to_string(),
+                args: serde_json::json!({}),
+            }),
+            max_runs: None,
+        };
+        assert!(scheduler.schedule(intent).await.is_err());
+    }
+}
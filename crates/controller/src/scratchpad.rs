@@ -0,0 +1,160 @@
+//! Scratchpad shared between a parent agent and its delegated subagents.
+//!
+//! The `DelegationManager` shows the scratchpad to each subagent and merges
+//! the subagent's writes back when it completes, so siblings delegated later
+//! see earlier discoveries. `ScratchpadTool` exposes the same scratchpad to
+//! the ReAct loop.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use multi_agent_core::{traits::Tool, types::ToolOutput, Error, Result};
+
+/// Line prefix subagents use to write an entry: `SCRATCHPAD: key = value`.
+pub const SCRATCHPAD_WRITE_PREFIX: &str = "SCRATCHPAD:";
+
+/// Concurrent-safe key/value store shared across agents.
+#[derive(Debug, Clone, Default)]
+pub struct SharedScratchpad {
+    entries: Arc<Mutex<HashMap<String, Value>>>,
+}
+
+impl SharedScratchpad {
+    /// Create an empty scratchpad.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the value stored under `key`.
+    pub fn read(&self, key: &str) -> Option<Value> {
+        self.lock().get(key).cloned()
+    }
+
+    /// Store `value` under `key`, replacing any previous value.
+    pub fn write(&self, key: impl Into<String>, value: Value) {
+        self.lock().insert(key.into(), value);
+    }
+
+    /// Copy of all entries.
+    pub fn snapshot(&self) -> HashMap<String, Value> {
+        self.lock().clone()
+    }
+
+    /// Apply a batch of writes.
+    pub fn merge(&self, writes: HashMap<String, Value>) {
+        self.lock().extend(writes);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Value>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Collect the `SCRATCHPAD: key = value` lines of a subagent response.
+///
+/// Values are parsed as JSON, falling back to a plain string.
+pub fn parse_scratchpad_writes(response: &str) -> HashMap<String, Value> {
+    response
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(SCRATCHPAD_WRITE_PREFIX))
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(key, _)| !key.trim().is_empty())
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+            (key.trim().to_string(), value)
+        })
+        .collect()
+}
+
+/// Tool reading and writing a `SharedScratchpad`.
+pub struct ScratchpadTool {
+    scratchpad: SharedScratchpad,
+}
+
+impl ScratchpadTool {
+    /// Expose `scratchpad` as the `scratchpad` tool.
+    pub fn new(scratchpad: SharedScratchpad) -> Self {
+        Self { scratchpad }
+    }
+}
+
+#[async_trait]
+impl Tool for ScratchpadTool {
+    fn name(&self) -> &str {
+        "scratchpad"
+    }
+
+    fn description(&self) -> &str {
+        "Read or write notes shared with delegated subagents"
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": { "type": "string", "enum": ["read", "write", "list"] },
+                "key": { "type": "string" },
+                "value": { "description": "Value to store (write only)" }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput> {
+        let key = || {
+            args.get("key")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_request("Missing 'key' parameter"))
+        };
+        match args.get("action").and_then(Value::as_str) {
+            Some("read") => Ok(match self.scratchpad.read(key()?) {
+                Some(value) => ToolOutput::text(value.to_string()).with_data(value),
+                None => ToolOutput::text(format!("No scratchpad entry '{}'", key()?)),
+            }),
+            Some("write") => {
+                let value = args.get("value").cloned().unwrap_or(Value::Null);
+                self.scratchpad.write(key()?, value);
+                Ok(ToolOutput::text(format!("Stored scratchpad entry '{}'", key()?)))
+            }
+            Some("list") => {
+                let entries = json!(self.scratchpad.snapshot());
+                Ok(ToolOutput::text(entries.to_string()).with_data(entries))
+            }
+            _ => Err(Error::invalid_request("'action' must be read, write or list")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scratchpad_writes() {
+        let writes = parse_scratchpad_writes(
+            "Found it.\nSCRATCHPAD: capital = \"Paris\"\n  SCRATCHPAD: population = 2100000\nSCRATCHPAD: note = plain text",
+        );
+        assert_eq!(writes["capital"], json!("Paris"));
+        assert_eq!(writes["population"], json!(2100000));
+        assert_eq!(writes["note"], json!("plain text"));
+        assert_eq!(writes.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_scratchpad_tool_round_trip() {
+        let scratchpad = SharedScratchpad::new();
+        let tool = ScratchpadTool::new(scratchpad.clone());
+
+        tool.execute(json!({ "action": "write", "key": "lead", "value": { "id": 7 } }))
+            .await
+            .unwrap();
+        assert_eq!(scratchpad.read("lead"), Some(json!({ "id": 7 })));
+
+        let read = tool.execute(json!({ "action": "read", "key": "lead" })).await.unwrap();
+        assert_eq!(read.data, Some(json!({ "id": 7 })));
+        assert!(tool.execute(json!({ "action": "delete" })).await.is_err());
+    }
+}
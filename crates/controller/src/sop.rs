@@ -1,18 +1,22 @@
 //! SOP (Standard Operating Procedure) engine.
 //!
 //! Supports loading YAML-defined workflows and executing them
-//! in order or in parallel using the DAG executor.
+//! in order or in parallel using the DAG executor. `SopRunner` runs
+//! prompt-driven SOPs step by step against an LLM.
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use multi_agent_core::{
-    traits::{SopDefinition, SopEngine, SopStep, ToolRegistry},
+    traits::{LlmClient, SopDefinition, SopEngine, SopStep, ToolRegistry},
     types::AgentResult,
     Error, Result,
 };
 
+use crate::parser::{ActionParser, DefaultTextParser, ReActAction};
+
 /// YAML SOP definition for parsing.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct YamlSopDefinition {
@@ -42,6 +46,9 @@ pub struct YamlSopStep {
     /// Tools allowed for this step (privilege de-escalation).
     #[serde(default)]
     pub allow_tools: Vec<String>,
+    /// Run concurrently with other steps in the same group.
+    #[serde(default)]
+    pub parallel_group: Option<String>,
 }
 
 /// Default SOP engine implementation.
@@ -130,6 +137,7 @@ impl SopEngine for DefaultSopEngine {
                     args: s.args,
                     depends_on: s.depends_on,
                     allow_tools: s.allow_tools,
+                    parallel_group: s.parallel_group,
                 })
                 .collect(),
         })
@@ -147,8 +155,11 @@ impl SopEngine for DefaultSopEngine {
             tools: tools.clone(),
         }).collect();
 
-        let executor = crate::dag::DagExecutor::new(sop.allow_parallel);
-        let results = executor.execute(tasks).await?;
+        let results = if !sop.allow_parallel && sop.steps.iter().any(|s| s.parallel_group.is_some()) {
+            execute_staged(tasks).await?
+        } else {
+            crate::dag::DagExecutor::new(sop.allow_parallel).execute(tasks).await?
+        };
 
         Ok(AgentResult::Data(serde_json::json!({
             "sop": sop.name,
@@ -157,9 +168,283 @@ impl SopEngine for DefaultSopEngine {
     }
 }
 
+/// Execute steps in declaration order, running each parallel group as one stage.
+///
+/// A group runs at the position of its first step. If any step in a group
+/// fails, the rest of the group is cancelled and the SOP aborts.
+async fn execute_staged(tasks: Vec<SopTask>) -> Result<HashMap<String, String>> {
+    use crate::dag::DagTask;
+
+    let mut stages: Vec<Vec<SopTask>> = Vec::new();
+    let mut group_stage: HashMap<String, usize> = HashMap::new();
+    for task in tasks {
+        match task.step.parallel_group.clone() {
+            Some(group) => match group_stage.get(&group) {
+                Some(&stage) => stages[stage].push(task),
+                None => {
+                    group_stage.insert(group, stages.len());
+                    stages.push(vec![task]);
+                }
+            },
+            None => stages.push(vec![task]),
+        }
+    }
+
+    let mut results = HashMap::new();
+    for stage in stages {
+        for task in &stage {
+            if let Some(dep) = task.dependencies().iter().find(|d| !results.contains_key(*d)) {
+                return Err(Error::SopExecution(format!(
+                    "Step '{}' depends on '{}', which does not run in an earlier stage",
+                    task.name(),
+                    dep
+                )));
+            }
+        }
+
+        let outputs = futures::future::try_join_all(
+            stage.iter().map(|task| task.execute(&results)),
+        )
+        .await?;
+        for (task, output) in stage.iter().zip(outputs) {
+            results.insert(task.name().to_string(), output);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Check applied to a prompt step's output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum SuccessCondition {
+    /// Any output succeeds.
+    #[default]
+    Always,
+    /// The output must contain this text.
+    Contains(String),
+    /// The output must not contain this text.
+    NotContains(String),
+}
+
+impl SuccessCondition {
+    /// Check whether `output` satisfies the condition.
+    pub fn is_met(&self, output: &str) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Contains(text) => output.contains(text.as_str()),
+            Self::NotContains(text) => !output.contains(text.as_str()),
+        }
+    }
+}
+
+/// A prompt-driven SOP step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptStep {
+    /// Step name, also the variable later steps use to refer to its output.
+    pub name: String,
+    /// Prompt template; `{{var}}` is replaced by an input or an earlier step's output.
+    pub prompt: String,
+    /// Tool the step must call. The LLM supplies the arguments and the
+    /// tool output becomes the step output.
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// Condition the step output must meet for the SOP to continue.
+    #[serde(default)]
+    pub success: SuccessCondition,
+}
+
+/// A prompt-driven SOP whose steps run in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sop {
+    /// SOP name.
+    pub name: String,
+    /// Steps in execution order.
+    pub steps: Vec<PromptStep>,
+}
+
+/// Runs prompt-driven SOPs step by step.
+pub struct SopRunner {
+    llm: Arc<dyn LlmClient>,
+    tools: Option<Arc<dyn ToolRegistry>>,
+}
+
+impl SopRunner {
+    /// Create a runner using the given LLM.
+    pub fn new(llm: Arc<dyn LlmClient>) -> Self {
+        Self { llm, tools: None }
+    }
+
+    /// Set the tool registry used by steps with a required tool.
+    pub fn with_tools(mut self, tools: Arc<dyn ToolRegistry>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Run all steps in order and return each step's output keyed by step name.
+    ///
+    /// Stops with `Error::SopExecution` at the first step whose output does
+    /// not meet its success condition.
+    pub async fn execute(&self, sop: &Sop, inputs: HashMap<String, String>) -> Result<HashMap<String, String>> {
+        tracing::info!(sop = %sop.name, steps = sop.steps.len(), "Running prompt SOP");
+
+        let mut vars = inputs;
+        let mut outputs = HashMap::new();
+        for step in &sop.steps {
+            let output = self.run_step(step, &vars).await?;
+            if !step.success.is_met(&output) {
+                return Err(Error::SopExecution(format!(
+                    "Step '{}' did not meet its success condition {:?}",
+                    step.name, step.success
+                )));
+            }
+            vars.insert(step.name.clone(), output.clone());
+            outputs.insert(step.name.clone(), output);
+        }
+
+        Ok(outputs)
+    }
+
+    async fn run_step(&self, step: &PromptStep, vars: &HashMap<String, String>) -> Result<String> {
+        let mut prompt = render_template(&step.prompt, vars);
+        let Some(ref tool) = step.tool else {
+            return Ok(self.llm.complete(&prompt).await?.content);
+        };
+
+        let tools = self.tools.as_ref().ok_or_else(|| {
+            Error::SopExecution(format!("Step '{}' requires tool '{}' but no registry is configured", step.name, tool))
+        })?;
+
+        prompt.push_str(&format!("\n\nCall the '{}' tool. Respond with:\nACTION: {}\nARGS: <JSON arguments>", tool, tool));
+        let response = self.llm.complete(&prompt).await?.content;
+        let args = match DefaultTextParser::new(vec![]).parse(&response) {
+            ReActAction::ToolCall { name, args } if name == *tool => args,
+            _ => serde_json::from_str(response.trim()).map_err(|_| {
+                Error::SopExecution(format!("Step '{}' did not call the required tool '{}'", step.name, tool))
+            })?,
+        };
+
+        let output = tools
+            .execute(tool, args)
+            .await
+            .map_err(|e| Error::SopExecution(format!("Step '{}' failed: {}", step.name, e)))?;
+        if !output.success {
+            return Err(Error::SopExecution(format!("Step '{}' failed: {}", step.name, output.content)));
+        }
+        Ok(output.content)
+    }
+}
+
+/// Replace `{{var}}` (or `{{ var }}`) placeholders with their values.
+fn render_template(template: &str, vars: &HashMap<String, String>) -> String {
+    vars.iter().fold(template.to_string(), |acc, (key, value)| {
+        acc.replace(&format!("{{{{{}}}}}", key), value)
+            .replace(&format!("{{{{ {} }}}}", key), value)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use multi_agent_core::traits::Tool;
+    use multi_agent_core::types::{ToolDefinition, ToolOutput};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    /// Registry whose tools sleep, recording start order and peak concurrency.
+    #[derive(Default)]
+    struct SleepyRegistry {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+        started: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ToolRegistry for SleepyRegistry {
+        async fn register(&self, _tool: Box<dyn Tool>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get(&self, _name: &str) -> Result<Option<Box<dyn Tool>>> {
+            Ok(None)
+        }
+
+        async fn list(&self) -> Result<Vec<ToolDefinition>> {
+            Ok(vec![])
+        }
+
+        async fn execute(&self, name: &str, _args: serde_json::Value) -> Result<ToolOutput> {
+            self.started.lock().unwrap().push(name.to_string());
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+
+            if name == "broken" {
+                return Err(Error::tool_execution("boom"));
+            }
+            Ok(ToolOutput::text(format!("{} done", name)))
+        }
+    }
+
+    const GROUPED_SOP: &str = r#"
+        name: grouped_sop
+        steps:
+          - name: prepare
+            tool: prepare
+            args: {}
+          - name: fetch_a
+            tool: fetch_a
+            args: {}
+            parallel_group: fetch
+          - name: fetch_b
+            tool: fetch_b
+            args: {}
+            parallel_group: fetch
+          - name: merge
+            tool: merge
+            args: {}
+            depends_on: [fetch_a, fetch_b]
+        "#;
+
+    #[tokio::test]
+    async fn test_parallel_group_runs_concurrently() {
+        let registry = Arc::new(SleepyRegistry::default());
+        let engine = DefaultSopEngine::new().with_tools(registry.clone());
+        let sop = engine.load(GROUPED_SOP).await.unwrap();
+        assert_eq!(sop.steps[1].parallel_group.as_deref(), Some("fetch"));
+
+        let start = Instant::now();
+        let result = engine.execute(&sop, serde_json::json!({})).await.unwrap();
+        let elapsed = start.elapsed();
+
+        // Only the grouped steps overlap: 3 stages of ~50ms instead of 4
+        assert_eq!(registry.peak.load(Ordering::SeqCst), 2);
+        assert!(elapsed < Duration::from_millis(190), "took {:?}", elapsed);
+
+        let started = registry.started.lock().unwrap().clone();
+        assert_eq!(started.first().map(String::as_str), Some("prepare"));
+        assert_eq!(started.last().map(String::as_str), Some("merge"));
+
+        let AgentResult::Data(data) = result else {
+            panic!("Expected Data result");
+        };
+        assert_eq!(data["results"]["merge"], "merge done");
+    }
+
+    #[tokio::test]
+    async fn test_parallel_group_failure_aborts() {
+        let registry = Arc::new(SleepyRegistry::default());
+        let engine = DefaultSopEngine::new().with_tools(registry.clone());
+        let sop = engine
+            .load(&GROUPED_SOP.replace("tool: fetch_b", "tool: broken"))
+            .await
+            .unwrap();
+
+        assert!(engine.execute(&sop, serde_json::json!({})).await.is_err());
+        assert!(!registry.started.lock().unwrap().contains(&"merge".to_string()));
+    }
 
     #[test]
     fn test_yaml_parsing() {
@@ -182,4 +467,89 @@ mod tests {
         assert!(parsed.allow_parallel);
         assert_eq!(parsed.steps.len(), 2);
     }
+
+    /// Returns scripted responses in order, recording every prompt.
+    struct ScriptedLlm {
+        responses: Mutex<Vec<String>>,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl ScriptedLlm {
+        fn new(responses: &[&str]) -> Self {
+            Self {
+                responses: Mutex::new(responses.iter().rev().map(|r| r.to_string()).collect()),
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LlmClient for ScriptedLlm {
+        async fn complete(&self, prompt: &str) -> Result<multi_agent_core::traits::LlmResponse> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Ok(multi_agent_core::traits::LlmResponse {
+                content: self.responses.lock().unwrap().pop().unwrap_or_default(),
+                finish_reason: "stop".to_string(),
+                usage: Default::default(),
+                tool_calls: None,
+                reasoning: None,
+            })
+        }
+
+        async fn chat(&self, _messages: &[multi_agent_core::traits::ChatMessage]) -> Result<multi_agent_core::traits::LlmResponse> {
+            self.complete("").await
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![])
+        }
+    }
+
+    fn report_sop() -> Sop {
+        serde_yaml::from_str(
+            r#"
+            name: report
+            steps:
+              - name: outline
+                prompt: "Outline a report on {{topic}}"
+                success: { type: contains, value: "1." }
+              - name: report
+                prompt: "Write the report following this outline:\n{{ outline }}"
+                tool: publish
+                success: { type: not_contains, value: "error" }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prompt_sop_end_to_end() {
+        let llm = Arc::new(ScriptedLlm::new(&[
+            "1. Intro\n2. Findings",
+            "ACTION: publish\nARGS: {\"title\": \"Rust\"}",
+        ]));
+        let sop = report_sop();
+        let runner = SopRunner::new(llm.clone()).with_tools(Arc::new(SleepyRegistry::default()));
+
+        let inputs = HashMap::from([("topic".to_string(), "Rust".to_string())]);
+        let outputs = runner.execute(&sop, inputs).await.unwrap();
+
+        assert_eq!(outputs["outline"], "1. Intro\n2. Findings");
+        assert_eq!(outputs["report"], "publish done");
+
+        let prompts = llm.prompts.lock().unwrap().clone();
+        assert_eq!(prompts[0], "Outline a report on Rust");
+        assert!(prompts[1].contains("following this outline:\n1. Intro\n2. Findings"), "{}", prompts[1]);
+        assert!(prompts[1].contains("ACTION: publish"));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_sop_stops_on_failed_condition() {
+        let llm = Arc::new(ScriptedLlm::new(&["No outline today", "unused"]));
+        let runner = SopRunner::new(llm.clone()).with_tools(Arc::new(SleepyRegistry::default()));
+
+        let err = runner.execute(&report_sop(), HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("Step 'outline'"), "{}", err);
+        assert_eq!(llm.prompts.lock().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,118 @@
+//! Stop conditions ending a mission without a FINAL ANSWER.
+//!
+//! Conditions are evaluated after every tool observation; the first one
+//! returning a result ends the ReAct loop with that result.
+
+use multi_agent_core::types::{AgentResult, FileArtifact, Session, ToolOutput};
+
+/// Condition checked after each tool call.
+pub trait StopCondition: Send + Sync {
+    /// Return the mission result if the call to `tool` should end the mission.
+    ///
+    /// `output` is None when the tool did not run or raised an error.
+    fn should_stop(&self, tool: &str, output: Option<&ToolOutput>, session: &Session) -> Option<AgentResult>;
+}
+
+/// Ends the mission when `tool_name` succeeds, returning its output.
+///
+/// An output creating several references is returned as `AgentResult::Files`,
+/// structured `data` as `AgentResult::Data`, otherwise the output content as
+/// `AgentResult::Text`.
+#[derive(Debug, Clone)]
+pub struct ToolResultStop {
+    pub tool_name: String,
+}
+
+impl ToolResultStop {
+    /// Stop once `tool_name` returns a successful result.
+    pub fn new(tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+        }
+    }
+}
+
+impl StopCondition for ToolResultStop {
+    fn should_stop(&self, tool: &str, output: Option<&ToolOutput>, _session: &Session) -> Option<AgentResult> {
+        let output = output.filter(|output| output.success && tool == self.tool_name)?;
+        if output.created_refs.len() > 1 {
+            return Some(AgentResult::Files(file_artifacts(output)));
+        }
+        Some(match output.data {
+            Some(ref data) => AgentResult::Data(data.clone()),
+            None => AgentResult::Text(output.content.clone()),
+        })
+    }
+}
+
+/// Describe the references created by `output` as files.
+///
+/// Names and MIME types come from the `files` entries of the structured data
+/// (`{"ref_id", "filename", "mime_type"}`); undescribed references are named
+/// after their ID.
+fn file_artifacts(output: &ToolOutput) -> Vec<FileArtifact> {
+    let described: Vec<FileArtifact> = output
+        .data
+        .as_ref()
+        .and_then(|data| data.get("files"))
+        .and_then(|files| serde_json::from_value(files.clone()).ok())
+        .unwrap_or_default();
+
+    output
+        .created_refs
+        .iter()
+        .map(|ref_id| {
+            described
+                .iter()
+                .find(|file| file.ref_id == *ref_id)
+                .cloned()
+                .unwrap_or_else(|| FileArtifact::new(ref_id.clone(), ref_id.as_str(), "application/octet-stream"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multi_agent_core::types::{RefId, TokenUsage};
+
+    fn session() -> Session {
+        let mut session = Session {
+            created_at: 0,
+            updated_at: 0,
+            ..Session::new("s")
+        };
+        session.token_usage = TokenUsage::with_budget(1000);
+        session
+    }
+
+    #[test]
+    fn test_tool_result_stop() {
+        let stop = ToolResultStop::new("submit");
+        let session = session();
+
+        let result = stop.should_stop("submit", Some(&ToolOutput::text("report.pdf")), &session);
+        assert!(matches!(result, Some(AgentResult::Text(ref t)) if t == "report.pdf"));
+
+        assert!(stop.should_stop("search", Some(&ToolOutput::text("x")), &session).is_none());
+        assert!(stop.should_stop("submit", None, &session).is_none());
+        assert!(stop.should_stop("submit", Some(&ToolOutput::error("bad")), &session).is_none());
+    }
+
+    #[test]
+    fn test_tool_result_stop_with_several_files() {
+        let stop = ToolResultStop::new("scaffold");
+        let mut output = ToolOutput::text("Generated the project").with_data(serde_json::json!({
+            "files": [{ "ref_id": "ref-main", "filename": "main.rs", "mime_type": "text/x-rust" }]
+        }));
+        output.created_refs = vec![RefId::from_string("ref-main"), RefId::from_string("ref-readme")];
+
+        let Some(AgentResult::Files(files)) = stop.should_stop("scaffold", Some(&output), &session()) else {
+            panic!("expected Files");
+        };
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0], FileArtifact::new(RefId::from_string("ref-main"), "main.rs", "text/x-rust"));
+        assert_eq!(files[1].filename, "ref-readme");
+        assert_eq!(files[1].mime_type, "application/octet-stream");
+    }
+}
@@ -0,0 +1,146 @@
+//! Deterministic test utilities for driving the ReAct loop.
+//!
+//! Enabled in this crate's tests and, for other crates, by the `testing` feature.
+
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use multi_agent_core::{
+    traits::{ChatMessage, LlmClient, LlmResponse, LlmUsage},
+    Error, Result,
+};
+
+/// LLM client returning a fixed script of responses, one per call, in order.
+///
+/// Unlike `MockLlm` it never wraps around: once the script is exhausted every
+/// call fails, so a test notices when the loop makes more calls than expected.
+/// The messages of each call are recorded for inspection.
+#[derive(Default)]
+pub struct ScriptedLlmClient {
+    responses: Mutex<VecDeque<String>>,
+    requests: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+impl ScriptedLlmClient {
+    /// Create a client answering successive calls with `responses`.
+    pub fn new<I, S>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            responses: Mutex::new(responses.into_iter().map(Into::into).collect()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Messages sent with each call so far, in call order.
+    pub fn requests(&self) -> Vec<Vec<ChatMessage>> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Number of calls made so far.
+    pub fn call_count(&self) -> usize {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Responses not consumed yet.
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+#[async_trait]
+impl LlmClient for ScriptedLlmClient {
+    async fn complete(&self, prompt: &str) -> Result<LlmResponse> {
+        let message = ChatMessage {
+            role: "user".to_string(),
+            content: prompt.to_string(),
+            tool_calls: None,
+            images: Vec::new(),
+        };
+        self.chat(&[message]).await
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse> {
+        let call = {
+            let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+            requests.push(messages.to_vec());
+            requests.len()
+        };
+        let content = self
+            .responses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+            .ok_or_else(|| Error::internal(format!("ScriptedLlmClient has no response left for call {}", call)))?;
+
+        Ok(LlmResponse {
+            content,
+            finish_reason: "stop".to_string(),
+            usage: LlmUsage {
+                prompt_tokens: 10,
+                completion_tokens: 20,
+                total_tokens: 30,
+            },
+            tool_calls: None,
+            reasoning: None,
+        })
+    }
+
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(vec![0.5; 1536])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReActController;
+    use multi_agent_core::mocks::{MockToolRegistry, RecordingTool};
+    use multi_agent_core::traits::Controller;
+    use multi_agent_core::types::{AgentResult, UserIntent};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_scripted_multi_iteration_mission() {
+        let llm = Arc::new(ScriptedLlmClient::new([
+            "THOUGHT: I need the weather first.",
+            "ACTION: weather\nARGS: {\"city\": \"Paris\"}",
+            "FINAL ANSWER: It is sunny in Paris.",
+        ]));
+        let tool = Arc::new(RecordingTool::new("weather", "Looks up the weather", "sunny, 24C"));
+        let controller = ReActController::builder()
+            .with_llm(llm.clone())
+            .with_tools(Arc::new(MockToolRegistry::with_tools(vec![tool.clone()])))
+            .build()
+            .unwrap();
+
+        let intent = UserIntent::ComplexMission {
+            goal: "What is the weather in Paris?".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        };
+        let result = controller.execute(intent).await.unwrap();
+
+        assert!(matches!(result, AgentResult::Text(ref text) if text == "It is sunny in Paris."));
+        assert_eq!(llm.call_count(), 3);
+        assert_eq!(llm.remaining(), 0);
+        assert_eq!(tool.calls(), vec![serde_json::json!({ "city": "Paris" })]);
+
+        // The final call saw the tool observation
+        let last = llm.requests().pop().unwrap();
+        assert!(last.iter().any(|m| m.content.contains("sunny, 24C")));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_script_fails() {
+        let llm = ScriptedLlmClient::new(["only"]);
+        assert_eq!(llm.complete("first").await.unwrap().content, "only");
+        assert!(llm.complete("second").await.is_err());
+        assert_eq!(llm.call_count(), 2);
+    }
+}
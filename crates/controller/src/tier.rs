@@ -0,0 +1,133 @@
+//! Per-iteration model tier selection.
+//!
+//! When the controller is given one LLM per `ModelTier`, a `TierSelector`
+//! picks which tier answers each ReAct iteration, so routine steps run on a
+//! cheap model and hard ones escalate to a stronger one.
+
+use multi_agent_core::types::{ModelTier, Session};
+
+/// Chooses the model tier for the next ReAct iteration.
+pub trait TierSelector: Send + Sync {
+    /// Select the tier that should answer the next LLM call for `session`.
+    fn select(&self, session: &Session) -> ModelTier;
+}
+
+/// Default selector: `Fast` for routine continuation, `Premium` when the
+/// history is long or the most recent tool call failed.
+#[derive(Debug, Clone)]
+pub struct HeuristicTierSelector {
+    /// History length from which every iteration escalates to `Premium`.
+    pub long_history_threshold: usize,
+}
+
+impl HeuristicTierSelector {
+    /// Create a selector escalating at the given history length.
+    pub fn new(long_history_threshold: usize) -> Self {
+        Self { long_history_threshold }
+    }
+}
+
+impl Default for HeuristicTierSelector {
+    fn default() -> Self {
+        Self::new(20)
+    }
+}
+
+impl TierSelector for HeuristicTierSelector {
+    fn select(&self, session: &Session) -> ModelTier {
+        if session.history.len() >= self.long_history_threshold || last_tool_failed(session) {
+            ModelTier::Premium
+        } else {
+            ModelTier::Fast
+        }
+    }
+}
+
+/// Whether the most recent tool observation reports anything but success.
+fn last_tool_failed(session: &Session) -> bool {
+    session
+        .history
+        .iter()
+        .rev()
+        .find_map(|entry| entry.tool_call.as_ref())
+        .and_then(|call| {
+            let result = call.result.as_ref()?;
+            Some(!result.starts_with(&format!("Tool '{}' succeeded", call.name)))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multi_agent_core::types::{HistoryEntry, ToolCallInfo};
+    use std::sync::Arc;
+
+    fn session_with(entries: Vec<HistoryEntry>) -> Session {
+        let mut session = Session {
+            created_at: 0,
+            updated_at: 0,
+            ..Session::new("tier-test")
+        };
+        session.history = entries;
+        session
+    }
+
+    fn entry(role: &str, content: &str) -> HistoryEntry {
+        HistoryEntry {
+            role: role.to_string(),
+            content: Arc::new(content.to_string()),
+            tool_call: None,
+            timestamp: 0,
+            compressed_from: None,
+            images: Vec::new(),
+        }
+    }
+
+    fn observation(name: &str, result: &str) -> HistoryEntry {
+        HistoryEntry {
+            tool_call: Some(ToolCallInfo {
+                name: name.to_string(),
+                arguments: serde_json::json!({}),
+                result: Some(Arc::new(result.to_string())),
+                retry_count: 0,
+            }),
+            ..entry("user", &format!("OBSERVATION: {}", result))
+        }
+    }
+
+    #[test]
+    fn test_fast_for_routine_iterations() {
+        let selector = HeuristicTierSelector::default();
+        assert_eq!(selector.select(&session_with(vec![entry("system", "prompt")])), ModelTier::Fast);
+
+        let session = session_with(vec![
+            entry("system", "prompt"),
+            observation("search", "Tool 'search' succeeded:\nresults"),
+        ]);
+        assert_eq!(selector.select(&session), ModelTier::Fast);
+    }
+
+    #[test]
+    fn test_escalates_after_tool_failure() {
+        let selector = HeuristicTierSelector::default();
+        let mut session = session_with(vec![
+            entry("system", "prompt"),
+            observation("search", "Tool 'search' succeeded:\nresults"),
+            entry("assistant", "ACTION: fetch"),
+            observation("fetch", "Tool 'fetch' error: connection refused"),
+        ]);
+        assert_eq!(selector.select(&session), ModelTier::Premium);
+
+        // A later successful call de-escalates
+        session.history.push(observation("fetch", "Tool 'fetch' succeeded:\npage"));
+        assert_eq!(selector.select(&session), ModelTier::Fast);
+    }
+
+    #[test]
+    fn test_escalates_for_long_history() {
+        let selector = HeuristicTierSelector::new(3);
+        let session = session_with(vec![entry("system", "prompt"), entry("assistant", "a"), entry("user", "b")]);
+        assert_eq!(selector.select(&session), ModelTier::Premium);
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use multi_agent_controller::{ApprovalDecision, ApprovalHook, ReActController};
+use multi_agent_core::mocks::{MockLlm, MockToolRegistry, RecordingTool};
+use multi_agent_core::traits::Controller;
+use multi_agent_core::types::{AgentResult, UserIntent};
+use serde_json::Value;
+
+/// Denies every call it is asked about.
+struct DenyAll;
+
+#[async_trait]
+impl ApprovalHook for DenyAll {
+    async fn approve(&self, tool: &str, _args: &Value) -> ApprovalDecision {
+        ApprovalDecision::deny(format!("{} needs a human", tool))
+    }
+}
+
+fn mission() -> UserIntent {
+    UserIntent::ComplexMission {
+        goal: "Clean up old reports".to_string(),
+        context_summary: String::new(),
+        visual_refs: vec![],
+        trace_id: None,
+        user_id: None,
+    }
+}
+
+fn llm() -> Arc<MockLlm> {
+    Arc::new(MockLlm::new(vec![
+        "ACTION: delete_file\nARGS: {\"path\": \"old.txt\"}\nACTION: list_files\nARGS: {}".to_string(),
+        "FINAL ANSWER: done".to_string(),
+    ]))
+}
+
+fn tools(delete: &Arc<RecordingTool>, list: &Arc<RecordingTool>) -> Arc<MockToolRegistry> {
+    Arc::new(MockToolRegistry::with_tools(vec![delete.clone(), list.clone()]))
+}
+
+#[tokio::test]
+async fn test_builder_enforces_approval_on_listed_tools() {
+    let delete = Arc::new(RecordingTool::new("delete_file", "Deletes a file", "deleted"));
+    let list = Arc::new(RecordingTool::new("list_files", "Lists files", "old.txt"));
+
+    let controller = ReActController::builder()
+        .with_llm(llm())
+        .with_tools(tools(&delete, &list))
+        .with_approval_hook(Arc::new(DenyAll))
+        .requires_approval(["delete_file"])
+        .build().unwrap();
+
+    let result = controller.execute(mission()).await.unwrap();
+    assert!(matches!(result, AgentResult::Text(ref t) if t == "done"));
+    assert!(delete.calls().is_empty());
+    assert_eq!(list.calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_builder_without_approval_config_runs_tools() {
+    let delete = Arc::new(RecordingTool::new("delete_file", "Deletes a file", "deleted"));
+    let list = Arc::new(RecordingTool::new("list_files", "Lists files", "old.txt"));
+
+    let controller = ReActController::builder()
+        .with_llm(llm())
+        .with_tools(tools(&delete, &list))
+        .build().unwrap();
+
+    controller.execute(mission()).await.unwrap();
+    assert_eq!(delete.calls().len(), 1);
+    assert_eq!(list.calls().len(), 1);
+}
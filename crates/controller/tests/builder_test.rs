@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use multi_agent_controller::{
+    HeuristicTierSelector, InMemorySessionStore, ReActConfig, ReActController,
+};
+use multi_agent_controller::context::CompressionConfig;
+use multi_agent_core::mocks::MockLlm;
+use multi_agent_core::Error;
+
+fn assert_invalid(result: multi_agent_core::Result<ReActController>, expected: &str) {
+    match result {
+        Err(Error::InvalidRequest(msg)) => assert!(msg.contains(expected), "{}", msg),
+        Err(e) => panic!("Expected InvalidRequest, got {}", e),
+        Ok(_) => panic!("Expected an invalid configuration error"),
+    }
+}
+
+#[test]
+fn test_persistence_without_session_store_is_rejected() {
+    let result = ReActController::builder().with_persistence(true).build();
+    assert_invalid(result, "no session store");
+
+    let controller = ReActController::builder()
+        .with_persistence(true)
+        .with_session_store(Arc::new(InMemorySessionStore::new()))
+        .build();
+    assert!(controller.is_ok());
+}
+
+#[test]
+fn test_compression_config_without_compressor_is_rejected() {
+    let result = ReActController::builder()
+        .with_compression_config(CompressionConfig::default())
+        .build();
+    assert_invalid(result, "no compressor");
+
+    let controller = ReActController::builder()
+        .with_compression_config(CompressionConfig::default())
+        .with_summarizing_compressor(Arc::new(MockLlm::constant("summary")))
+        .build();
+    assert!(controller.is_ok());
+}
+
+#[test]
+fn test_tier_selector_without_tier_llms_is_rejected() {
+    let result = ReActController::builder()
+        .with_tier_selector(Arc::new(HeuristicTierSelector::default()))
+        .build();
+    assert_invalid(result, "no tier LLMs");
+
+    let controller = ReActController::builder()
+        .with_tier_selector(Arc::new(HeuristicTierSelector::default()))
+        .with_tier_llms(HashMap::from([(
+            multi_agent_core::types::ModelTier::Fast,
+            Arc::new(MockLlm::constant("FINAL ANSWER: ok")) as Arc<dyn multi_agent_core::traits::LlmClient>,
+        )]))
+        .build();
+    assert!(controller.is_ok());
+}
+
+#[test]
+fn test_planning_without_llm_is_rejected() {
+    let config = ReActConfig {
+        enable_planning: true,
+        ..Default::default()
+    };
+    let result = ReActController::builder().with_config(config.clone()).build();
+    assert_invalid(result, "enable_planning requires an LLM");
+
+    let controller = ReActController::builder()
+        .with_config(config)
+        .with_llm(Arc::new(MockLlm::constant("1. Do it")))
+        .build();
+    assert!(controller.is_ok());
+}
+
+#[test]
+fn test_default_builder_is_valid() {
+    assert!(ReActController::builder().build().is_ok());
+}
@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use multi_agent_controller::ReActController;
+use multi_agent_core::mocks::{MockLlm, MockSessionStore};
+use multi_agent_core::traits::{Controller, ToolRegistry};
+use multi_agent_core::types::{AgentResult, UserIntent};
+use multi_agent_core::Result;
+use multi_agent_skills::{DefaultToolRegistry, McpClient, McpToolAdapter, McpTransport};
+use serde_json::{json, Value};
+
+/// MCP transport stand-in for a weather server, recording tool calls.
+#[derive(Default)]
+struct MockWeatherServer {
+    calls: Mutex<Vec<Value>>,
+}
+
+#[async_trait]
+impl McpClient for MockWeatherServer {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        Ok(match method {
+            "initialize" => json!({ "protocolVersion": "2024-11-05", "capabilities": { "tools": {} } }),
+            "tools/list" => json!({
+                "tools": [{
+                    "name": "forecast",
+                    "description": "Weather forecast for a city",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": { "city": { "type": "string" } },
+                        "required": ["city"]
+                    }
+                }]
+            }),
+            "tools/call" => {
+                self.calls.lock().unwrap().push(params);
+                json!({ "content": [{ "type": "text", "text": "Sunny, 24C" }] })
+            }
+            _ => json!({}),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_agent_calls_remote_mcp_tool() {
+    let server = Arc::new(MockWeatherServer::default());
+    let adapter = Arc::new(McpToolAdapter::new());
+    let transport = McpTransport::Stdio {
+        command: "weather-server".to_string(),
+        args: vec![],
+    };
+    adapter.connect_client("weather", transport, server.clone()).await.unwrap();
+
+    let tools = Arc::new(DefaultToolRegistry::new());
+    assert_eq!(adapter.register_tools("weather", tools.as_ref()).await.unwrap(), 1);
+    assert!(tools.list().await.unwrap().iter().any(|t| t.name == "weather/forecast"));
+
+    let store = Arc::new(MockSessionStore::new());
+    let controller = ReActController::builder()
+        .with_llm(Arc::new(MockLlm::new(vec![
+            "ACTION: weather/forecast\nARGS: {\"city\": \"Paris\"}".to_string(),
+            "FINAL ANSWER: It is sunny in Paris".to_string(),
+        ])))
+        .with_tools(tools)
+        .with_session_store(store.clone())
+        .build()
+        .unwrap();
+
+    let result = controller
+        .execute(UserIntent::ComplexMission {
+            goal: "What is the weather in Paris?".to_string(),
+            context_summary: String::new(),
+            visual_refs: vec![],
+            trace_id: None,
+            user_id: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(matches!(result, AgentResult::Text(ref t) if t == "It is sunny in Paris"));
+    assert_eq!(
+        *server.calls.lock().unwrap(),
+        vec![json!({ "name": "forecast", "arguments": { "city": "Paris" } })]
+    );
+    let session = store.sessions().pop().unwrap();
+    assert!(session.history.iter().any(|e| e.content.contains("Sunny, 24C")));
+}
@@ -4,9 +4,8 @@ use multi_agent_core::traits::{LlmClient, LlmResponse, ChatMessage, MemoryStore,
 use multi_agent_core::Result;
 use multi_agent_controller::memory::MemoryCapability;
 use multi_agent_controller::capability::AgentCapability;
-use multi_agent_core::types::{Session, SessionStatus, TaskState, AgentResult};
+use multi_agent_core::types::{Session, TaskState, AgentResult, RefId};
 use multi_agent_store::SimpleVectorStore;
-use chrono::Utc;
 use uuid::Uuid;
 
 // --- Mock LLM ---
@@ -42,20 +41,8 @@ async fn test_memory_context_injection() -> Result<()> {
     store.add(entry).await?;
 
     // 3. Create Session with a goal
-    let mut session = Session {
-        id: Uuid::new_v4().to_string(),
-        history: Vec::new(),
-        created_at: Utc::now().timestamp(),
-        updated_at: Utc::now().timestamp(),
-        status: SessionStatus::Running,
-        token_usage: Default::default(),
-        task_state: Some(TaskState {
-            goal: "Refactor the controller".to_string(),
-            iteration: 0,
-            observations: Vec::new(),
-            pending_actions: Vec::new(),
-        }),
-    };
+    let mut session = Session::new(Uuid::new_v4().to_string());
+    session.task_state = Some(TaskState::new("Refactor the controller"));
 
     // 4. Run on_start (should populate history)
     memory.on_start(&mut session).await?;
@@ -77,20 +64,11 @@ async fn test_memory_archival() -> Result<()> {
     let memory = MemoryCapability::new(store.clone(), llm.clone(), 3, 0.5);
 
     // 2. Create Session
-    let mut session = Session {
-        id: "sess1".to_string(),
-        history: Vec::new(),
-        created_at: Utc::now().timestamp(),
-        updated_at: Utc::now().timestamp(),
-        status: SessionStatus::Running,
-        token_usage: Default::default(),
-        task_state: Some(TaskState {
-            goal: "Fix the bug".to_string(), // This is cached in on_start
-            iteration: 0,
-            observations: Vec::new(),
-            pending_actions: Vec::new(),
-        }),
-    };
+    let mut session = Session::new("sess1");
+    let mut task_state = TaskState::new("Fix the bug");
+    task_state.// This is cached in on_start
+            iteration = 0;
+    session.task_state = Some(task_state);
 
     // Call on_start to cache the goal
     memory.on_start(&mut session).await?;
@@ -108,3 +86,84 @@ async fn test_memory_archival() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_memory_link_two_hop_traversal() -> Result<()> {
+    let store = Arc::new(SimpleVectorStore::new());
+    let memory = MemoryCapability::new(store.clone(), Arc::new(MockLlm), 3, 0.5);
+
+    for (id, content) in [
+        ("alice", "Alice is a director"),
+        ("bob", "Bob is a team lead"),
+        ("carol", "Carol is an engineer"),
+        ("dave", "Dave is an intern"),
+    ] {
+        store.add(MemoryEntry {
+            id: id.to_string(),
+            content: content.to_string(),
+            embedding: vec![0.1, 0.2, 0.3],
+            metadata: Default::default(),
+        }).await?;
+    }
+
+    let id = |s: &str| RefId::from_string(s);
+    memory.link(&id("alice"), &id("bob"), "manages").await?;
+    memory.link(&id("bob"), &id("carol"), "manages").await?;
+    memory.link(&id("carol"), &id("dave"), "mentors").await?;
+
+    let related = memory.related(&id("alice"), 2).await?;
+    let ids: Vec<&str> = related.iter().map(|(e, _)| e.id.as_str()).collect();
+    assert_eq!(ids, vec!["bob", "carol"]);
+    assert!((related[0].1 - 1.0).abs() < f32::EPSILON);
+    assert!((related[1].1 - 0.5).abs() < f32::EPSILON);
+
+    // Dave is three hops away
+    let related = memory.related(&id("alice"), 3).await?;
+    assert_eq!(related.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_keyword_recall_ranks_relevant_entries() -> Result<()> {
+    let store = Arc::new(SimpleVectorStore::new());
+    let memory = MemoryCapability::keyword(store.clone(), 3);
+
+    memory.store("deploy", "Deploying the gateway requires the REDIS_URL variable").await?;
+    memory.store("billing", "Billing reports are generated every month").await?;
+    memory.store("redis", "Redis connection errors usually mean REDIS_URL is wrong").await?;
+    memory.store("lunch", "The team has lunch at noon").await?;
+
+    let hits = memory.recall("redis connection failed", 2).await?;
+    let keys: Vec<&str> = hits.iter().map(|h| h.key.as_str()).collect();
+    assert_eq!(keys, vec!["redis", "deploy"]);
+    assert!(hits[0].score > hits[1].score);
+    assert!(hits[0].content.contains("connection errors"));
+
+    // Unrelated queries recall nothing
+    assert!(memory.recall("quarterly roadmap", 3).await?.is_empty());
+
+    // Stored entries are regular memory entries
+    assert!(store.get("billing").await?.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_keyword_memory_injected_on_start() -> Result<()> {
+    let memory = MemoryCapability::keyword(Arc::new(SimpleVectorStore::new()), 3);
+    memory.store("tip", "Always run the migrations before deploying").await?;
+    memory.store("other", "Cats sleep a lot").await?;
+
+    let mut session = Session {
+        created_at: 0,
+        updated_at: 0,
+        ..Session::new("s1")
+    };
+    session.task_state = Some(TaskState::new("Deploying the new release"));
+    memory.on_start(&mut session).await?;
+
+    assert_eq!(session.history.len(), 1);
+    assert!(session.history[0].content.contains("run the migrations"));
+    assert!(!session.history[0].content.contains("Cats"));
+    Ok(())
+}
@@ -5,8 +5,7 @@ use multi_agent_core::LlmUsage;
 use multi_agent_core::Result;
 use multi_agent_controller::planning::PlanningCapability;
 use multi_agent_controller::capability::AgentCapability;
-use multi_agent_core::types::{Session, SessionStatus, TaskState};
-use chrono::Utc;
+use multi_agent_core::types::{Session, TaskState};
 use uuid::Uuid;
 
 // --- Mock LLM ---
@@ -21,6 +20,7 @@ impl LlmClient for MockPlannerLlm {
                 finish_reason: "stop".to_string(),
                 usage: LlmUsage::default(),
                 tool_calls: None,
+                reasoning: None,
             })
         } else {
             Ok(LlmResponse {
@@ -28,6 +28,7 @@ impl LlmClient for MockPlannerLlm {
                 finish_reason: "stop".to_string(),
                 usage: LlmUsage::default(),
                 tool_calls: None,
+                reasoning: None,
             })
         }
     }
@@ -47,20 +48,8 @@ async fn test_planning_generation() -> Result<()> {
     let planner = PlanningCapability::new(llm);
 
     // 2. Create Session
-    let mut session = Session {
-        id: Uuid::new_v4().to_string(),
-        history: Vec::new(),
-        created_at: Utc::now().timestamp(),
-        updated_at: Utc::now().timestamp(),
-        status: SessionStatus::Running,
-        token_usage: Default::default(),
-        task_state: Some(TaskState {
-            goal: "Build a house".to_string(),
-            iteration: 0,
-            observations: Vec::new(),
-            pending_actions: Vec::new(),
-        }),
-    };
+    let mut session = Session::new(Uuid::new_v4().to_string());
+    session.task_state = Some(TaskState::new("Build a house"));
 
     // 3. on_start (Should generate plan)
     planner.on_start(&mut session).await?;
@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use multi_agent_controller::react::{ReActConfig, ReActController};
+use multi_agent_core::traits::{Controller, Tool, ToolRegistry};
+use multi_agent_core::types::{AgentResult, ToolDefinition, ToolOutput, UserIntent};
+use multi_agent_core::Result;
+use multi_agent_skills::CompositeToolRegistry;
+use serde_json::{json, Value};
+
+const STARTUP_COST: Duration = Duration::from_millis(100);
+
+/// Registry with an expensive backend that initializes on first use unless preloaded.
+#[derive(Default)]
+struct SlowBackendRegistry {
+    loaded: AtomicBool,
+    preload_calls: AtomicUsize,
+    /// Whether the backend was already loaded when `execute` was first called.
+    loaded_at_first_execute: std::sync::Mutex<Option<bool>>,
+}
+
+impl SlowBackendRegistry {
+    async fn load(&self) {
+        if !self.loaded.load(Ordering::SeqCst) {
+            tokio::time::sleep(STARTUP_COST).await;
+            self.loaded.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolRegistry for SlowBackendRegistry {
+    async fn register(&self, _tool: Box<dyn Tool>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get(&self, _name: &str) -> Result<Option<Box<dyn Tool>>> {
+        Ok(None)
+    }
+
+    async fn list(&self) -> Result<Vec<ToolDefinition>> {
+        Ok(vec![])
+    }
+
+    async fn execute(&self, _name: &str, _args: Value) -> Result<ToolOutput> {
+        self.loaded_at_first_execute
+            .lock()
+            .unwrap()
+            .get_or_insert(self.loaded.load(Ordering::SeqCst));
+        self.load().await;
+        Ok(ToolOutput::text("ok"))
+    }
+
+    async fn preload(&self) -> Result<()> {
+        self.preload_calls.fetch_add(1, Ordering::SeqCst);
+        self.load().await;
+        Ok(())
+    }
+
+    fn is_preloaded(&self) -> bool {
+        self.loaded.load(Ordering::SeqCst)
+    }
+}
+
+fn fast_action() -> UserIntent {
+    UserIntent::FastAction {
+        tool_name: "model_inference".to_string(),
+        args: json!({}),
+    }
+}
+
+fn controller(registry: Arc<dyn ToolRegistry>, preload_tools: bool) -> ReActController {
+    ReActController::builder()
+        .with_config(ReActConfig {
+            preload_tools,
+            ..Default::default()
+        })
+        .with_tools(registry)
+        .build().unwrap()
+}
+
+#[tokio::test]
+async fn test_preload_before_first_execute() {
+    let registry = Arc::new(SlowBackendRegistry::default());
+    let controller = controller(registry.clone(), true);
+
+    let result = controller.execute(fast_action()).await.unwrap();
+    assert!(matches!(result, AgentResult::Text(_)));
+
+    assert_eq!(registry.preload_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(*registry.loaded_at_first_execute.lock().unwrap(), Some(true));
+}
+
+#[tokio::test]
+async fn test_no_preload_when_disabled() {
+    let registry = Arc::new(SlowBackendRegistry::default());
+    let controller = controller(registry.clone(), false);
+
+    controller.execute(fast_action()).await.unwrap();
+
+    assert_eq!(registry.preload_calls.load(Ordering::SeqCst), 0);
+    assert_eq!(*registry.loaded_at_first_execute.lock().unwrap(), Some(false));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_composite_preloads_all_registries_concurrently() {
+    let a = Arc::new(SlowBackendRegistry::default());
+    let b = Arc::new(SlowBackendRegistry::default());
+    let mut composite = CompositeToolRegistry::new();
+    composite.add_registry(a.clone());
+    composite.add_registry(b.clone());
+    assert!(!composite.is_preloaded());
+
+    let start = tokio::time::Instant::now();
+    composite.preload().await.unwrap();
+    let elapsed = start.elapsed();
+
+    assert!(composite.is_preloaded());
+    assert!(a.is_preloaded() && b.is_preloaded());
+    // Sequential preloading would take at least 2x the startup cost
+    assert!(elapsed < STARTUP_COST * 2, "preload took {:?}", elapsed);
+}
@@ -41,7 +41,7 @@ async fn test_unified_registry_routing() {
     // 5. Verify Controller Integration
     let controller = ReActController::builder()
         .with_tools(composite_arc.clone())
-        .build();
+        .build().unwrap();
         
     // (Controller logic for execution uses self.tools.execute(), which maps to Composite::execute)
     
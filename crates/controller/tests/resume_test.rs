@@ -1,7 +1,9 @@
 use std::sync::Arc;
 use multi_agent_controller::{ReActController, ReActConfig, InMemorySessionStore, SessionStore};
 use multi_agent_core::traits::Controller;
-use multi_agent_core::types::{Session, SessionStatus, TokenUsage, TaskState, HistoryEntry};
+use multi_agent_core::mocks::MockToolRegistry;
+use multi_agent_core::traits::{ChatMessage, LlmClient, LlmResponse};
+use multi_agent_core::types::{AgentResult, Session, SessionStatus, TaskState, HistoryEntry, UserIntent};
 use multi_agent_controller::chrono_timestamp;
 
 #[tokio::test]
@@ -12,37 +14,30 @@ async fn test_resume_session() -> anyhow::Result<()> {
     // 2. Setup controller with store
     let controller = ReActController::builder()
         .with_session_store(session_store.clone())
-        .build();
+        .build().unwrap();
 
     // 3. Create a mock session that looks like it was interrupted
     let session_id = "test-resume-session-id";
-    let session = Session {
-        id: session_id.to_string(),
-        status: SessionStatus::Running,
-        history: vec![
-            HistoryEntry {
-                role: "system".to_string(),
-                content: Arc::new("System prompt".to_string()),
-                tool_call: None,
-                timestamp: chrono_timestamp(),
-            },
-            HistoryEntry {
-                role: "user".to_string(),
-                content: Arc::new("Do something".to_string()),
-                tool_call: None,
-                timestamp: chrono_timestamp(),
-            }
-        ],
-        task_state: Some(TaskState {
-            iteration: 0,
-            goal: "Do something".to_string(),
-            observations: vec![],
-            pending_actions: vec![],
-        }),
-        token_usage: TokenUsage::default(),
-        created_at: chrono_timestamp(),
-        updated_at: chrono_timestamp(),
-    };
+    let mut session = Session::new(session_id);
+    session.history = vec![
+        HistoryEntry {
+            role: "system".to_string(),
+            content: Arc::new("System prompt".to_string()),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        },
+        HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new("Do something".to_string()),
+            tool_call: None,
+            timestamp: chrono_timestamp(),
+            compressed_from: None,
+            images: Vec::new(),
+        }
+    ];
+    session.task_state = Some(TaskState::new("Do something"));
 
     // 4. Save session manually to store
     session_store.save(&session).await?;
@@ -65,3 +60,158 @@ async fn test_resume_session() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// LLM that plays back a script of responses; `None` simulates a provider outage.
+struct ScriptedLlm {
+    script: std::sync::Mutex<std::collections::VecDeque<Option<&'static str>>>,
+}
+
+impl ScriptedLlm {
+    fn new(script: Vec<Option<&'static str>>) -> Self {
+        Self {
+            script: std::sync::Mutex::new(script.into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmClient for ScriptedLlm {
+    async fn complete(&self, _prompt: &str) -> multi_agent_core::Result<LlmResponse> {
+        match self.script.lock().unwrap().pop_front().flatten() {
+            Some(content) => Ok(LlmResponse {
+                content: content.to_string(),
+                finish_reason: "stop".to_string(),
+                usage: Default::default(),
+                tool_calls: None,
+                reasoning: None,
+            }),
+            None => Err(multi_agent_core::Error::ModelProvider("connection reset".to_string())),
+        }
+    }
+
+    async fn chat(&self, _messages: &[ChatMessage]) -> multi_agent_core::Result<LlmResponse> {
+        self.complete("").await
+    }
+
+    async fn embed(&self, _text: &str) -> multi_agent_core::Result<Vec<f32>> {
+        Ok(vec![])
+    }
+}
+
+fn controller_with(llm: ScriptedLlm, store: Arc<InMemorySessionStore>) -> ReActController {
+    ReActController::builder()
+        .with_config(ReActConfig {
+            max_iterations: 5,
+            ..Default::default()
+        })
+        .with_llm(Arc::new(llm))
+        .with_tools(Arc::new(MockToolRegistry::new()))
+        .with_session_store(store)
+        .build().unwrap()
+}
+
+#[tokio::test]
+async fn test_resume_interrupted_loop_to_completion() -> anyhow::Result<()> {
+    let store = Arc::new(InMemorySessionStore::new());
+
+    // First run: one tool call, then the provider goes away mid-loop
+    let first = controller_with(
+        ScriptedLlm::new(vec![Some("ACTION: search\nARGS: {\"q\": \"rust\"}"), None]),
+        store.clone(),
+    );
+    let intent = UserIntent::ComplexMission {
+        goal: "Research rust".to_string(),
+        context_summary: "Find recent news".to_string(),
+        visual_refs: vec![],
+        trace_id: None,
+        user_id: None,
+    };
+    assert!(first.execute(intent).await.is_err());
+
+    let session_id = store.list_running().await?.pop().expect("session persisted");
+    let interrupted = store.load(&session_id).await?.unwrap();
+    assert_eq!(interrupted.status, SessionStatus::Running);
+    assert_eq!(interrupted.task_state.as_ref().unwrap().iteration, 0);
+    let history_len = interrupted.history.len();
+
+    // Second run: resume with a healthy provider
+    let second = controller_with(ScriptedLlm::new(vec![Some("FINAL ANSWER: Rust is great")]), store.clone());
+    match second.resume(&interrupted.id).await? {
+        AgentResult::Text(text) => assert_eq!(text, "Rust is great"),
+        other => panic!("Expected Text result, got {:?}", other),
+    }
+
+    let resumed = store.load(&interrupted.id).await?.unwrap();
+    assert_eq!(resumed.status, SessionStatus::Completed);
+    assert_eq!(resumed.task_state.as_ref().unwrap().iteration, 1);
+    // Existing history is kept and extended
+    for (resumed, original) in resumed.history.iter().zip(&interrupted.history) {
+        assert_eq!(resumed.content, original.content);
+    }
+    assert!(resumed.history.len() > history_len);
+
+    // Resuming a completed session returns the final answer without calling the LLM
+    let third = controller_with(ScriptedLlm::new(vec![]), store.clone());
+    match third.resume(&interrupted.id).await? {
+        AgentResult::Text(text) => assert_eq!(text, "Rust is great"),
+        other => panic!("Expected Text result, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resume_errors() {
+    let without_store = ReActController::builder().build().unwrap();
+    assert!(without_store.resume("any").await.is_err());
+
+    let store = Arc::new(InMemorySessionStore::new());
+    let controller = controller_with(ScriptedLlm::new(vec![]), store);
+    assert!(controller.resume("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_resume_runs_pending_actions_first() -> anyhow::Result<()> {
+    use multi_agent_core::mocks::RecordingTool;
+
+    let store = Arc::new(InMemorySessionStore::new());
+    let search = Arc::new(RecordingTool::new("search", "Searches the web", "Rust 1.80 released"));
+    let plan_step = serde_json::json!({ "description": "Summarize the findings" });
+
+    // A session interrupted after the model asked for a search that never ran
+    let mut session = Session::new("pending-session");
+    session.history = vec![HistoryEntry {
+        role: "system".to_string(),
+        content: Arc::new("System prompt".to_string()),
+        tool_call: None,
+        timestamp: chrono_timestamp(),
+        compressed_from: None,
+        images: Vec::new(),
+    }];
+    let mut task_state = TaskState::new("Research rust");
+    task_state.iteration = 1;
+    task_state.pending_actions = vec![
+        plan_step.clone(),
+        serde_json::json!({ "tool": "search", "args": { "q": "rust" } }),
+    ];
+    session.task_state = Some(task_state);
+    store.save(&session).await?;
+
+    let controller = ReActController::builder()
+        .with_llm(Arc::new(ScriptedLlm::new(vec![Some("FINAL ANSWER: Rust 1.80 is out")])))
+        .with_tools(Arc::new(MockToolRegistry::with_tools(vec![search.clone()])))
+        .with_session_store(store.clone())
+        .build()?;
+    controller.resume("pending-session").await?;
+
+    assert_eq!(search.calls(), vec![serde_json::json!({ "q": "rust" })]);
+
+    let resumed = store.load("pending-session").await?.unwrap();
+    let observation = resumed.history.iter().position(|e| e.tool_call.is_some()).unwrap();
+    let answer = resumed.history.iter().position(|e| e.role == "assistant").unwrap();
+    assert!(observation < answer, "pending call must run before the LLM is called");
+    // Only the executed call is removed; plan steps stay
+    assert_eq!(resumed.task_state.unwrap().pending_actions, vec![plan_step]);
+
+    Ok(())
+}
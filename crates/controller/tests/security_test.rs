@@ -18,6 +18,7 @@ impl LlmClient for MockLlm {
             finish_reason: "stop".to_string(),
             usage: LlmUsage::default(),
             tool_calls: None,
+            reasoning: None,
         })
     }
 
@@ -27,6 +28,7 @@ impl LlmClient for MockLlm {
             finish_reason: "stop".to_string(),
             usage: LlmUsage::default(),
             tool_calls: None,
+            reasoning: None,
         })
     }
 
@@ -48,13 +50,15 @@ async fn test_security_pii_violation() {
         .with_config(config)
         .with_llm(Arc::new(MockLlm))
         .with_security(Arc::new(guardrail))
-        .build();
+        .build().unwrap();
 
     // 2. Intent with PII (Email)
     let intent = UserIntent::ComplexMission {
         goal: "Send an email to malicious@example.com including my SSN 123-45-6789".to_string(),
         context_summary: "".to_string(),
         visual_refs: vec![],
+        trace_id: None,
+        user_id: None,
     };
 
     // 3. Execute should fail (Security Block)
@@ -32,6 +32,18 @@ pub enum Error {
     #[error("ReAct loop exceeded max iterations: {0}")]
     MaxIterationsExceeded(usize),
 
+    #[error("ReAct loop exceeded max tool calls: {0}")]
+    MaxToolCallsExceeded(usize),
+
+    #[error("Timed out waiting for a controller pool permit")]
+    PoolTimeout,
+
+    #[error("Session was cancelled")]
+    Cancelled,
+
+    #[error("Mission timed out after {elapsed:?}")]
+    MissionTimeout { elapsed: std::time::Duration },
+
     #[error("State persistence error: {0}")]
     StatePersistence(String),
 
@@ -47,6 +59,16 @@ pub enum Error {
     #[error("Tool execution failed: {0}")]
     ToolExecution(String),
 
+    #[error("Invalid arguments for tool '{tool}': {reason}")]
+    ToolValidationFailed { tool: String, reason: String },
+
+    #[error("Tool execution failed: {tool}: {source}")]
+    ToolExecutionFailed {
+        tool: String,
+        #[source]
+        source: Box<Error>,
+    },
+
     #[error("MCP adapter error: {0}")]
     McpAdapter(String),
 
@@ -71,6 +93,9 @@ pub enum Error {
     #[error("Governance error: {0}")]
     Governance(String),
 
+    #[error("Content violation: {categories:?}")]
+    ContentViolation { categories: Vec<String> },
+
     // =========================================================================
     // Model Gateway Errors (L-M)
     // =========================================================================
@@ -83,6 +108,9 @@ pub enum Error {
     #[error("Model selection failed: {0}")]
     ModelSelection(String),
 
+    #[error("LLM error: {0}")]
+    LlmError(String),
+
     // =========================================================================
     // Template Errors (L-T)
     // =========================================================================
@@ -101,6 +129,9 @@ pub enum Error {
     #[error("Timeout: {0}")]
     Timeout(String),
 
+    #[error("Missing environment variable: {0}")]
+    MissingEnvVar(String),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 
@@ -133,6 +164,27 @@ impl Error {
         Self::ToolExecution(msg.into())
     }
 
+    /// Create a tool argument validation error.
+    pub fn tool_validation_failed(tool: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::ToolValidationFailed {
+            tool: tool.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Wrap an error raised while executing `tool`.
+    pub fn tool_execution_failed(tool: impl Into<String>, source: Error) -> Self {
+        Self::ToolExecutionFailed {
+            tool: tool.into(),
+            source: Box::new(source),
+        }
+    }
+
+    /// Create an LLM error.
+    pub fn llm(msg: impl Into<String>) -> Self {
+        Self::LlmError(msg.into())
+    }
+
     /// Create an MCP adapter error.
     pub fn mcp_adapter(msg: impl Into<String>) -> Self {
         Self::McpAdapter(msg.into())
@@ -153,4 +205,66 @@ impl Error {
         Self::Internal(msg.into())
     }
 
+    /// Check if the error is likely to go away on retry (timeouts, 5xx, rate limits).
+    pub fn is_transient(&self) -> bool {
+        const TRANSIENT_MARKERS: &[&str] = &[
+            "timeout",
+            "timed out",
+            "rate limit",
+            "too many requests",
+            "429",
+            "500",
+            "502",
+            "503",
+            "504",
+            "overloaded",
+            "unavailable",
+            "connection",
+        ];
+
+        match self {
+            Self::Timeout(_) | Self::PoolTimeout => true,
+            Self::ToolExecutionFailed { source, .. } => source.is_transient(),
+            Self::ModelProvider(msg) => {
+                let msg = msg.to_lowercase();
+                TRANSIENT_MARKERS.iter().any(|marker| msg.contains(marker))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        assert!(Error::Timeout("llm".to_string()).is_transient());
+        assert!(Error::ModelProvider("OpenAI error: 503 Service Unavailable".to_string()).is_transient());
+        assert!(Error::ModelProvider("Rate limit reached for gpt-4o".to_string()).is_transient());
+        assert!(Error::ModelProvider("connection reset by peer".to_string()).is_transient());
+
+        assert!(!Error::ModelProvider("OPENAI_API_KEY not set".to_string()).is_transient());
+        assert!(!Error::ModelProvider("401 Unauthorized".to_string()).is_transient());
+        assert!(!Error::BudgetExceeded { used: 1, limit: 1 }.is_transient());
+        assert!(!Error::controller("timeout").is_transient());
+        assert!(Error::tool_execution_failed("fetch", Error::Timeout("fetch".to_string())).is_transient());
+        assert!(!Error::tool_execution_failed("fetch", Error::tool_execution("404")).is_transient());
+    }
+
+    #[test]
+    fn test_tool_error_variants() {
+        let err = Error::tool_validation_failed("search", "- \"q\" is a required property");
+        assert!(matches!(err, Error::ToolValidationFailed { ref tool, .. } if tool == "search"));
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments for tool 'search': - \"q\" is a required property"
+        );
+
+        let err = Error::tool_execution_failed("fetch", Error::tool_execution("connection reset"));
+        assert_eq!(err.to_string(), "Tool execution failed: fetch: Tool execution failed: connection reset");
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(source.to_string(), "Tool execution failed: connection reset");
+    }
 }
@@ -69,6 +69,7 @@ impl LlmClient for MockLlm {
                 total_tokens: 30,
             },
             tool_calls: None,
+            reasoning: None,
         })
     }
 
@@ -150,6 +151,10 @@ impl MemoryStore for MockMemoryStore {
         Ok(results)
     }
 
+    async fn get(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        Ok(self.entries.lock().unwrap().get(id).cloned())
+    }
+
     async fn delete(&self, id: &str) -> Result<()> {
         let mut entries = self.entries.lock().unwrap();
         entries.remove(id);
@@ -295,6 +300,8 @@ impl MockRouter {
             goal: goal.to_string(),
             context_summary: String::new(),
             visual_refs: Vec::new(),
+            trace_id: None,
+            user_id: None,
         })
     }
 
@@ -373,6 +380,11 @@ impl MockSessionStore {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Get a snapshot of all stored sessions.
+    pub fn sessions(&self) -> Vec<Session> {
+        self.sessions.lock().unwrap().values().cloned().collect()
+    }
 }
 
 #[async_trait]
@@ -394,10 +406,10 @@ impl SessionStore for MockSessionStore {
         Ok(())
     }
 
-    async fn list_running(&self) -> Result<Vec<String>> {
+    async fn list_by_status(&self, status: crate::types::SessionStatus) -> Result<Vec<String>> {
         let sessions = self.sessions.lock().unwrap();
         Ok(sessions.iter()
-            .filter(|(_, s)| s.status == crate::types::SessionStatus::Running)
+            .filter(|(_, s)| s.status == status)
             .map(|(id, _)| id.clone())
             .collect())
     }
@@ -39,9 +39,14 @@ pub trait SessionStore: Send + Sync {
     
     /// Delete a session.
     async fn delete(&self, session_id: &str) -> Result<()>;
-    
+
+    /// List IDs of sessions with the given status.
+    async fn list_by_status(&self, status: crate::types::SessionStatus) -> Result<Vec<String>>;
+
     /// List all running sessions.
-    async fn list_running(&self) -> Result<Vec<String>>;
+    async fn list_running(&self) -> Result<Vec<String>> {
+        self.list_by_status(crate::types::SessionStatus::Running).await
+    }
 }
 
 /// SOP definition structure.
@@ -69,4 +74,6 @@ pub struct SopStep {
     /// Tools allowed for this step (privilege de-escalation).
     /// If empty, all tools are allowed.
     pub allow_tools: Vec<String>,
+    /// Steps sharing a group run concurrently in sequential SOPs.
+    pub parallel_group: Option<String>,
 }
@@ -11,6 +11,12 @@ pub trait IntentRouter: Send + Sync {
     async fn classify(&self, request: &NormalizedRequest) -> Result<UserIntent>;
 }
 
+/// Maps inbound system events (webhooks) to the intents they trigger.
+pub trait EventMapper: Send + Sync {
+    /// Map an event to an intent (None = the event triggers nothing).
+    fn map_event(&self, event_type: &str, payload: &serde_json::Value) -> Option<UserIntent>;
+}
+
 /// Semantic cache for high-frequency queries.
 #[async_trait]
 pub trait SemanticCache: Send + Sync {
@@ -3,7 +3,8 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use crate::error::Result;
-use crate::types::{AgentResult, NormalizedRequest};
+use crate::types::{AgentResult, NormalizedRequest, Session};
+use std::sync::Arc;
 
 /// Budget controller for token management.
 #[async_trait]
@@ -24,6 +25,69 @@ pub trait BudgetController: Send + Sync {
     async fn is_exceeded(&self, session_id: &str) -> Result<bool>;
 }
 
+/// PII anonymizer for data export.
+pub trait Anonymizer: Send + Sync {
+    /// Replace PII in text with pseudonymous tokens.
+    fn anonymize_text(&self, text: &str) -> String;
+
+    /// Replace an identifier (e.g. a user ID) with a stable pseudonym.
+    fn pseudonymize(&self, value: &str) -> String;
+
+    /// Replace PII in every string within a JSON value.
+    fn anonymize_json(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.anonymize_text(s)),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.anonymize_json(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.anonymize_json(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Secret redactor applied to sessions before they are persisted.
+pub trait Redactor: Send + Sync {
+    /// Mask secrets in text.
+    fn redact(&self, text: &str) -> String;
+
+    /// Mask secrets in every string within a JSON value.
+    fn redact_json(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.redact(s)),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.redact_json(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.redact_json(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Copy of `session` with history contents, tool calls and observations redacted.
+    fn redact_session(&self, session: &Session) -> Session {
+        let mut redacted = session.clone();
+        for entry in &mut redacted.history {
+            entry.content = Arc::new(self.redact(&entry.content));
+            if let Some(ref mut call) = entry.tool_call {
+                call.arguments = self.redact_json(&call.arguments);
+                if let Some(ref result) = call.result {
+                    call.result = Some(Arc::new(self.redact(result)));
+                }
+            }
+        }
+        if let Some(ref mut state) = redacted.task_state {
+            for observation in &mut state.observations {
+                observation.content = Arc::new(self.redact(&observation.content));
+            }
+        }
+        redacted
+    }
+}
+
 /// Security proxy for request validation.
 #[async_trait]
 pub trait SecurityProxy: Send + Sync {
@@ -3,7 +3,8 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::types::RefId;
 
 /// LLM client interface.
 #[async_trait]
@@ -14,8 +15,62 @@ pub trait LlmClient: Send + Sync {
     /// Generate a chat completion.
     async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse>;
 
+    /// Generate a chat completion of at most `max_tokens` completion tokens.
+    ///
+    /// Clients that cannot cap the completion length ignore the limit.
+    async fn chat_with_max_tokens(&self, messages: &[ChatMessage], _max_tokens: u64) -> Result<LlmResponse> {
+        self.chat(messages).await
+    }
+
     /// Generate embeddings for text.
     async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Estimate the prompt token count of `messages` without a completion call.
+    async fn count_tokens(&self, messages: &[ChatMessage]) -> Result<u64> {
+        // Rough estimation: ~4 chars per token on average
+        Ok(messages.iter().map(|m| (m.content.len() / 4) as u64).sum())
+    }
+
+    /// Convert `text` into the model's token IDs.
+    async fn tokenize(&self, _text: &str) -> Result<Vec<u32>> {
+        Err(Error::ModelProvider("Tokenization is not supported by this client".to_string()))
+    }
+
+    /// Convert token IDs back into text.
+    async fn detokenize(&self, _tokens: &[u32]) -> Result<String> {
+        Err(Error::ModelProvider("Detokenization is not supported by this client".to_string()))
+    }
+}
+
+#[async_trait]
+impl<T: LlmClient + ?Sized> LlmClient for std::sync::Arc<T> {
+    async fn complete(&self, prompt: &str) -> Result<LlmResponse> {
+        (**self).complete(prompt).await
+    }
+
+    async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse> {
+        (**self).chat(messages).await
+    }
+
+    async fn chat_with_max_tokens(&self, messages: &[ChatMessage], max_tokens: u64) -> Result<LlmResponse> {
+        (**self).chat_with_max_tokens(messages, max_tokens).await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        (**self).embed(text).await
+    }
+
+    async fn count_tokens(&self, messages: &[ChatMessage]) -> Result<u64> {
+        (**self).count_tokens(messages).await
+    }
+
+    async fn tokenize(&self, text: &str) -> Result<Vec<u32>> {
+        (**self).tokenize(text).await
+    }
+
+    async fn detokenize(&self, tokens: &[u32]) -> Result<String> {
+        (**self).detokenize(tokens).await
+    }
 }
 
 /// Chat message for LLM interactions.
@@ -27,6 +82,9 @@ pub struct ChatMessage {
     pub content: String,
     /// Optional tool calls.
     pub tool_calls: Option<Vec<Value>>,
+    /// Images (L3 references) attached for vision-capable models.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<RefId>,
 }
 
 /// Response from an LLM.
@@ -40,6 +98,9 @@ pub struct LlmResponse {
     pub usage: LlmUsage,
     /// Optional tool calls.
     pub tool_calls: Option<Vec<Value>>,
+    /// Reasoning trace, for models that expose it separately from the content.
+    #[serde(default)]
+    pub reasoning: Option<String>,
 }
 
 /// Token usage from LLM call.
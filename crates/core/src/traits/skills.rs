@@ -1,8 +1,9 @@
 //! L2 Skills traits.
 
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use serde_json::Value;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::types::{ToolDefinition, ToolOutput};
 
 /// Tool interface for atomic operations.
@@ -21,6 +22,9 @@ pub trait Tool: Send + Sync {
     async fn execute(&self, args: Value) -> Result<ToolOutput>;
 }
 
+/// Incremental output of a streaming tool, one chunk per item.
+pub type ToolOutputStream = BoxStream<'static, Result<String>>;
+
 /// Tool registry for managing available tools.
 #[async_trait]
 pub trait ToolRegistry: Send + Sync {
@@ -35,6 +39,28 @@ pub trait ToolRegistry: Send + Sync {
 
     /// Execute a tool by name with arguments.
     async fn execute(&self, name: &str, args: Value) -> Result<ToolOutput>;
+
+    /// Execute a tool by name, yielding its output as it is produced.
+    ///
+    /// Called for tools whose definition sets `supports_streaming`. The default runs
+    /// `execute` and yields its output as a single chunk.
+    async fn execute_streaming(&self, name: &str, args: Value) -> Result<ToolOutputStream> {
+        let output = self.execute(name, args).await?;
+        if !output.success {
+            return Err(Error::tool_execution(output.content));
+        }
+        Ok(Box::pin(futures::stream::once(async move { Ok(output.content) })))
+    }
+
+    /// Eagerly initialize expensive tool backends (must be idempotent).
+    async fn preload(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Check if the registry is ready to execute without cold-start cost.
+    fn is_preloaded(&self) -> bool {
+        true
+    }
 }
 
 /// MCP (Model Context Protocol) adapter.
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::types::RefId;
 
 /// Artifact store for managing large content.
@@ -75,6 +75,11 @@ pub trait MemoryStore: Send + Sync {
     /// Search for similar entries.
     async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<MemoryEntry>>;
 
+    /// Get an entry by ID.
+    async fn get(&self, _id: &str) -> Result<Option<MemoryEntry>> {
+        Err(Error::storage("Lookup by ID is not supported by this memory store"))
+    }
+
     /// Delete an entry by ID.
     async fn delete(&self, id: &str) -> Result<()>;
 }
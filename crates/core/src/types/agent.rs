@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use super::refs::RefId;
+use super::session::Session;
 
 // =============================================================================
 // Agent Result Types (L1 Output)
@@ -22,6 +23,9 @@ pub enum AgentResult {
         mime_type: String,
     },
 
+    /// Several file artifacts (e.g. a generated project).
+    Files(Vec<FileArtifact>),
+
     /// Structured data response.
     Data(serde_json::Value),
 
@@ -41,3 +45,71 @@ pub enum AgentResult {
         code: String,
     },
 }
+
+/// File artifact stored in L3, one of the outputs of a mission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileArtifact {
+    /// Reference to the file in L3.
+    pub ref_id: RefId,
+    /// File name.
+    pub filename: String,
+    /// MIME type.
+    pub mime_type: String,
+}
+
+impl FileArtifact {
+    /// Create a file artifact.
+    pub fn new(ref_id: RefId, filename: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Self {
+            ref_id,
+            filename: filename.into(),
+            mime_type: mime_type.into(),
+        }
+    }
+}
+
+/// Agent result together with the model reasoning that led to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentResultWithExplanation {
+    /// The mission result.
+    pub result: AgentResult,
+    /// Model reasoning recorded during the mission, oldest first.
+    pub reasoning_trace: Vec<String>,
+}
+
+impl AgentResultWithExplanation {
+    /// Attach the reasoning recorded in `session` to `result`.
+    pub fn from_session(result: AgentResult, session: &Session) -> Self {
+        Self {
+            result,
+            reasoning_trace: session
+                .reasoning_entries()
+                .into_iter()
+                .map(|entry| entry.content.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_result_round_trip() {
+        let result = AgentResult::Files(vec![
+            FileArtifact::new(RefId::from_string("ref-1"), "main.rs", "text/x-rust"),
+            FileArtifact::new(RefId::from_string("ref-2"), "Cargo.toml", "application/toml"),
+        ]);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["type"], "Files");
+        assert_eq!(json["payload"][1]["filename"], "Cargo.toml");
+
+        let AgentResult::Files(files) = serde_json::from_value(json).unwrap() else {
+            panic!("expected Files");
+        };
+        assert_eq!(files[0], FileArtifact::new(RefId::from_string("ref-1"), "main.rs", "text/x-rust"));
+        assert_eq!(files.len(), 2);
+    }
+}
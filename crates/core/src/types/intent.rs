@@ -26,5 +26,33 @@ pub enum UserIntent {
         context_summary: String,
         /// Visual references (image RefIds).
         visual_refs: Vec<String>,
+        /// Trace ID of the originating request.
+        #[serde(default)]
+        trace_id: Option<String>,
+        /// User who issued the request.
+        #[serde(default)]
+        user_id: Option<String>,
+    },
+
+    /// Time-triggered execution of another intent.
+    #[serde(rename = "schedule")]
+    Schedule {
+        /// Cron expression (sec min hour day-of-month month day-of-week [year]).
+        cron_expr: String,
+        /// Intent to execute on each run.
+        intent: Box<UserIntent>,
+        /// Maximum number of runs (None = unlimited).
+        max_runs: Option<u32>,
+    },
+
+    /// Mission registered with the controller and run by `tick` whenever due.
+    #[serde(rename = "scheduled_mission")]
+    ScheduledMission {
+        /// High-level goal of each run.
+        goal: String,
+        /// Cron expression (sec min hour day-of-month month day-of-week [year]).
+        cron: String,
+        /// Context passed to each run.
+        context_summary: String,
     },
 }
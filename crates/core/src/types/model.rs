@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::session::TokenUsage;
 
 // =============================================================================
 // Model Types (L-M)
 // =============================================================================
 
 /// Model tier for selection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ModelTier {
     /// Fast, cheap models (e.g., GPT-4o-mini).
     Fast,
@@ -15,6 +18,117 @@ pub enum ModelTier {
     Premium,
 }
 
+impl std::str::FromStr for ModelTier {
+    type Err = crate::Error;
+
+    /// Parse a tier name (`fast`, `standard` or `premium`, case-insensitive).
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "standard" => Ok(Self::Standard),
+            "premium" => Ok(Self::Premium),
+            other => Err(crate::Error::invalid_request(format!("Unknown model tier '{}'", other))),
+        }
+    }
+}
+
+/// Token prices of a model, in USD per 1K tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TierPrice {
+    /// Price per 1K prompt tokens.
+    pub prompt_per_1k: f64,
+    /// Price per 1K completion tokens.
+    pub completion_per_1k: f64,
+}
+
+/// Pricing table keyed by model tier.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelPricing {
+    prices: HashMap<ModelTier, TierPrice>,
+}
+
+impl ModelPricing {
+    /// Create an empty pricing table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the prices (USD per 1K tokens) of a tier.
+    pub fn with_tier(mut self, tier: ModelTier, prompt_per_1k: f64, completion_per_1k: f64) -> Self {
+        self.prices.insert(tier, TierPrice { prompt_per_1k, completion_per_1k });
+        self
+    }
+
+    /// Get the prices of a tier.
+    pub fn price(&self, tier: ModelTier) -> Option<TierPrice> {
+        self.prices.get(&tier).copied()
+    }
+
+    /// Estimate the cost of `usage` at the prices of `tier` (None if the tier is not priced).
+    pub fn cost_usd(&self, tier: ModelTier, usage: &TokenUsage) -> Option<f64> {
+        self.price(tier)
+            .map(|p| usage.cost_usd(p.prompt_per_1k, p.completion_per_1k))
+    }
+}
+
+/// Capabilities of a model that decide whether it can replay a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    /// Maximum context window in tokens.
+    pub context_window: u64,
+}
+
+impl ModelCapabilities {
+    /// Create capabilities with the given context window.
+    pub fn new(context_window: u64) -> Self {
+        Self { context_window }
+    }
+}
+
+/// Prices and capabilities of the models a session is replayed against.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PricingTable {
+    /// Model the session actually ran on.
+    pub original_model: String,
+    /// Prices (USD per 1K tokens) keyed by model ID.
+    pub prices: HashMap<String, TierPrice>,
+    /// Capabilities keyed by model ID.
+    pub capabilities: HashMap<String, ModelCapabilities>,
+}
+
+impl PricingTable {
+    /// Create an empty table for a session that ran on `original_model`.
+    pub fn new(original_model: impl Into<String>) -> Self {
+        Self {
+            original_model: original_model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the prices (USD per 1K tokens) of a model.
+    pub fn with_price(mut self, model_id: impl Into<String>, prompt_per_1k: f64, completion_per_1k: f64) -> Self {
+        self.prices.insert(model_id.into(), TierPrice { prompt_per_1k, completion_per_1k });
+        self
+    }
+
+    /// Set the capabilities of a model.
+    pub fn with_capabilities(mut self, model_id: impl Into<String>, caps: ModelCapabilities) -> Self {
+        self.capabilities.insert(model_id.into(), caps);
+        self
+    }
+}
+
+/// What-if cost analysis of a completed session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayCostEstimate {
+    /// Cost of the session on the model it actually ran on (0 if unpriced).
+    pub original_cost_usd: f64,
+    /// Cost of the same token usage on each priced model.
+    pub alternative_costs: HashMap<String, f64>,
+    /// Cheapest model whose context window fits the session's largest prompt.
+    pub cheapest_feasible_model: Option<String>,
+}
+
 /// Provider health status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProviderHealth {
@@ -27,3 +141,33 @@ pub enum ProviderHealth {
     /// Provider circuit is open (temporarily blocked).
     CircuitOpen,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_usage_cost() {
+        let mut usage = TokenUsage::with_budget(100_000);
+        usage.add(1500, 500);
+
+        // 1.5 * 0.01 + 0.5 * 0.03
+        assert!((usage.cost_usd(0.01, 0.03) - 0.03).abs() < 1e-9);
+        assert_eq!(TokenUsage::default().cost_usd(0.01, 0.03), 0.0);
+    }
+
+    #[test]
+    fn test_model_pricing_by_tier() {
+        let pricing = ModelPricing::new()
+            .with_tier(ModelTier::Fast, 0.00015, 0.0006)
+            .with_tier(ModelTier::Premium, 0.0025, 0.01);
+        let mut usage = TokenUsage::default();
+        usage.add(2000, 1000);
+
+        let fast = pricing.cost_usd(ModelTier::Fast, &usage).unwrap();
+        assert!((fast - 0.0009).abs() < 1e-9);
+        let premium = pricing.cost_usd(ModelTier::Premium, &usage).unwrap();
+        assert!((premium - 0.015).abs() < 1e-9);
+        assert!(pricing.cost_usd(ModelTier::Standard, &usage).is_none());
+    }
+}
@@ -1,6 +1,13 @@
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
+use super::model::{PricingTable, ReplayCostEstimate};
+use super::refs::RefId;
+use super::request::RequestMetadata;
+use crate::traits::Anonymizer;
+
 // =============================================================================
 // Session & State Types
 // =============================================================================
@@ -28,8 +35,306 @@ pub struct Session {
 
     /// Last updated timestamp.
     pub updated_at: i64,
+
+    /// Session metadata (user, custom tags).
+    #[serde(default)]
+    pub metadata: RequestMetadata,
+
+    /// Result of the last instruction-following check on a final answer.
+    #[serde(default)]
+    pub instruction_check_passed: Option<bool>,
+
+    /// Trace ID of the request that started the session.
+    #[serde(default)]
+    pub trace_id: Option<String>,
+
+    /// Session of the agent that delegated to this one (None = top level).
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+
+    /// Wall-clock duration of each ReAct iteration, in milliseconds, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub iteration_durations_ms: Vec<u64>,
+}
+
+impl Session {
+    /// Create an empty running session with no task state.
+    pub fn new(id: impl Into<String>) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self {
+            id: id.into(),
+            status: SessionStatus::Running,
+            history: Vec::new(),
+            task_state: None,
+            token_usage: TokenUsage::default(),
+            created_at: now,
+            updated_at: now,
+            metadata: RequestMetadata::default(),
+            instruction_check_passed: None,
+            trace_id: None,
+            parent_session_id: None,
+            iteration_durations_ms: Vec::new(),
+        }
+    }
+
+    /// Seconds between creation and the last update (0 if the clock went backwards).
+    pub fn duration_secs(&self) -> u64 {
+        self.updated_at.saturating_sub(self.created_at).max(0) as u64
+    }
+
+    /// Total time spent in recorded iterations, in milliseconds.
+    pub fn total_iteration_ms(&self) -> u64 {
+        self.iteration_durations_ms.iter().sum()
+    }
+
+    /// Index and duration (ms) of the slowest recorded iteration.
+    pub fn slowest_iteration(&self) -> Option<(usize, u64)> {
+        self.iteration_durations_ms
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by_key(|&(_, ms)| ms)
+    }
+
+    /// Estimate the tokens of the history sent to the LLM (~4 chars per token).
+    pub fn estimated_context_tokens(&self) -> u64 {
+        self.history
+            .iter()
+            .filter(|entry| entry.role != REASONING_ROLE)
+            .map(|entry| (entry.content.len() / 4) as u64)
+            .sum()
+    }
+
+    /// Get the remaining context tokens for a model (negative means over limit).
+    pub fn token_headroom(&self, model_context_window: u64) -> i64 {
+        model_context_window as i64 - self.estimated_context_tokens() as i64
+    }
+
+    /// Estimate what this session would have cost on each model priced in `table`.
+    ///
+    /// A model is feasible if its context window is larger than the prompt of the
+    /// session's last LLM call (~4 chars per token).
+    pub fn replay_cost_estimate(&self, table: &PricingTable) -> ReplayCostEstimate {
+        let alternative_costs: HashMap<String, f64> = table
+            .prices
+            .iter()
+            .map(|(id, p)| (id.clone(), self.token_usage.cost_usd(p.prompt_per_1k, p.completion_per_1k)))
+            .collect();
+        let original_cost_usd = alternative_costs.get(&table.original_model).copied().unwrap_or(0.0);
+
+        // History only grows between calls, so the last call sent the largest prompt
+        let largest_prompt_tokens: u64 = self
+            .history
+            .iter()
+            .rposition(|e| e.role == "assistant")
+            .map_or(0, |last| {
+                self.history[..last]
+                    .iter()
+                    .filter(|e| e.role != REASONING_ROLE)
+                    .map(|e| (e.content.len() / 4) as u64)
+                    .sum()
+            });
+
+        let cheapest_feasible_model = alternative_costs
+            .iter()
+            .filter(|(id, _)| {
+                table
+                    .capabilities
+                    .get(id.as_str())
+                    .is_some_and(|c| c.context_window > largest_prompt_tokens)
+            })
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(id, _)| id.clone());
+
+        ReplayCostEstimate {
+            original_cost_usd,
+            alternative_costs,
+            cheapest_feasible_model,
+        }
+    }
+
+    /// Get a copy of this session with all PII replaced by pseudonymous tokens.
+    ///
+    /// Covers history content, tool call arguments and results, and task state.
+    /// The user ID is replaced with its pseudonym.
+    pub fn anonymize(&self, anonymizer: &dyn Anonymizer) -> Session {
+        let mut session = self.clone();
+
+        for entry in &mut session.history {
+            entry.content = Arc::new(anonymizer.anonymize_text(&entry.content));
+            if let Some(ref mut call) = entry.tool_call {
+                call.arguments = anonymizer.anonymize_json(&call.arguments);
+                if let Some(ref result) = call.result {
+                    call.result = Some(Arc::new(anonymizer.anonymize_text(result)));
+                }
+            }
+        }
+
+        if let Some(ref mut state) = session.task_state {
+            state.goal = anonymizer.anonymize_text(&state.goal);
+            for observation in &mut state.observations {
+                observation.content = Arc::new(anonymizer.anonymize_text(&observation.content));
+            }
+            for action in &mut state.pending_actions {
+                *action = anonymizer.anonymize_json(action);
+            }
+        }
+
+        if let Some(ref user_id) = session.metadata.user_id {
+            session.metadata.user_id = Some(anonymizer.pseudonymize(user_id));
+        }
+        session.metadata.custom.insert("anonymized".to_string(), "true".to_string());
+
+        session
+    }
+
+    /// Replace `history[range]` with a single summary entry recording the replaced indices.
+    pub fn replace_with_summary(&mut self, range: Range<usize>, summary: impl Into<String>) {
+        let Some(last) = range.end.checked_sub(1).and_then(|i| self.history.get(i)) else {
+            return;
+        };
+        let entry = HistoryEntry {
+            role: "system".to_string(),
+            content: Arc::new(summary.into()),
+            tool_call: None,
+            timestamp: last.timestamp,
+            compressed_from: Some(range.clone().collect()),
+            images: Vec::new(),
+        };
+        self.history.splice(range, std::iter::once(entry));
+    }
+
+    /// Like `replace_with_summary`, but entries matching `pinned` are kept verbatim
+    /// after the summary instead of being replaced.
+    ///
+    /// `expand_summaries` restores the summarized entries ahead of the pinned ones.
+    pub fn replace_with_summary_pinned(
+        &mut self,
+        range: Range<usize>,
+        summary: impl Into<String>,
+        pinned: impl Fn(&HistoryEntry) -> bool,
+    ) {
+        let (kept, replaced): (Vec<usize>, Vec<usize>) =
+            range.clone().partition(|&i| self.history.get(i).is_some_and(&pinned));
+        let Some(last) = replaced.last().and_then(|&i| self.history.get(i)) else {
+            return;
+        };
+        let entry = HistoryEntry {
+            role: "system".to_string(),
+            content: Arc::new(summary.into()),
+            tool_call: None,
+            timestamp: last.timestamp,
+            compressed_from: Some(replaced),
+            images: Vec::new(),
+        };
+        let kept: Vec<_> = kept.into_iter().map(|i| self.history[i].clone()).collect();
+        self.history.splice(range, std::iter::once(entry).chain(kept));
+    }
+
+    /// Drop the oldest entries so at most `max_entries` remain, returning how many were dropped.
+    ///
+    /// The initial system prompt is always kept; the rest of the budget goes to the
+    /// most recent entries.
+    pub fn prune_history(&mut self, max_entries: usize) -> usize {
+        if self.history.len() <= max_entries {
+            return 0;
+        }
+        let start = usize::from(self.history.first().is_some_and(|e| e.role == "system"));
+        let recent = max_entries.saturating_sub(start);
+        let dropped = self.history.len() - start - recent;
+        self.history.drain(start..start + dropped);
+        dropped
+    }
+
+    /// Check if any history entry is a summary of compressed entries.
+    pub fn is_compressed(&self) -> bool {
+        self.history.iter().any(HistoryEntry::is_summary)
+    }
+
+    /// Restore summarized entries from `original`, the history before compression.
+    ///
+    /// Summaries whose indices fall outside `original` are kept as-is.
+    pub fn expand_summaries(&mut self, original: &[HistoryEntry]) {
+        let mut expanded = Vec::with_capacity(original.len());
+        for entry in self.history.drain(..) {
+            match entry.compressed_from {
+                Some(ref indices)
+                    if !indices.is_empty() && indices.iter().all(|&i| i < original.len()) =>
+                {
+                    expanded.extend(indices.iter().map(|&i| original[i].clone()));
+                }
+                _ => expanded.push(entry),
+            }
+        }
+        self.history = expanded;
+    }
+
+    /// Break down the estimated token cost of the history (~4 chars per token).
+    ///
+    /// Every history entry is counted, including reasoning traces, so the
+    /// per-role totals add up to `TokenUsageExplanation::total_tokens`.
+    pub fn explain_token_usage(&self) -> TokenUsageExplanation {
+        let mut by_role: HashMap<String, u64> = HashMap::new();
+        // Setup entries (system prompt, user input) count towards the first iteration
+        let mut by_iteration: Vec<u64> = vec![0];
+        let mut entries: Vec<(EntryIndex, u64, String)> = Vec::with_capacity(self.history.len());
+        let mut prev_role: Option<&str> = None;
+        let mut seen_response = false;
+
+        for (index, entry) in self.history.iter().enumerate() {
+            // Each LLM response (led by its reasoning trace, if any) starts an iteration
+            let starts_iteration = entry.role == REASONING_ROLE
+                || (entry.role == "assistant" && prev_role != Some(REASONING_ROLE));
+            if starts_iteration {
+                if seen_response {
+                    by_iteration.push(0);
+                }
+                seen_response = true;
+            }
+            prev_role = Some(&entry.role);
+
+            let tokens = (entry.content.len() / 4) as u64;
+            *by_role.entry(entry.role.clone()).or_insert(0) += tokens;
+            *by_iteration.last_mut().expect("by_iteration is never empty") += tokens;
+            entries.push((index, tokens, entry.content.chars().take(80).collect()));
+        }
+
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        entries.truncate(10);
+
+        TokenUsageExplanation {
+            total_tokens: by_role.values().sum(),
+            top_entries: entries,
+            by_role,
+            by_iteration,
+            compression_events: self.history.iter().filter(|e| e.is_summary()).count() as u32,
+        }
+    }
+
+    /// Get the total number of tool call retries in this session.
+    pub fn total_tool_retries(&self) -> u32 {
+        self.history
+            .iter()
+            .filter_map(|entry| entry.tool_call.as_ref())
+            .map(|call| call.retry_count)
+            .sum()
+    }
+
+    /// Get the model reasoning entries (hidden from the LLM context).
+    pub fn reasoning_entries(&self) -> Vec<&HistoryEntry> {
+        self.history
+            .iter()
+            .filter(|entry| entry.role == REASONING_ROLE)
+            .collect()
+    }
 }
 
+/// History role used for model reasoning traces.
+pub const REASONING_ROLE: &str = "reasoning";
+
 /// Session status for state tracking.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SessionStatus {
@@ -41,6 +346,10 @@ pub enum SessionStatus {
     Completed,
     /// Session failed with error.
     Failed,
+    /// Session was cancelled by the user.
+    Cancelled,
+    /// Session ran out of token budget and returned partial results.
+    BudgetExhausted,
 }
 
 /// Entry in conversation history.
@@ -57,6 +366,21 @@ pub struct HistoryEntry {
 
     /// Timestamp.
     pub timestamp: i64,
+
+    /// Original history indices replaced by this summary entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compressed_from: Option<Vec<usize>>,
+
+    /// Images (L3 references) attached to the message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<RefId>,
+}
+
+impl HistoryEntry {
+    /// Check if this entry summarizes compressed entries (`Some([])` counts as not compressed).
+    pub fn is_summary(&self) -> bool {
+        self.compressed_from.as_ref().is_some_and(|indices| !indices.is_empty())
+    }
 }
 
 /// Information about a tool call.
@@ -68,6 +392,9 @@ pub struct ToolCallInfo {
     pub arguments: serde_json::Value,
     /// Tool result (if completed).
     pub result: Option<Arc<String>>,
+    /// Number of retries performed before the final result.
+    #[serde(default)]
+    pub retry_count: u32,
 }
 
 /// Task state for resurrection pattern.
@@ -79,11 +406,104 @@ pub struct TaskState {
     /// Current goal.
     pub goal: String,
 
-    /// Accumulated observations.
-    pub observations: Vec<Arc<String>>,
+    /// Accumulated observations, in the order they were recorded.
+    pub observations: Vec<Observation>,
 
     /// Pending actions.
     pub pending_actions: Vec<serde_json::Value>,
+
+    /// Number of calls made to each tool.
+    #[serde(default)]
+    pub tool_call_frequency: HashMap<String, u32>,
+}
+
+impl TaskState {
+    /// Create the state of a task that has not started working on `goal` yet.
+    pub fn new(goal: impl Into<String>) -> Self {
+        Self {
+            iteration: 0,
+            goal: goal.into(),
+            observations: Vec::new(),
+            pending_actions: Vec::new(),
+            tool_call_frequency: HashMap::new(),
+        }
+    }
+
+    /// Record an observation made in the current iteration, by `tool` if a tool produced it.
+    pub fn observe(&mut self, tool: Option<&str>, content: impl Into<String>) {
+        self.observations.push(Observation {
+            tool: tool.map(str::to_string),
+            content: Arc::new(content.into()),
+            iteration: self.iteration,
+        });
+    }
+}
+
+/// Observation recorded in `TaskState`, with the tool and iteration that produced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ObservationRecord")]
+pub struct Observation {
+    /// Tool that produced the observation (None for feedback from the controller).
+    pub tool: Option<String>,
+    /// Observation text.
+    pub content: Arc<String>,
+    /// ReAct iteration the observation was recorded in.
+    pub iteration: usize,
+}
+
+impl std::fmt::Display for Observation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.tool {
+            Some(ref tool) => write!(f, "[{}, iteration {}] {}", tool, self.iteration, self.content),
+            None => write!(f, "[iteration {}] {}", self.iteration, self.content),
+        }
+    }
+}
+
+/// Stored form of an `Observation`; sessions saved before provenance was
+/// tracked hold bare strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ObservationRecord {
+    Structured {
+        #[serde(default)]
+        tool: Option<String>,
+        content: Arc<String>,
+        #[serde(default)]
+        iteration: usize,
+    },
+    Legacy(Arc<String>),
+}
+
+impl From<ObservationRecord> for Observation {
+    fn from(record: ObservationRecord) -> Self {
+        match record {
+            ObservationRecord::Structured { tool, content, iteration } => Self { tool, content, iteration },
+            ObservationRecord::Legacy(content) => Self {
+                tool: None,
+                content,
+                iteration: 0,
+            },
+        }
+    }
+}
+
+/// Index of an entry in `Session::history`.
+pub type EntryIndex = usize;
+
+/// Breakdown of where a session's tokens went, for budget audits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenUsageExplanation {
+    /// The 10 most expensive entries as (index, tokens, content preview).
+    pub top_entries: Vec<(EntryIndex, u64, String)>,
+    /// Tokens per history role.
+    pub by_role: HashMap<String, u64>,
+    /// Tokens per ReAct iteration.
+    pub by_iteration: Vec<u64>,
+    /// Number of compression summaries in the history.
+    pub compression_events: u32,
+    /// Estimated tokens across all entries.
+    pub total_tokens: u64,
 }
 
 /// Token usage tracking.
@@ -127,4 +547,103 @@ impl TokenUsage {
     pub fn remaining(&self) -> u64 {
         self.budget_limit.saturating_sub(self.total_tokens)
     }
+
+    /// Cost in USD given prompt and completion prices per 1K tokens.
+    pub fn cost_usd(&self, prompt_price_per_1k: f64, completion_price_per_1k: f64) -> f64 {
+        (self.prompt_tokens as f64 * prompt_price_per_1k
+            + self.completion_tokens as f64 * completion_price_per_1k)
+            / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(role: &str, content: &str) -> HistoryEntry {
+        HistoryEntry {
+            role: role.to_string(),
+            content: Arc::new(content.to_string()),
+            tool_call: None,
+            timestamp: 0,
+            compressed_from: None,
+            images: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_prune_history_keeps_system_and_recent_entries() {
+        let mut history = vec![entry("system", "prompt")];
+        history.extend((0..10).map(|i| entry("assistant", &format!("step {}", i))));
+        let mut session = Session {
+            created_at: 0,
+            updated_at: 0,
+            ..Session::new("s1")
+        };
+        session.history = history;
+
+        assert_eq!(session.prune_history(4), 7);
+        let contents: Vec<&str> = session.history.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["prompt", "step 7", "step 8", "step 9"]);
+
+        // Already under the cap
+        assert_eq!(session.prune_history(4), 0);
+        assert_eq!(session.history.len(), 4);
+    }
+
+    #[test]
+    fn test_replay_cost_estimate() {
+        use crate::types::ModelCapabilities;
+
+        let table = PricingTable::new("big")
+            .with_price("big", 10.0, 30.0)
+            .with_price("small", 1.0, 2.0)
+            .with_price("tiny", 0.1, 0.2)
+            .with_capabilities("big", ModelCapabilities::new(128_000))
+            .with_capabilities("small", ModelCapabilities::new(1_000))
+            .with_capabilities("tiny", ModelCapabilities::new(100));
+
+        let mut session = Session::new("s1");
+        session.token_usage.add(2000, 1000);
+        // The last call was prompted with 2 entries of 400 chars = ~200 tokens
+        let long = "x".repeat(400);
+        session.history = vec![entry("user", &long), entry("user", &long), entry("assistant", "done")];
+
+        let estimate = session.replay_cost_estimate(&table);
+
+        // big: 2 * $10 + 1 * $30 = $50
+        assert!((estimate.original_cost_usd - 50.0).abs() < 0.001);
+        // small: 2 * $1 + 1 * $2 = $4
+        assert!((estimate.alternative_costs["small"] - 4.0).abs() < 0.001);
+        assert_eq!(estimate.alternative_costs.len(), 3);
+        // tiny is cheapest but its context window is too small
+        assert_eq!(estimate.cheapest_feasible_model.as_deref(), Some("small"));
+    }
+
+    #[test]
+    fn test_observations_keep_provenance() {
+        let mut state = TaskState::new("Research");
+        state.iteration = 2;
+        state.observe(Some("search"), "3 results");
+        state.iteration = 3;
+        state.observe(None, "Answer rejected");
+
+        let json = serde_json::to_value(&state).unwrap();
+        let restored: TaskState = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.observations, state.observations);
+        assert_eq!(restored.observations[0].tool.as_deref(), Some("search"));
+        assert_eq!(restored.observations[0].iteration, 2);
+        assert_eq!(restored.observations[1].to_string(), "[iteration 3] Answer rejected");
+
+        // Sessions saved before provenance hold bare strings
+        let legacy: TaskState = serde_json::from_value(serde_json::json!({
+            "iteration": 1,
+            "goal": "Research",
+            "observations": ["old observation"],
+            "pending_actions": [],
+        }))
+        .unwrap();
+        assert_eq!(legacy.observations[0].content.as_str(), "old observation");
+        assert_eq!(legacy.observations[0].tool, None);
+    }
 }
@@ -5,12 +5,44 @@
 
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use multi_agent_core::{
     traits::ArtifactStore,
-    types::RefId,
+    types::{NormalizedRequest, RefId, RequestContent},
     Error, Result,
 };
 
+/// Speech-to-text for audio artifacts stored in L3.
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    /// Transcribe the audio artifact referenced by `ref_id`.
+    async fn transcribe(&self, ref_id: &RefId) -> Result<String>;
+}
+
+/// Fill in the transcription of an audio request.
+///
+/// For `RequestContent::Audio` without a transcription, calls the transcriber,
+/// stores the text in the content and uses it as the request content.
+/// Other requests are left untouched.
+pub async fn preprocess_audio(request: &mut NormalizedRequest, transcriber: &dyn Transcriber) -> Result<()> {
+    let RequestContent::Audio {
+        ref ref_id,
+        ref mut transcription,
+    } = request.original_content
+    else {
+        return Ok(());
+    };
+    if transcription.is_some() {
+        return Ok(());
+    }
+
+    let text = transcriber.transcribe(ref_id).await?;
+    tracing::debug!(ref_id = %ref_id, len = text.len(), "Audio request transcribed");
+    *transcription = Some(text.clone());
+    request.content = text;
+    Ok(())
+}
+
 /// Supported audio formats.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioFormat {
@@ -167,6 +199,23 @@ impl AudioProcessor {
     }
 }
 
+#[async_trait]
+impl Transcriber for AudioProcessor {
+    async fn transcribe(&self, ref_id: &RefId) -> Result<String> {
+        let stored = self
+            .store
+            .load(ref_id)
+            .await?
+            .ok_or_else(|| Error::gateway(format!("Audio artifact not found: {}", ref_id)))?;
+
+        // store_audio saves base64; fall back to raw bytes for other writers
+        let audio_data = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &stored)
+            .unwrap_or_else(|_| stored.to_vec());
+
+        Ok(AudioProcessor::transcribe(self, &audio_data).await?.text)
+    }
+}
+
 /// Result of audio transcription.
 #[derive(Debug, Clone)]
 pub struct TranscriptionResult {
@@ -202,4 +251,46 @@ mod tests {
         assert_eq!(AudioFormat::Mp3.mime_type(), "audio/mpeg");
         assert_eq!(AudioFormat::Wav.mime_type(), "audio/wav");
     }
+
+    struct MockTranscriber;
+
+    #[async_trait]
+    impl Transcriber for MockTranscriber {
+        async fn transcribe(&self, ref_id: &RefId) -> Result<String> {
+            Ok(format!("transcript of {}", ref_id))
+        }
+    }
+
+    fn audio_request(transcription: Option<&str>) -> NormalizedRequest {
+        let mut request = NormalizedRequest::text("");
+        request.original_content = RequestContent::Audio {
+            ref_id: RefId::from_string("audio_1"),
+            transcription: transcription.map(String::from),
+        };
+        request
+    }
+
+    #[tokio::test]
+    async fn test_preprocess_audio_fills_transcription() {
+        let mut request = audio_request(None);
+        preprocess_audio(&mut request, &MockTranscriber).await.unwrap();
+
+        assert_eq!(request.content, "transcript of audio_1");
+        assert!(matches!(
+            request.original_content,
+            RequestContent::Audio { transcription: Some(ref t), .. } if t == "transcript of audio_1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_preprocess_audio_keeps_existing_transcription() {
+        let mut request = audio_request(Some("already done"));
+        request.content = "already done".to_string();
+        preprocess_audio(&mut request, &MockTranscriber).await.unwrap();
+        assert_eq!(request.content, "already done");
+
+        let mut text = NormalizedRequest::text("hello");
+        preprocess_audio(&mut text, &MockTranscriber).await.unwrap();
+        assert_eq!(text.content, "hello");
+    }
 }
@@ -0,0 +1,121 @@
+//! Mapping of inbound system events to intents.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use multi_agent_core::{traits::EventMapper, types::UserIntent};
+
+/// Intent an event type triggers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IntentTemplate {
+    /// Start a mission. `{path}` placeholders in the goal are filled from the
+    /// payload, with `.` separating nested keys (e.g. `{repository.name}`).
+    Mission { goal: String },
+    /// Call a tool with the event payload as arguments.
+    FastAction { tool_name: String },
+}
+
+/// Event mapper looking up the template registered for each event type.
+///
+/// Events without a template trigger nothing.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateEventMapper {
+    templates: HashMap<String, IntentTemplate>,
+}
+
+impl TemplateEventMapper {
+    /// Create a mapper from templates keyed by event type.
+    pub fn new(templates: HashMap<String, IntentTemplate>) -> Self {
+        Self { templates }
+    }
+
+    /// Register the template for `event_type`.
+    pub fn with_template(mut self, event_type: impl Into<String>, template: IntentTemplate) -> Self {
+        self.templates.insert(event_type.into(), template);
+        self
+    }
+}
+
+impl EventMapper for TemplateEventMapper {
+    fn map_event(&self, event_type: &str, payload: &Value) -> Option<UserIntent> {
+        Some(match self.templates.get(event_type)? {
+            IntentTemplate::Mission { goal } => UserIntent::ComplexMission {
+                goal: render_goal(goal, payload),
+                context_summary: format!("System event '{}' with payload: {}", event_type, payload),
+                visual_refs: Vec::new(),
+                trace_id: None,
+                user_id: None,
+            },
+            IntentTemplate::FastAction { tool_name } => UserIntent::FastAction {
+                tool_name: tool_name.clone(),
+                args: payload.clone(),
+            },
+        })
+    }
+}
+
+/// Replace `{path}` placeholders with payload values; unresolved ones are kept as is.
+fn render_goal(goal: &str, payload: &Value) -> String {
+    let mut rendered = String::with_capacity(goal.len());
+    let mut rest = goal;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let path = &rest[start + 1..start + len];
+        rendered.push_str(&rest[..start]);
+        match path.split('.').try_fold(payload, |value, key| value.get(key)) {
+            Some(Value::String(text)) => rendered.push_str(text),
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => rendered.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_github_push_becomes_mission() {
+        let mapper = TemplateEventMapper::default().with_template(
+            "github.push",
+            IntentTemplate::Mission {
+                goal: "Review the {commits} new commits pushed to {repository.full_name} by {pusher}".to_string(),
+            },
+        );
+        let payload = json!({
+            "repository": { "full_name": "acme/api" },
+            "commits": 3,
+        });
+
+        let Some(UserIntent::ComplexMission { goal, context_summary, .. }) = mapper.map_event("github.push", &payload)
+        else {
+            panic!("expected a mission");
+        };
+        assert_eq!(goal, "Review the 3 new commits pushed to acme/api by {pusher}");
+        assert!(context_summary.contains("github.push"));
+
+        assert!(mapper.map_event("github.issue", &payload).is_none());
+    }
+
+    #[test]
+    fn test_event_becomes_fast_action() {
+        let mapper = TemplateEventMapper::new(HashMap::from([(
+            "alert.fired".to_string(),
+            IntentTemplate::FastAction { tool_name: "page_oncall".to_string() },
+        )]));
+
+        let intent = mapper.map_event("alert.fired", &json!({ "severity": "high" }));
+        assert!(matches!(
+            intent,
+            Some(UserIntent::FastAction { ref tool_name, ref args }) if tool_name == "page_oncall" && args["severity"] == "high"
+        ));
+    }
+}
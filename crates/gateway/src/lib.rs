@@ -5,12 +5,14 @@
 //! including semantic caching and intent routing.
 
 pub mod audio;
+pub mod events;
 pub mod router;
 pub mod semantic_cache;
 pub mod server;
 pub mod vision;
 
-pub use audio::{AudioProcessor, AudioFormat, TranscriptionResult};
+pub use audio::{preprocess_audio, AudioProcessor, AudioFormat, Transcriber, TranscriptionResult};
+pub use events::{IntentTemplate, TemplateEventMapper};
 pub use router::DefaultRouter;
 pub use semantic_cache::InMemorySemanticCache;
 pub use server::{GatewayServer, GatewayConfig};
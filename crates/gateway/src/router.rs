@@ -182,6 +182,8 @@ impl IntentRouter for DefaultRouter {
                 goal: self.extract_goal(content),
                 context_summary: content.clone(),
                 visual_refs: request.refs.iter().map(|r| r.0.clone()).collect(),
+                trace_id: Some(request.trace_id.clone()),
+                user_id: request.metadata.user_id.clone(),
             });
         }
 
@@ -192,6 +194,8 @@ impl IntentRouter for DefaultRouter {
                 goal: self.extract_goal(content),
                 context_summary: content.clone(),
                 visual_refs: Vec::new(),
+                trace_id: Some(request.trace_id.clone()),
+                user_id: request.metadata.user_id.clone(),
             });
         }
 
@@ -210,6 +214,8 @@ impl IntentRouter for DefaultRouter {
             goal: self.extract_goal(content),
             context_summary: content.clone(),
             visual_refs: Vec::new(),
+            trace_id: Some(request.trace_id.clone()),
+            user_id: request.metadata.user_id.clone(),
         })
     }
 }
@@ -248,6 +254,23 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_complex_mission_keeps_trace_id() {
+        let router = DefaultRouter::new();
+
+        let mut request = NormalizedRequest::text("Help me build a REST API in Rust");
+        request.metadata.user_id = Some("user-7".to_string());
+        let intent = router.classify(&request).await.unwrap();
+
+        match intent {
+            UserIntent::ComplexMission { trace_id, user_id, .. } => {
+                assert_eq!(trace_id, Some(request.trace_id.clone()));
+                assert_eq!(user_id.as_deref(), Some("user-7"));
+            }
+            _ => panic!("Expected ComplexMission"),
+        }
+    }
+
     #[tokio::test]
     async fn test_refs_force_complex() {
         use multi_agent_core::types::RefId;
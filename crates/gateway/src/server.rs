@@ -14,7 +14,7 @@ use tower_http::trace::TraceLayer;
 use uuid::Uuid;
 
 use multi_agent_core::{
-    traits::{Controller, IntentRouter, SemanticCache},
+    traits::{Controller, EventMapper, IntentRouter, SemanticCache},
     types::{AgentResult, NormalizedRequest, RequestContent, RequestMetadata, UserIntent},
     Result,
 };
@@ -53,6 +53,8 @@ pub struct AppState {
     pub controller: Option<Arc<dyn Controller>>,
     /// Optional distributed rate limiter.
     pub rate_limiter: Option<Arc<dyn DistributedRateLimiter>>,
+    /// Maps webhook events to intents, ahead of the router.
+    pub event_mapper: Option<Arc<dyn EventMapper>>,
 }
 
 use metrics_exporter_prometheus::PrometheusHandle;
@@ -80,6 +82,7 @@ impl GatewayServer {
                 cache,
                 controller: None,
                 rate_limiter: None,
+                event_mapper: None,
             }),
             metrics_handle: None,
             admin_state: None,
@@ -114,6 +117,14 @@ impl GatewayServer {
         self
     }
 
+    /// Map webhook events with `mapper`; mapped events start their intent on the controller.
+    pub fn with_event_mapper(mut self, mapper: Arc<dyn EventMapper>) -> Self {
+        if let Some(state) = Arc::get_mut(&mut self.state) {
+            state.event_mapper = Some(mapper);
+        }
+        self
+    }
+
 
     /// Build the Axum router.
 
@@ -345,6 +356,8 @@ async fn chat_handler(
                         goal: "Error".to_string(),
                         context_summary: e.to_string(),
                         visual_refs: Vec::new(),
+                        trace_id: None,
+                        user_id: None,
                     },
                     result: Some(AgentResult::Error {
                         message: e.to_string(),
@@ -415,6 +428,8 @@ async fn intent_handler(
                     goal: "Error".to_string(),
                     context_summary: e.to_string(),
                     visual_refs: Vec::new(),
+                    trace_id: None,
+                    user_id: None,
                 },
             }),
         ),
@@ -444,8 +459,9 @@ pub struct WebhookResponse {
 
 /// Webhook handler for system events.
 ///
-/// Accepts events at `/v1/webhook/:event_type` and processes them
-/// as system events through the normal intent routing pipeline.
+/// Accepts events at `/v1/webhook/:event_type`. Events the event mapper knows
+/// are started on the controller in the background; others go through the
+/// normal intent routing pipeline.
 async fn webhook_handler(
     State(state): State<Arc<AppState>>,
     Path(event_type): Path<String>,
@@ -470,6 +486,42 @@ async fn webhook_handler(
             .collect::<String>()
     );
 
+    let mapped = state
+        .event_mapper
+        .as_ref()
+        .and_then(|mapper| mapper.map_event(&event_type, &payload.data));
+    if let Some(intent) = mapped {
+        let started = match state.controller {
+            Some(ref controller) => {
+                let controller = controller.clone();
+                let run = intent.clone();
+                let event = event_type.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = controller.execute(run).await {
+                        tracing::warn!(event_type = %event, error = %e, "Webhook mission failed");
+                    }
+                });
+                true
+            }
+            None => false,
+        };
+        tracing::info!(trace_id = %trace_id, event_type = %event_type, started = started, "Mapped webhook event");
+        let message = if started {
+            format!("Event '{}' started its intent", event_type)
+        } else {
+            format!("Event '{}' mapped, no controller configured", event_type)
+        };
+        return (
+            StatusCode::OK,
+            Json(WebhookResponse {
+                trace_id,
+                accepted: true,
+                message: Some(message),
+                intent: Some(intent),
+            }),
+        );
+    }
+
     let request = NormalizedRequest {
         trace_id: trace_id.clone(),
         content: event_summary.clone(),
@@ -8,7 +8,11 @@
 use async_trait::async_trait;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use multi_agent_core::traits::Anonymizer;
 use multi_agent_core::Result;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
 
 /// Result of a guardrail check.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,19 +71,40 @@ pub trait Guardrail: Send + Sync {
 /// PII Scanner using regex patterns.
 pub struct PiiScanner {
     patterns: Vec<(String, Regex)>,
+    /// HMAC key for pseudonyms.
+    secret: Vec<u8>,
 }
 
 impl PiiScanner {
     /// Create a new PII scanner with default patterns.
+    ///
+    /// Longer patterns come first so anonymization never splits a match.
+    /// Pseudonyms are keyed with a random secret, so they are only stable for
+    /// this scanner; use `with_secret` to keep them stable across restarts.
     pub fn new() -> Self {
         let patterns = vec![
             ("email".to_string(), Regex::new(r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap()),
-            ("phone_us".to_string(), Regex::new(r"\b\d{3}[-.]?\d{3}[-.]?\d{4}\b").unwrap()),
-            ("ssn".to_string(), Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").unwrap()),
             ("credit_card".to_string(), Regex::new(r"\b\d{4}[-\s]?\d{4}[-\s]?\d{4}[-\s]?\d{4}\b").unwrap()),
+            ("ssn".to_string(), Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").unwrap()),
+            ("phone_us".to_string(), Regex::new(r"\b\d{3}[-.]?\d{3}[-.]?\d{4}\b").unwrap()),
             ("ip_address".to_string(), Regex::new(r"\b\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}\b").unwrap()),
         ];
-        Self { patterns }
+        let mut secret = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        Self { patterns, secret }
+    }
+
+    /// Key pseudonyms with a per-deployment secret.
+    pub fn with_secret(mut self, secret: impl Into<Vec<u8>>) -> Self {
+        self.secret = secret.into();
+        self
+    }
+
+    /// HMAC-SHA256 of `value` under the scanner's secret, hex-encoded.
+    fn keyed_digest(&self, value: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(value.as_bytes());
+        mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
     }
     
     /// Check for PII in text.
@@ -120,6 +145,25 @@ impl Guardrail for PiiScanner {
     }
 }
 
+/// Replaces each PII match with a keyed pseudonym such as `<EMAIL_1a2b...>`.
+impl Anonymizer for PiiScanner {
+    fn anonymize_text(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for (name, regex) in &self.patterns {
+            result = regex
+                .replace_all(&result, |caps: &regex::Captures| {
+                    format!("<{}_{}>", name.to_uppercase(), self.keyed_digest(&caps[0]))
+                })
+                .into_owned();
+        }
+        result
+    }
+
+    fn pseudonymize(&self, value: &str) -> String {
+        self.keyed_digest(value)
+    }
+}
+
 /// Prompt Injection detector.
 pub struct PromptInjectionDetector {
     patterns: Vec<Regex>,
@@ -244,6 +288,73 @@ mod tests {
         assert!(found.is_empty());
     }
     
+    #[test]
+    fn test_session_anonymize() {
+        use multi_agent_core::types::{
+            HistoryEntry, Observation, RequestMetadata, Session, SessionStatus, TaskState, ToolCallInfo,
+        };
+        use std::sync::Arc;
+
+        let entry = |content: &str| HistoryEntry {
+            role: "user".to_string(),
+            content: Arc::new(content.to_string()),
+            tool_call: None,
+            timestamp: 0,
+            compressed_from: None,
+            images: Vec::new(),
+        };
+        let mut tool_entry = entry("OBSERVATION: lookup done");
+        tool_entry.tool_call = Some(ToolCallInfo {
+            name: "crm_lookup".to_string(),
+            arguments: serde_json::json!({"query": {"email": "jane.doe@corp.com"}}),
+            result: Some(Arc::new("Phone: 555-123-4567, SSN 123-45-6789".to_string())),
+            retry_count: 0,
+        });
+
+        let mut session = Session::new("s1");
+        session.status = SessionStatus::Completed;
+        // Keep epoch timestamps out of the phone number patterns
+        session.created_at = 0;
+        session.updated_at = 0;
+        session.history = vec![entry("Email john@example.com or call 415.555.0199"), tool_entry];
+        let mut task_state = TaskState::new("Contact john@example.com");
+        task_state.iteration = 1;
+        task_state.observations = vec![Observation {
+            tool: Some("crm_lookup".to_string()),
+            content: Arc::new("Card 4111 1111 1111 1111".to_string()),
+            iteration: 1,
+        }];
+        session.task_state = Some(task_state);
+        session.metadata = RequestMetadata {
+            user_id: Some("user-42".to_string()),
+            ..Default::default()
+        };
+
+        let anonymizer = PiiScanner::new().with_secret("deployment-secret");
+        let anonymized = session.anonymize(&anonymizer);
+        let exported = serde_json::to_string(&anonymized).unwrap();
+
+        let scanner = PiiScanner::new();
+        assert!(scanner.scan(&exported).is_empty(), "PII left in: {}", exported);
+        assert!(anonymized.history[0].content.contains("<EMAIL_"));
+        assert!(anonymized.history[0].content.contains("<PHONE_US_"));
+        // The same value maps to the same pseudonym
+        let goal = &anonymized.task_state.as_ref().unwrap().goal;
+        assert!(anonymized.history[0].content.contains(goal.trim_start_matches("Contact ")));
+
+        assert_eq!(anonymized.metadata.custom.get("anonymized").map(String::as_str), Some("true"));
+        let user_id = anonymized.metadata.user_id.unwrap();
+        assert_ne!(user_id, "user-42");
+        assert_eq!(user_id.len(), 64);
+        // Pseudonyms depend on the secret
+        let pseudonym = |secret: &str| PiiScanner::new().with_secret(secret).pseudonymize("user-42");
+        assert_eq!(pseudonym("deployment-secret"), user_id);
+        assert_ne!(pseudonym("other-secret"), user_id);
+
+        // The original is untouched
+        assert!(session.history[0].content.contains("john@example.com"));
+    }
+
     #[test]
     fn test_injection_detector() {
         let detector = PromptInjectionDetector::new();
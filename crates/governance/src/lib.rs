@@ -8,6 +8,8 @@
 //! - RBAC connector for enterprise IAM
 //! - Audit logging
 //! - Encrypted secrets management
+//! - Content moderation
+//! - Secret redaction
 
 pub mod budget;
 pub mod security;
@@ -17,12 +19,16 @@ pub mod guardrails;
 pub mod rbac;
 pub mod audit;
 pub mod secrets;
+pub mod moderation;
+pub mod redaction;
 
 pub use budget::TokenBudgetController;
 pub use security::DefaultSecurityProxy;
 pub use tracing_layer::configure_tracing;
-pub use metrics::{setup_metrics_recorder, track_request, track_tokens};
+pub use metrics::{setup_metrics_recorder, track_request, track_tokens, track_tool_retries};
 pub use guardrails::{Guardrail, GuardrailResult, ViolationType, PiiScanner, PromptInjectionDetector, CompositeGuardrail};
 pub use rbac::{RbacConnector, UserRoles, NoOpRbacConnector};
 pub use audit::{AuditStore, AuditEntry, AuditOutcome, AuditFilter, InMemoryAuditStore, FileAuditStore};
 pub use secrets::{SecretsManager, EncryptedSecret, AesGcmSecretsManager};
+pub use moderation::{ContentModerator, ModerationDirection, ModerationResult, OpenAiModerationClient};
+pub use redaction::RegexRedactor;
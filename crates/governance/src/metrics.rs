@@ -33,6 +33,12 @@ pub fn track_request(method: &str, path: &str, status: u16, latency_sec: f64) {
     .record(latency_sec);
 }
 
+/// Helper to track tool call retries.
+pub fn track_tool_retries(tool_name: &str, retries: u32) {
+    metrics::counter!("react_tool_retries_total", "tool_name" => tool_name.to_string())
+        .increment(u64::from(retries));
+}
+
 /// Helper to track token usage.
 pub fn track_tokens(model: &str, prompt: u64, completion: u64) {
     metrics::counter!("llm_token_usage_total", "model" => model.to_string(), "type" => "prompt").increment(prompt);
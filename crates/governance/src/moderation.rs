@@ -0,0 +1,177 @@
+//! Content moderation for agent input and output.
+//!
+//! Complements the prompt injection guardrails by screening user messages and
+//! final answers for harmful content categories.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use multi_agent_core::{Error, Result};
+
+/// Direction of the text being moderated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModerationDirection {
+    /// User-provided input.
+    Input,
+    /// Agent-generated output.
+    Output,
+}
+
+/// Result of a moderation check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationResult {
+    /// Whether the text passed moderation.
+    pub passed: bool,
+    /// Flagged categories (empty if passed).
+    pub categories: Vec<String>,
+    /// Confidence of the verdict (0.0 - 1.0).
+    pub confidence: f32,
+}
+
+impl ModerationResult {
+    /// Create a passing result.
+    pub fn pass() -> Self {
+        Self {
+            passed: true,
+            categories: Vec::new(),
+            confidence: 1.0,
+        }
+    }
+
+    /// Create a failing result with the flagged categories.
+    pub fn flagged(categories: Vec<String>, confidence: f32) -> Self {
+        Self {
+            passed: false,
+            categories,
+            confidence,
+        }
+    }
+}
+
+/// Content moderator for input/output safety.
+#[async_trait]
+pub trait ContentModerator: Send + Sync {
+    /// Moderate a piece of text.
+    async fn moderate(&self, text: &str, direction: ModerationDirection) -> Result<ModerationResult>;
+}
+
+#[derive(Deserialize)]
+struct OpenAiModerationResponse {
+    results: Vec<OpenAiModerationEntry>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiModerationEntry {
+    flagged: bool,
+    categories: std::collections::HashMap<String, bool>,
+    category_scores: std::collections::HashMap<String, f32>,
+}
+
+/// Content moderator backed by the OpenAI moderation endpoint.
+pub struct OpenAiModerationClient {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    model: String,
+}
+
+impl OpenAiModerationClient {
+    /// Create a new client with the given API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            model: "omni-moderation-latest".to_string(),
+        }
+    }
+
+    /// Create a client using the `OPENAI_API_KEY` environment variable.
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| Error::governance("OPENAI_API_KEY not set"))?;
+        Ok(Self::new(api_key))
+    }
+
+    /// Override the API base URL.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Override the moderation model.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+}
+
+#[async_trait]
+impl ContentModerator for OpenAiModerationClient {
+    async fn moderate(&self, text: &str, _direction: ModerationDirection) -> Result<ModerationResult> {
+        let resp = self
+            .client
+            .post(format!("{}/moderations", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({ "model": self.model, "input": text }))
+            .send()
+            .await
+            .map_err(|e| Error::governance(format!("Moderation request failed: {}", e)))?
+            .error_for_status()
+            .map_err(|e| Error::governance(format!("Moderation request failed: {}", e)))?;
+
+        let body: OpenAiModerationResponse = resp
+            .json()
+            .await
+            .map_err(|e| Error::governance(format!("Failed to parse moderation response: {}", e)))?;
+
+        let Some(entry) = body.results.into_iter().next() else {
+            return Err(Error::governance("Moderation response contained no results"));
+        };
+
+        if !entry.flagged {
+            return Ok(ModerationResult::pass());
+        }
+
+        let mut categories: Vec<String> = entry
+            .categories
+            .into_iter()
+            .filter(|(_, flagged)| *flagged)
+            .map(|(name, _)| name)
+            .collect();
+        categories.sort();
+
+        let confidence = categories
+            .iter()
+            .filter_map(|c| entry.category_scores.get(c))
+            .fold(0.0f32, |acc, s| acc.max(*s));
+
+        Ok(ModerationResult::flagged(categories, confidence))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_openai_response() {
+        let raw = r#"{"results":[{"flagged":true,
+            "categories":{"violence":true,"hate":false},
+            "category_scores":{"violence":0.92,"hate":0.01}}]}"#;
+        let body: OpenAiModerationResponse = serde_json::from_str(raw).unwrap();
+        let entry = &body.results[0];
+
+        assert!(entry.flagged);
+        assert_eq!(entry.categories.get("violence"), Some(&true));
+        assert!((entry.category_scores["violence"] - 0.92).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_moderation_result_constructors() {
+        assert!(ModerationResult::pass().passed);
+
+        let flagged = ModerationResult::flagged(vec!["violence".to_string()], 0.9);
+        assert!(!flagged.passed);
+        assert_eq!(flagged.categories, vec!["violence".to_string()]);
+    }
+}
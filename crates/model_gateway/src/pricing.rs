@@ -3,6 +3,7 @@
 //! Provides pricing information and intelligent routing to minimize costs
 //! while maintaining quality requirements.
 
+use multi_agent_core::types::PricingTable;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -86,6 +87,13 @@ impl PricingRegistry {
         self.models.get(model_id)
     }
     
+    /// Build a replay pricing table from the registered models, for a session that ran on `original_model`.
+    pub fn to_pricing_table(&self, original_model: &str) -> PricingTable {
+        self.models.values().fold(PricingTable::new(original_model), |table, m| {
+            table.with_price(m.model_id.clone(), m.input_cost_per_1k, m.output_cost_per_1k)
+        })
+    }
+    
     /// Get all models sorted by cost (cheapest first).
     pub fn sorted_by_cost(&self) -> Vec<&ModelPricing> {
         let mut models: Vec<_> = self.models.values().collect();
@@ -238,6 +238,21 @@ impl LlmClient for CircuitBreakerClient {
         }
     }
 
+    async fn chat_with_max_tokens(&self, messages: &[ChatMessage], max_tokens: u64) -> Result<LlmResponse> {
+        self.check_health()?;
+
+        match self.inner.chat_with_max_tokens(messages, max_tokens).await {
+            Ok(res) => {
+                self.registry.record_success(&self.key);
+                Ok(res)
+            }
+            Err(e) => {
+                self.registry.record_failure(&self.key);
+                Err(e)
+            }
+        }
+    }
+
     async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         self.check_health()?;
 
@@ -300,6 +315,7 @@ impl LlmClient for MockLlmClient {
                 total_tokens: (prompt.len() + self.response.len()) as u64 / 4,
             },
             tool_calls: None,
+            reasoning: None,
         })
     }
 
@@ -319,6 +335,7 @@ impl LlmClient for MockLlmClient {
                 total_tokens: 0, // Will be calculated
             },
             tool_calls: None,
+            reasoning: None,
         })
     }
 
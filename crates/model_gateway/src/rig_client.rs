@@ -12,12 +12,15 @@ use multi_agent_core::{
 // Import required Rig traits
 use rig::client::{CompletionClient, EmbeddingsClient, ProviderClient};
 use rig::completion::Prompt;
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+use tiktoken_rs::CoreBPE;
 
 /// Provider type for Rig clients.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RigProvider {
     OpenAI,
     Anthropic,
+    Ollama,
 }
 
 /// Configuration for Rig client.
@@ -33,6 +36,8 @@ pub struct RigConfig {
     pub temperature: Option<f32>,
     /// Max tokens.
     pub max_tokens: Option<u32>,
+    /// API key (falls back to the provider's environment variable).
+    pub api_key: Option<String>,
 }
 
 impl Default for RigConfig {
@@ -43,6 +48,7 @@ impl Default for RigConfig {
             system_prompt: None,
             temperature: Some(0.7),
             max_tokens: Some(4096),
+            api_key: None,
         }
     }
 }
@@ -66,6 +72,21 @@ impl RigConfig {
         }
     }
 
+    /// Create config for a local Ollama server (`OLLAMA_API_BASE_URL`).
+    pub fn ollama(model: impl Into<String>) -> Self {
+        Self {
+            provider: RigProvider::Ollama,
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the API key.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
     /// Set system prompt.
     pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
         self.system_prompt = Some(prompt.into());
@@ -113,6 +134,23 @@ impl RigLlmClient {
         Self::new(RigConfig::anthropic("claude-3-haiku-20240307"))
     }
 
+    /// Get the local BPE tokenizer for the configured model.
+    ///
+    /// Only OpenAI publishes its tokenizers; Anthropic exposes token counts
+    /// but not token IDs, and Ollama models vary by model family.
+    fn bpe(&self) -> Result<fn() -> &'static CoreBPE> {
+        match self.config.provider {
+            RigProvider::OpenAI => Ok(match get_tokenizer(&self.config.model) {
+                Some(Tokenizer::Cl100kBase) => tiktoken_rs::cl100k_base_singleton,
+                _ => tiktoken_rs::o200k_base_singleton,
+            }),
+            provider => Err(Error::ModelProvider(format!(
+                "Tokenization is not supported for {:?}",
+                provider
+            ))),
+        }
+    }
+
     /// Build messages into a prompt string.
     fn build_prompt(&self, messages: &[ChatMessage]) -> String {
         let mut prompt = String::new();
@@ -135,27 +173,56 @@ impl RigLlmClient {
                     prompt.push_str(&format!("{}: {}\n\n", msg.role, msg.content));
                 }
             }
+            // The text prompt path cannot carry image payloads; list the refs instead.
+            for image in &msg.images {
+                prompt.push_str(&format!("[Image: {}]\n\n", image));
+            }
         }
 
         prompt
     }
 
-    /// Call OpenAI via Rig.
-    async fn call_openai(&self, prompt: &str) -> Result<LlmResponse> {
-        use rig::providers::openai;
+    /// Call the configured provider, optionally capping the completion length.
+    async fn call_provider(&self, prompt: &str, max_tokens: Option<u64>) -> Result<LlmResponse> {
+        tracing::debug!(
+            provider = ?self.config.provider,
+            model = %self.config.model,
+            prompt_len = prompt.len(),
+            max_tokens = ?max_tokens,
+            "Calling LLM"
+        );
 
-        // Check env var first to avoid panic
-        if std::env::var("OPENAI_API_KEY").is_err() {
-            return Err(Error::ModelProvider("OPENAI_API_KEY not set".to_string()));
+        match self.config.provider {
+            RigProvider::OpenAI => self.call_openai(prompt, max_tokens).await,
+            RigProvider::Anthropic => self.call_anthropic(prompt, max_tokens).await,
+            RigProvider::Ollama => self.call_ollama(prompt, max_tokens).await,
         }
+    }
 
-        let client = openai::Client::from_env();
+    /// Call OpenAI via Rig.
+    async fn call_openai(&self, prompt: &str, max_tokens: Option<u64>) -> Result<LlmResponse> {
+        use rig::providers::openai;
+
+        let client = match self.config.api_key {
+            Some(ref key) => openai::Client::from_val(key.clone().into()),
+            None => {
+                // Check env var first to avoid panic
+                if std::env::var("OPENAI_API_KEY").is_err() {
+                    return Err(Error::ModelProvider("OPENAI_API_KEY not set".to_string()));
+                }
+                openai::Client::from_env()
+            }
+        };
         
         let mut agent_builder = client.agent(&self.config.model);
         
         if let Some(ref system) = self.config.system_prompt {
             agent_builder = agent_builder.preamble(system);
         }
+
+        if let Some(max_tokens) = max_tokens {
+            agent_builder = agent_builder.max_tokens(max_tokens);
+        }
         
         let agent = agent_builder.build();
 
@@ -164,8 +231,10 @@ impl RigLlmClient {
             .await
             .map_err(|e| Error::ModelProvider(format!("OpenAI error: {}", e)))?;
 
+        let (content, reasoning) = split_reasoning(&response);
+
         Ok(LlmResponse {
-            content: response.clone(),
+            content,
             finish_reason: "stop".to_string(),
             usage: LlmUsage {
                 prompt_tokens: (prompt.len() / 4) as u64,
@@ -173,25 +242,34 @@ impl RigLlmClient {
                 total_tokens: ((prompt.len() + response.len()) / 4) as u64,
             },
             tool_calls: None,
+            reasoning,
         })
     }
 
     /// Call Anthropic via Rig.
-    async fn call_anthropic(&self, prompt: &str) -> Result<LlmResponse> {
+    async fn call_anthropic(&self, prompt: &str, max_tokens: Option<u64>) -> Result<LlmResponse> {
         use rig::providers::anthropic;
 
-        // Check env var first to avoid panic
-        if std::env::var("ANTHROPIC_API_KEY").is_err() {
-            return Err(Error::ModelProvider("ANTHROPIC_API_KEY not set".to_string()));
-        }
-
-        let client = anthropic::Client::from_env();
+        let client = match self.config.api_key {
+            Some(ref key) => anthropic::Client::from_val(key.clone()),
+            None => {
+                // Check env var first to avoid panic
+                if std::env::var("ANTHROPIC_API_KEY").is_err() {
+                    return Err(Error::ModelProvider("ANTHROPIC_API_KEY not set".to_string()));
+                }
+                anthropic::Client::from_env()
+            }
+        };
         
         let mut agent_builder = client.agent(&self.config.model);
         
         if let Some(ref system) = self.config.system_prompt {
             agent_builder = agent_builder.preamble(system);
         }
+
+        if let Some(max_tokens) = max_tokens {
+            agent_builder = agent_builder.max_tokens(max_tokens);
+        }
         
         let agent = agent_builder.build();
 
@@ -200,8 +278,48 @@ impl RigLlmClient {
             .await
             .map_err(|e| Error::ModelProvider(format!("Anthropic error: {}", e)))?;
 
+        let (content, reasoning) = split_reasoning(&response);
+
+        Ok(LlmResponse {
+            content,
+            finish_reason: "stop".to_string(),
+            usage: LlmUsage {
+                prompt_tokens: (prompt.len() / 4) as u64,
+                completion_tokens: (response.len() / 4) as u64,
+                total_tokens: ((prompt.len() + response.len()) / 4) as u64,
+            },
+            tool_calls: None,
+            reasoning,
+        })
+    }
+
+    /// Call a local Ollama server via Rig.
+    async fn call_ollama(&self, prompt: &str, max_tokens: Option<u64>) -> Result<LlmResponse> {
+        use rig::providers::ollama;
+
+        let client = ollama::Client::from_env();
+
+        let mut agent_builder = client.agent(&self.config.model);
+
+        if let Some(ref system) = self.config.system_prompt {
+            agent_builder = agent_builder.preamble(system);
+        }
+
+        if let Some(max_tokens) = max_tokens {
+            agent_builder = agent_builder.max_tokens(max_tokens);
+        }
+
+        let agent = agent_builder.build();
+
+        let response: String = agent
+            .prompt(prompt)
+            .await
+            .map_err(|e| Error::ModelProvider(format!("Ollama error: {}", e)))?;
+
+        let (content, reasoning) = split_reasoning(&response);
+
         Ok(LlmResponse {
-            content: response.clone(),
+            content,
             finish_reason: "stop".to_string(),
             usage: LlmUsage {
                 prompt_tokens: (prompt.len() / 4) as u64,
@@ -209,6 +327,7 @@ impl RigLlmClient {
                 total_tokens: ((prompt.len() + response.len()) / 4) as u64,
             },
             tool_calls: None,
+            reasoning,
         })
     }
 }
@@ -216,17 +335,7 @@ impl RigLlmClient {
 #[async_trait]
 impl LlmClient for RigLlmClient {
     async fn complete(&self, prompt: &str) -> Result<LlmResponse> {
-        tracing::debug!(
-            provider = ?self.config.provider,
-            model = %self.config.model,
-            prompt_len = prompt.len(),
-            "Calling LLM"
-        );
-
-        match self.config.provider {
-            RigProvider::OpenAI => self.call_openai(prompt).await,
-            RigProvider::Anthropic => self.call_anthropic(prompt).await,
-        }
+        self.call_provider(prompt, None).await
     }
 
     async fn chat(&self, messages: &[ChatMessage]) -> Result<LlmResponse> {
@@ -234,6 +343,34 @@ impl LlmClient for RigLlmClient {
         self.complete(&prompt).await
     }
 
+    async fn chat_with_max_tokens(&self, messages: &[ChatMessage], max_tokens: u64) -> Result<LlmResponse> {
+        let prompt = self.build_prompt(messages);
+        self.call_provider(&prompt, Some(max_tokens)).await
+    }
+
+    async fn count_tokens(&self, messages: &[ChatMessage]) -> Result<u64> {
+        // Matches the prompt token accounting used for completions
+        Ok((self.build_prompt(messages).len() / 4) as u64)
+    }
+
+    async fn tokenize(&self, text: &str) -> Result<Vec<u32>> {
+        let bpe = self.bpe()?;
+        let text = text.to_string();
+        // BPE encoding is CPU-bound, keep it off the async workers
+        tokio::task::spawn_blocking(move || bpe().encode_with_special_tokens(&text))
+            .await
+            .map_err(|e| Error::Internal(format!("Tokenizer task failed: {}", e)))
+    }
+
+    async fn detokenize(&self, tokens: &[u32]) -> Result<String> {
+        let bpe = self.bpe()?;
+        let tokens = tokens.to_vec();
+        tokio::task::spawn_blocking(move || bpe().decode(tokens))
+            .await
+            .map_err(|e| Error::Internal(format!("Tokenizer task failed: {}", e)))?
+            .map_err(|e| Error::ModelProvider(format!("Invalid token IDs: {}", e)))
+    }
+
     async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         use rig::providers::openai;
         use rig::embeddings::EmbeddingsBuilder;
@@ -265,6 +402,24 @@ impl LlmClient for RigLlmClient {
     }
 }
 
+/// Split an inline `<think>...</think>` reasoning block from the response text.
+///
+/// Rig's prompt API returns plain text, so reasoning models surface their
+/// trace inline rather than as a separate field.
+fn split_reasoning(response: &str) -> (String, Option<String>) {
+    let trimmed = response.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("<think>") {
+        if let Some((reasoning, content)) = rest.split_once("</think>") {
+            let reasoning = reasoning.trim();
+            return (
+                content.trim().to_string(),
+                (!reasoning.is_empty()).then(|| reasoning.to_string()),
+            );
+        }
+    }
+    (response.to_string(), None)
+}
+
 /// Create a default LLM client based on available API keys.
 pub fn create_default_client() -> Result<RigLlmClient> {
     if std::env::var("OPENAI_API_KEY").is_ok() {
@@ -294,6 +449,38 @@ mod tests {
         assert_eq!(config.temperature, Some(0.5));
     }
 
+    #[tokio::test]
+    async fn test_tokenize_round_trip() {
+        let client = RigLlmClient::gpt4o_mini();
+        let text = "Tokenizers split unbelievably long words into subwords. <|endoftext|>";
+
+        let tokens = client.tokenize(text).await.unwrap();
+        assert!(!tokens.is_empty());
+        assert!(tokens.len() < text.len());
+        assert_eq!(client.detokenize(&tokens).await.unwrap(), text);
+
+        let legacy = RigLlmClient::new(RigConfig::openai("gpt-4"));
+        let legacy_tokens = legacy.tokenize(text).await.unwrap();
+        assert_eq!(legacy.detokenize(&legacy_tokens).await.unwrap(), text);
+    }
+
+    #[tokio::test]
+    async fn test_tokenize_unsupported_provider() {
+        let client = RigLlmClient::claude_haiku();
+        assert!(client.tokenize("hello").await.is_err());
+    }
+
+    #[test]
+    fn test_split_reasoning() {
+        let (content, reasoning) = split_reasoning("<think>2 + 2 is 4</think>\nFINAL ANSWER: 4");
+        assert_eq!(content, "FINAL ANSWER: 4");
+        assert_eq!(reasoning.as_deref(), Some("2 + 2 is 4"));
+
+        let (content, reasoning) = split_reasoning("FINAL ANSWER: 4");
+        assert_eq!(content, "FINAL ANSWER: 4");
+        assert!(reasoning.is_none());
+    }
+
     #[test]
     fn test_build_prompt() {
         let client = RigLlmClient::gpt4o_mini();
@@ -303,11 +490,13 @@ mod tests {
                 role: "system".to_string(),
                 content: "You are helpful".to_string(),
                 tool_calls: None,
+                images: Vec::new(),
             },
             ChatMessage {
                 role: "user".to_string(),
                 content: "Hello".to_string(),
                 tool_calls: None,
+                images: Vec::new(),
             },
         ];
 
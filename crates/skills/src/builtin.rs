@@ -110,6 +110,82 @@ impl Tool for ReadArtifactTool {
     }
 }
 
+// =============================================================================
+// Fetch Artifact Tool
+// =============================================================================
+
+/// Default number of characters returned by `FetchArtifactTool`.
+pub const DEFAULT_FETCH_ARTIFACT_MAX_CHARS: usize = 20_000;
+
+/// Tool dereferencing a RefID, registered by the ReAct controller when an
+/// artifact store is configured.
+pub struct FetchArtifactTool {
+    store: Arc<dyn ArtifactStore>,
+    max_chars: usize,
+}
+
+impl FetchArtifactTool {
+    /// Create a fetch artifact tool returning at most `DEFAULT_FETCH_ARTIFACT_MAX_CHARS`.
+    pub fn new(store: Arc<dyn ArtifactStore>) -> Self {
+        Self {
+            store,
+            max_chars: DEFAULT_FETCH_ARTIFACT_MAX_CHARS,
+        }
+    }
+
+    /// Set the number of characters returned before the content is truncated.
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = max_chars;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for FetchArtifactTool {
+    fn name(&self) -> &str {
+        "fetch_artifact"
+    }
+
+    fn description(&self) -> &str {
+        "Fetch the full content behind a RefID returned in place of a large output"
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "ref_id": {
+                    "type": "string",
+                    "description": "The RefID of the stored output"
+                }
+            },
+            "required": ["ref_id"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolOutput> {
+        let ref_id_str = args
+            .get("ref_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| multi_agent_core::Error::invalid_request("ref_id is required"))?;
+
+        let Some(bytes) = self.store.load(&RefId::from_string(ref_id_str)).await? else {
+            return Ok(ToolOutput::error(format!("Artifact not found: {}", ref_id_str)));
+        };
+
+        let content = String::from_utf8_lossy(&bytes);
+        let total_chars = content.chars().count();
+        if total_chars <= self.max_chars {
+            return Ok(ToolOutput::text(content));
+        }
+        let truncated: String = content.chars().take(self.max_chars).collect();
+        Ok(ToolOutput::text(format!(
+            "{}\n[Truncated: showing {} of {} characters]",
+            truncated, self.max_chars, total_chars
+        )))
+    }
+}
+
 // =============================================================================
 // Calculator Tool
 // =============================================================================
@@ -275,4 +351,18 @@ mod tests {
         assert!(!result.success);
         assert!(result.content.contains("Division by zero"));
     }
+
+    #[tokio::test]
+    async fn test_fetch_artifact_truncates() {
+        let store = Arc::new(multi_agent_store::InMemoryStore::new());
+        let ref_id = store.save(bytes::Bytes::from("abcdefghij")).await.unwrap();
+        let tool = FetchArtifactTool::new(store).with_max_chars(4);
+
+        let result = tool.execute(json!({ "ref_id": ref_id.as_str() })).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.starts_with("abcd\n[Truncated: showing 4 of 10 characters]"));
+
+        let missing = tool.execute(json!({ "ref_id": "missing" })).await.unwrap();
+        assert!(!missing.success);
+    }
 }
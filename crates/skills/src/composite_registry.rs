@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::sync::Arc;
 use multi_agent_core::{Result, Error};
-use multi_agent_core::traits::{Tool, ToolRegistry};
+use multi_agent_core::traits::{Tool, ToolOutputStream, ToolRegistry};
 use multi_agent_core::types::{ToolDefinition, ToolOutput};
 
 /// A registry that aggregates multiple other registries.
@@ -65,4 +65,22 @@ impl ToolRegistry for CompositeToolRegistry {
         }
         Err(Error::tool_not_found(name))
     }
+
+    async fn execute_streaming(&self, name: &str, args: Value) -> Result<ToolOutputStream> {
+        for registry in &self.registries {
+            if let Ok(Some(_)) = registry.get(name).await {
+                return registry.execute_streaming(name, args).await;
+            }
+        }
+        Err(Error::tool_not_found(name))
+    }
+
+    async fn preload(&self) -> Result<()> {
+        futures::future::try_join_all(self.registries.iter().map(|r| r.preload())).await?;
+        Ok(())
+    }
+
+    fn is_preloaded(&self) -> bool {
+        self.registries.iter().all(|r| r.is_preloaded())
+    }
 }
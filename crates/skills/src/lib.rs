@@ -10,6 +10,7 @@
 pub mod builtin;
 pub mod code_simplifier;
 pub mod mcp_adapter;
+pub mod mcp_client;
 pub mod mcp_registry;
 pub mod registry;
 pub mod composite_registry;
@@ -17,6 +18,7 @@ pub mod composite_registry;
 pub use builtin::*;
 pub use code_simplifier::{simplify_rust_code, SimplifiedCode};
 pub use mcp_adapter::{McpToolAdapter, McpTransport, McpTool};
+pub use mcp_client::{HttpMcpClient, McpClient, StdioMcpClient};
 pub use mcp_registry::{McpRegistry, McpServerInfo, McpCapability};
 pub use registry::DefaultToolRegistry;
 pub use composite_registry::CompositeToolRegistry;
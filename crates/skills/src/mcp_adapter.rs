@@ -9,10 +9,13 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use multi_agent_core::{
+    traits::ToolRegistry,
     types::{ToolDefinition, ToolOutput},
     Error, Result,
 };
 
+use crate::mcp_client::{self, HttpMcpClient, McpClient, StdioMcpClient};
+
 /// MCP transport type for connecting to servers.
 #[derive(Debug, Clone)]
 pub enum McpTransport {
@@ -23,12 +26,17 @@ pub enum McpTransport {
         /// Command arguments
         args: Vec<String>,
     },
-    /// Connect via Server-Sent Events
+    /// Connect via Streamable HTTP (JSON-RPC over POST)
+    Http {
+        /// Endpoint URL
+        url: String,
+    },
+    /// Connect via Server-Sent Events (not supported yet)
     Sse {
         /// SSE endpoint URL
         url: String,
     },
-    /// Connect via WebSocket
+    /// Connect via WebSocket (not supported yet)
     WebSocket {
         /// WebSocket URL
         url: String,
@@ -57,6 +65,8 @@ pub struct McpServerConnection {
 pub struct McpToolAdapter {
     /// Connected servers
     servers: DashMap<String, Arc<RwLock<McpServerConnection>>>,
+    /// JSON-RPC clients of the connected servers
+    clients: DashMap<String, Arc<dyn McpClient>>,
 }
 
 impl Default for McpToolAdapter {
@@ -70,6 +80,7 @@ impl McpToolAdapter {
     pub fn new() -> Self {
         Self {
             servers: DashMap::new(),
+            clients: DashMap::new(),
         }
     }
 
@@ -89,52 +100,68 @@ impl McpToolAdapter {
     pub async fn connect(&self, name: &str, transport: McpTransport) -> Result<()> {
         tracing::info!(server = %name, transport = ?transport, "Connecting to MCP server");
 
-        // Create connection state
+        let client: Arc<dyn McpClient> = match transport {
+            McpTransport::Stdio { ref command, ref args } => Arc::new(StdioMcpClient::spawn(command, args)?),
+            McpTransport::Http { ref url } => Arc::new(HttpMcpClient::new(url.clone())),
+            McpTransport::Sse { .. } | McpTransport::WebSocket { .. } => {
+                return Err(Error::mcp_adapter(format!(
+                    "Transport {:?} is not supported yet, use stdio or http",
+                    transport
+                )));
+            }
+        };
+        self.connect_client(name, transport, client).await
+    }
+
+    /// Connect to an MCP server through an existing client.
+    ///
+    /// Performs the `initialize` handshake and fetches the tool list; tools are
+    /// named `server_name/tool_name`.
+    pub async fn connect_client(&self, name: &str, transport: McpTransport, client: Arc<dyn McpClient>) -> Result<()> {
+        mcp_client::initialize(client.as_ref()).await?;
+        let tools: Vec<ToolDefinition> = mcp_client::list_tools(client.as_ref())
+            .await?
+            .into_iter()
+            .map(|tool| ToolDefinition {
+                name: format!("{}/{}", name, tool.name),
+                ..tool
+            })
+            .collect();
+
+        tracing::info!(server = %name, tools = tools.len(), "MCP server connected");
         let connection = McpServerConnection {
             name: name.to_string(),
-            transport: transport.clone(),
-            connected: false,
-            tools: Vec::new(),
+            transport,
+            connected: true,
+            tools,
         };
+        self.clients.insert(name.to_string(), client);
+        self.servers.insert(name.to_string(), Arc::new(RwLock::new(connection)));
+        Ok(())
+    }
 
-        // Store connection (actual MCP connection would happen here)
-        self.servers.insert(
-            name.to_string(),
-            Arc::new(RwLock::new(connection)),
-        );
-
-        // In a full implementation, we would:
-        // 1. Spawn the transport (subprocess, HTTP client, WebSocket client)
-        // 2. Send initialize request
-        // 3. Receive capabilities and tool list
-        // 4. Store the tools
-
-        // For now, mark as connected (mock)
-        if let Some(server) = self.servers.get(name) {
-            let mut conn = server.write().await;
-            conn.connected = true;
-            
-            // Mock: Add some placeholder tools
-            conn.tools.push(ToolDefinition {
-                name: format!("{}/list_files", name),
-                description: "List files in a directory".to_string(),
-                parameters: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "path": {"type": "string", "description": "Directory path"}
-                    },
-                    "required": ["path"]
-                }),
-                supports_streaming: false,
-            });
+    /// Register the tools of a connected server into `registry`.
+    ///
+    /// Returns the number of tools registered.
+    pub async fn register_tools(self: &Arc<Self>, server_name: &str, registry: &dyn ToolRegistry) -> Result<usize> {
+        let tools = self.get_server_tools(server_name).await?;
+        let count = tools.len();
+        for def in tools {
+            registry
+                .register(Box::new(McpToolWrapper {
+                    adapter: self.clone(),
+                    name: def.name,
+                    description: def.description,
+                    parameters: def.parameters,
+                }))
+                .await?;
         }
-
-        tracing::info!(server = %name, "MCP server connected (mock)");
-        Ok(())
+        Ok(count)
     }
 
     /// Disconnect from an MCP server.
     pub async fn disconnect(&self, name: &str) -> Result<()> {
+        self.clients.remove(name);
         if let Some((_, server)) = self.servers.remove(name) {
             let mut conn = server.write().await;
             conn.connected = false;
@@ -294,17 +321,13 @@ impl McpToolAdapter {
             )));
         }
 
-        // In a full implementation, we would:
-        // 1. Send tool call request to the MCP server
-        // 2. Wait for response
-        // 3. Parse and return result
-
-        // Mock response
-        Ok(ToolOutput::text(format!(
-            "MCP tool '{}' executed with args: {}. (Mock response - real MCP integration pending)",
-            full_tool_name,
-            serde_json::to_string_pretty(&args).unwrap_or_default()
-        )))
+        drop(conn);
+        let client = self
+            .clients
+            .get(server_name)
+            .map(|client| client.value().clone())
+            .ok_or_else(|| Error::mcp_adapter(format!("MCP server '{}' is not connected", server_name)))?;
+        mcp_client::call_tool(client.as_ref(), tool_name, args).await
     }
 
     /// Check if a tool name is an MCP tool (contains '/').
@@ -402,30 +425,69 @@ impl multi_agent_core::traits::Tool for McpTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::{json, Value};
+    use std::sync::Mutex;
+
+    /// MCP server stand-in exposing a `list_files` tool and recording requests.
+    #[derive(Default)]
+    struct MockMcpClient {
+        requests: Mutex<Vec<(String, Value)>>,
+    }
+
+    #[async_trait]
+    impl McpClient for MockMcpClient {
+        async fn request(&self, method: &str, params: Value) -> Result<Value> {
+            self.requests.lock().unwrap().push((method.to_string(), params.clone()));
+            Ok(match method {
+                "initialize" => json!({ "protocolVersion": mcp_client::MCP_PROTOCOL_VERSION, "capabilities": {} }),
+                "tools/list" => json!({
+                    "tools": [{
+                        "name": "list_files",
+                        "description": "List files in a directory",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": { "path": { "type": "string" } },
+                            "required": ["path"]
+                        }
+                    }]
+                }),
+                "tools/call" => json!({
+                    "content": [{ "type": "text", "text": format!("files in {}", params["arguments"]["path"]) }]
+                }),
+                _ => json!({}),
+            })
+        }
+    }
+
+    fn stdio() -> McpTransport {
+        McpTransport::Stdio {
+            command: "mock".to_string(),
+            args: vec![],
+        }
+    }
 
     #[tokio::test]
     async fn test_connect_and_list() {
         let adapter = McpToolAdapter::new();
-        
-        adapter.connect("test-server", McpTransport::Stdio {
-            command: "echo".to_string(),
-            args: vec![],
-        }).await.unwrap();
+        adapter
+            .connect_client("test-server", stdio(), Arc::new(MockMcpClient::default()))
+            .await
+            .unwrap();
 
         let servers = adapter.list_servers();
         assert!(servers.contains(&"test-server".to_string()));
 
         let tools = adapter.list_tools().await.unwrap();
-        assert!(!tools.is_empty());
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "test-server/list_files");
+        assert_eq!(tools[0].parameters["required"], json!(["path"]));
     }
 
     #[tokio::test]
     async fn test_call_tool() {
         let adapter = McpToolAdapter::new();
-        
-        adapter.connect("fs", McpTransport::Sse {
-            url: "http://localhost:8080".to_string(),
-        }).await.unwrap();
+        let client = Arc::new(MockMcpClient::default());
+        adapter.connect_client("fs", stdio(), client.clone()).await.unwrap();
 
         let result = adapter.call_tool(
             "fs/list_files",
@@ -433,7 +495,21 @@ mod tests {
         ).await.unwrap();
 
         assert!(result.success);
-        assert!(result.content.contains("list_files"));
+        assert_eq!(result.content, "files in \"/tmp\"");
+        let requests = client.requests.lock().unwrap();
+        let (method, params) = requests.last().unwrap();
+        assert_eq!(method, "tools/call");
+        assert_eq!(params, &json!({ "name": "list_files", "arguments": { "path": "/tmp" } }));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_transport_is_rejected() {
+        let adapter = McpToolAdapter::new();
+        let result = adapter
+            .connect("fs", McpTransport::Sse { url: "http://localhost:8080".to_string() })
+            .await;
+        assert!(result.is_err());
+        assert!(adapter.list_servers().is_empty());
     }
 
     #[test]
@@ -0,0 +1,331 @@
+//! JSON-RPC clients speaking the Model Context Protocol.
+//!
+//! `McpClient` abstracts the transport; `StdioMcpClient` talks to a server
+//! subprocess over newline-delimited JSON and `HttpMcpClient` posts requests
+//! to a Streamable HTTP endpoint. The helpers below implement the handshake
+//! and the `tools/list` / `tools/call` methods on top of any client.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+use multi_agent_core::{
+    types::{ToolDefinition, ToolOutput},
+    Error, Result,
+};
+
+/// MCP protocol revision sent during the handshake.
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Transport carrying JSON-RPC messages to an MCP server.
+#[async_trait]
+pub trait McpClient: Send + Sync {
+    /// Send a request and return its `result`, failing on a JSON-RPC error.
+    async fn request(&self, method: &str, params: Value) -> Result<Value>;
+
+    /// Send a notification (no response expected).
+    async fn notify(&self, _method: &str, _params: Value) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Perform the `initialize` handshake.
+pub async fn initialize(client: &dyn McpClient) -> Result<Value> {
+    let result = client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "multi_agent", "version": env!("CARGO_PKG_VERSION") }
+            }),
+        )
+        .await?;
+    client.notify("notifications/initialized", json!({})).await?;
+    Ok(result)
+}
+
+/// List the server's tools, following pagination cursors.
+pub async fn list_tools(client: &dyn McpClient) -> Result<Vec<ToolDefinition>> {
+    let mut tools = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let params = match cursor {
+            Some(ref cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let result = client.request("tools/list", params).await?;
+        let page = result
+            .get("tools")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::mcp_adapter("tools/list response has no 'tools' array"))?;
+
+        for tool in page {
+            let name = tool
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::mcp_adapter("tools/list entry has no name"))?;
+            tools.push(ToolDefinition {
+                name: name.to_string(),
+                description: tool.get("description").and_then(Value::as_str).unwrap_or_default().to_string(),
+                parameters: tool.get("inputSchema").cloned().unwrap_or_else(|| json!({ "type": "object" })),
+                supports_streaming: false,
+            });
+        }
+
+        cursor = result.get("nextCursor").and_then(Value::as_str).map(String::from);
+        if cursor.is_none() {
+            return Ok(tools);
+        }
+    }
+}
+
+/// Call `name` on the server and convert the result to a `ToolOutput`.
+///
+/// Text content blocks are joined; `structuredContent` becomes `data`, and
+/// `isError` marks the output as failed.
+pub async fn call_tool(client: &dyn McpClient, name: &str, arguments: Value) -> Result<ToolOutput> {
+    let result = client
+        .request("tools/call", json!({ "name": name, "arguments": arguments }))
+        .await?;
+
+    let text = result
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|blocks| {
+            blocks
+                .iter()
+                .map(|block| match block.get("text").and_then(Value::as_str) {
+                    Some(text) => text.to_string(),
+                    None => block.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+
+    let mut output = if result.get("isError").and_then(Value::as_bool).unwrap_or(false) {
+        ToolOutput::error(text)
+    } else {
+        ToolOutput::text(text)
+    };
+    if let Some(data) = result.get("structuredContent") {
+        output.data = Some(data.clone());
+    }
+    Ok(output)
+}
+
+/// Extract the `result` of a JSON-RPC response.
+fn into_result(mut response: Value) -> Result<Value> {
+    if let Some(error) = response.get("error") {
+        return Err(Error::mcp_adapter(format!(
+            "MCP error {}: {}",
+            error.get("code").and_then(Value::as_i64).unwrap_or_default(),
+            error.get("message").and_then(Value::as_str).unwrap_or("unknown error")
+        )));
+    }
+    Ok(response.get_mut("result").map(Value::take).unwrap_or(Value::Null))
+}
+
+/// MCP client for a server subprocess speaking newline-delimited JSON-RPC on stdio.
+pub struct StdioMcpClient {
+    /// Kept so the subprocess is killed when the client is dropped.
+    _child: Child,
+    io: Mutex<(ChildStdin, BufReader<ChildStdout>)>,
+    next_id: AtomicU64,
+}
+
+impl StdioMcpClient {
+    /// Spawn `command` with `args` as the server process.
+    pub fn spawn(command: &str, args: &[String]) -> Result<Self> {
+        let mut child = Command::new(command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::mcp_adapter(format!("Failed to start MCP server '{}': {}", command, e)))?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(Error::mcp_adapter("MCP server stdio is not available"));
+        };
+        Ok(Self {
+            _child: child,
+            io: Mutex::new((stdin, BufReader::new(stdout))),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    async fn send(stdin: &mut ChildStdin, message: &Value) -> Result<()> {
+        let mut line = message.to_string();
+        line.push('\n');
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| Error::mcp_adapter(format!("Failed to write to MCP server: {}", e)))?;
+        stdin
+            .flush()
+            .await
+            .map_err(|e| Error::mcp_adapter(format!("Failed to write to MCP server: {}", e)))
+    }
+}
+
+#[async_trait]
+impl McpClient for StdioMcpClient {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut io = self.io.lock().await;
+        let (stdin, stdout) = &mut *io;
+        Self::send(stdin, &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).await?;
+
+        // Skip server notifications and log lines until our response arrives
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = stdout
+                .read_line(&mut line)
+                .await
+                .map_err(|e| Error::mcp_adapter(format!("Failed to read from MCP server: {}", e)))?;
+            if read == 0 {
+                return Err(Error::mcp_adapter(format!("MCP server closed the connection during '{}'", method)));
+            }
+            let Ok(message) = serde_json::from_str::<Value>(line.trim()) else {
+                continue;
+            };
+            if message.get("id").and_then(Value::as_u64) == Some(id) {
+                return into_result(message);
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let mut io = self.io.lock().await;
+        Self::send(&mut io.0, &json!({ "jsonrpc": "2.0", "method": method, "params": params })).await
+    }
+}
+
+/// MCP client posting JSON-RPC messages to a Streamable HTTP endpoint.
+pub struct HttpMcpClient {
+    url: String,
+    http: reqwest::Client,
+    /// Session id assigned by the server during initialization.
+    session_id: std::sync::Mutex<Option<String>>,
+    next_id: AtomicU64,
+}
+
+impl HttpMcpClient {
+    /// Create a client for the endpoint at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::new(),
+            session_id: std::sync::Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response> {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        let session_id = self.session_id.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some(session_id) = session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::mcp_adapter(format!("MCP request to {} failed: {}", self.url, e)))?;
+        if !response.status().is_success() {
+            return Err(Error::mcp_adapter(format!("MCP server {} returned {}", self.url, response.status())));
+        }
+        if let Some(session_id) = response.headers().get("Mcp-Session-Id").and_then(|v| v.to_str().ok()) {
+            *self.session_id.lock().unwrap_or_else(|e| e.into_inner()) = Some(session_id.to_string());
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl McpClient for HttpMcpClient {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self
+            .post(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+        let is_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::mcp_adapter(format!("Failed to read MCP response: {}", e)))?;
+
+        if !is_stream {
+            return into_result(serde_json::from_str(&body)?);
+        }
+        // Streamed responses carry JSON-RPC messages in `data:` lines
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|message| message.get("id").and_then(Value::as_u64) == Some(id))
+            .map(into_result)
+            .unwrap_or_else(|| Err(Error::mcp_adapter(format!("No response to '{}' in MCP event stream", method))))
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.post(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves canned results per method.
+    struct CannedClient;
+
+    #[async_trait]
+    impl McpClient for CannedClient {
+        async fn request(&self, method: &str, params: Value) -> Result<Value> {
+            match method {
+                "tools/list" if params.get("cursor").is_none() => Ok(json!({
+                    "tools": [{ "name": "a", "inputSchema": { "type": "object" } }],
+                    "nextCursor": "page2"
+                })),
+                "tools/list" => Ok(json!({ "tools": [{ "name": "b", "description": "Second" }] })),
+                "tools/call" => into_result(json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": { "code": -32602, "message": "Unknown tool" }
+                })),
+                _ => Ok(json!({})),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_follows_cursor() {
+        let tools = list_tools(&CannedClient).await.unwrap();
+        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(tools[1].description, "Second");
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_surfaces_rpc_errors() {
+        let err = call_tool(&CannedClient, "missing", json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("Unknown tool"));
+    }
+}
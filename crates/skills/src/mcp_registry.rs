@@ -65,7 +65,7 @@ pub struct McpServerInfo {
     pub keywords: Vec<String>,
     /// Connection URL or command.
     pub connection_uri: String,
-    /// Transport type (stdio, sse, websocket).
+    /// Transport type (stdio, http, sse, websocket).
     pub transport_type: String,
     /// Priority (higher = preferred).
     pub priority: u8,
@@ -305,9 +305,15 @@ impl McpRegistry {
             .ok_or_else(|| Error::mcp_adapter(format!("Server '{}' not found in registry", server_id)))?;
         
         let transport = match server.transport_type.as_str() {
-            "stdio" => McpTransport::Stdio {
-                command: server.connection_uri.clone(),
-                args: vec![],
+            "stdio" => {
+                let mut parts = server.connection_uri.split_whitespace().map(String::from);
+                McpTransport::Stdio {
+                    command: parts.next().unwrap_or_default(),
+                    args: parts.collect(),
+                }
+            }
+            "http" => McpTransport::Http {
+                url: server.connection_uri.clone(),
             },
             "sse" => McpTransport::Sse {
                 url: server.connection_uri.clone(),
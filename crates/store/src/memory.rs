@@ -109,11 +109,11 @@ impl SessionStore for InMemorySessionStore {
         Ok(())
     }
 
-    async fn list_running(&self) -> Result<Vec<String>> {
+    async fn list_by_status(&self, status: SessionStatus) -> Result<Vec<String>> {
         Ok(self
             .sessions
             .iter()
-            .filter(|r| r.status == SessionStatus::Running)
+            .filter(|r| r.status == status)
             .map(|r| r.key().clone())
             .collect())
     }
@@ -5,9 +5,9 @@
 
 use async_trait::async_trait;
 use qdrant_client::qdrant::{
-    CreateCollectionBuilder, Distance, PointStruct, SearchPointsBuilder,
+    CreateCollectionBuilder, Distance, GetPointsBuilder, PointStruct, SearchPointsBuilder,
     UpsertPointsBuilder, VectorParamsBuilder, PointId, DeletePointsBuilder,
-    PointsIdsList, Value as QdrantValue,
+    PointsIdsList, Value as QdrantValue, VectorsOutput,
     vectors_config::Config as VectorsConfigEnum, VectorsConfig,
 };
 use qdrant_client::Qdrant;
@@ -18,6 +18,9 @@ use multi_agent_core::{
     Error, Result,
 };
 
+/// Payload field holding the original entry ID.
+const ENTRY_ID_FIELD: &str = "entry_id";
+
 /// Qdrant-backed vector store for production RAG workloads.
 pub struct QdrantMemoryStore {
     client: Qdrant,
@@ -82,9 +85,30 @@ impl QdrantMemoryStore {
         Ok(())
     }
 
+    /// Map an entry ID to a Qdrant point ID.
+    ///
+    /// Qdrant only accepts unsigned integers and UUIDs as point IDs; any other
+    /// ID is mapped to a name-based UUID. The original ID is kept in the payload.
+    fn point_id(id: &str) -> PointId {
+        if let Ok(num) = id.parse::<u64>() {
+            return PointId::from(num);
+        }
+        match uuid::Uuid::parse_str(id) {
+            Ok(uuid) => PointId::from(uuid.to_string()),
+            Err(_) => PointId::from(uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, id.as_bytes()).to_string()),
+        }
+    }
+
     /// Convert a HashMap<String, String> to Qdrant payload format.
-    fn to_qdrant_payload(metadata: &HashMap<String, String>, content: &str) -> HashMap<String, QdrantValue> {
+    fn to_qdrant_payload(id: &str, metadata: &HashMap<String, String>, content: &str) -> HashMap<String, QdrantValue> {
         let mut payload = HashMap::new();
+
+        payload.insert(
+            ENTRY_ID_FIELD.to_string(),
+            QdrantValue {
+                kind: Some(qdrant_client::qdrant::value::Kind::StringValue(id.to_string())),
+            },
+        );
         
         // Add content as a field
         payload.insert(
@@ -107,22 +131,64 @@ impl QdrantMemoryStore {
         payload
     }
 
-    /// Extract content and metadata from Qdrant payload.
-    fn from_qdrant_payload(payload: &HashMap<String, QdrantValue>) -> (String, HashMap<String, String>) {
+    /// Extract the entry ID (if stored), content and metadata from Qdrant payload.
+    fn from_qdrant_payload(payload: &HashMap<String, QdrantValue>) -> (Option<String>, String, HashMap<String, String>) {
         let mut metadata = HashMap::new();
         let mut content = String::new();
+        let mut id = None;
 
         for (key, value) in payload {
             if let Some(qdrant_client::qdrant::value::Kind::StringValue(s)) = &value.kind {
-                if key == "content" {
-                    content = s.clone();
-                } else {
-                    metadata.insert(key.clone(), s.clone());
+                match key.as_str() {
+                    "content" => content = s.clone(),
+                    ENTRY_ID_FIELD => id = Some(s.clone()),
+                    _ => {
+                        metadata.insert(key.clone(), s.clone());
+                    }
                 }
             }
         }
 
-        (content, metadata)
+        (id, content, metadata)
+    }
+
+    /// Build a memory entry from a returned Qdrant point.
+    fn to_memory_entry(
+        id: Option<PointId>,
+        payload: &HashMap<String, QdrantValue>,
+        vectors: Option<VectorsOutput>,
+    ) -> Option<MemoryEntry> {
+        let id = match id? {
+            PointId { point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) } => uuid,
+            PointId { point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) } => num.to_string(),
+            _ => return None,
+        };
+
+        // Extract embedding from VectorsOutput
+        let embedding = vectors.and_then(|vo| {
+            use qdrant_client::qdrant::vectors_output::VectorsOptions;
+            use qdrant_client::qdrant::vector_output::Vector;
+            match vo.vectors_options? {
+                VectorsOptions::Vector(v) => {
+                    // The VectorOutput has a .vector field which is Option<Vector enum>
+                    match v.vector? {
+                        Vector::Dense(dense) => Some(dense.data),
+                        _ => None,
+                    }
+                },
+                _ => None,
+            }
+        })?;
+
+        let (entry_id, content, metadata) = Self::from_qdrant_payload(payload);
+
+        Some(MemoryEntry {
+            // Points stored before the entry ID was kept in the payload
+            id: entry_id.unwrap_or(id),
+            content,
+            embedding,
+            metadata,
+        })
     }
 }
 
@@ -130,9 +196,9 @@ impl QdrantMemoryStore {
 impl MemoryStore for QdrantMemoryStore {
     async fn add(&self, entry: MemoryEntry) -> Result<()> {
         let point = PointStruct::new(
-            entry.id.clone(),
+            Self::point_id(&entry.id),
             entry.embedding.clone(),
-            Self::to_qdrant_payload(&entry.metadata, &entry.content),
+            Self::to_qdrant_payload(&entry.id, &entry.metadata, &entry.content),
         );
 
         self.client
@@ -157,51 +223,37 @@ impl MemoryStore for QdrantMemoryStore {
         let entries = search_result
             .result
             .into_iter()
-            .filter_map(|point| {
-                let id = match point.id? {
-                    PointId { point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid)) } => uuid,
-                    PointId { point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)) } => num.to_string(),
-                    _ => return None,
-                };
-
-                // Extract embedding from VectorsOutput
-                let embedding = point.vectors.and_then(|vo| {
-                    use qdrant_client::qdrant::vectors_output::VectorsOptions;
-                    use qdrant_client::qdrant::vector_output::Vector;
-                    match vo.vectors_options? {
-                        VectorsOptions::Vector(v) => {
-                            // The VectorOutput has a .vector field which is Option<Vector enum>
-                            match v.vector? {
-                                Vector::Dense(dense) => Some(dense.data),
-                                _ => None,
-                            }
-                        },
-                        _ => None,
-                    }
-                })?;
-
-                let (content, metadata) = Self::from_qdrant_payload(&point.payload);
-
-                Some(MemoryEntry {
-                    id,
-                    content,
-                    embedding,
-                    metadata,
-                })
-            })
+            .filter_map(|point| Self::to_memory_entry(point.id, &point.payload, point.vectors))
             .collect();
 
         Ok(entries)
     }
 
+    async fn get(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        let response = self.client
+            .get_points(
+                GetPointsBuilder::new(
+                    &self.collection_name,
+                    vec![Self::point_id(id)],
+                )
+                .with_payload(true)
+                .with_vectors(true)
+            )
+            .await
+            .map_err(|e| Error::storage(format!("Failed to get point: {}", e)))?;
+
+        Ok(response
+            .result
+            .into_iter()
+            .find_map(|point| Self::to_memory_entry(point.id, &point.payload, point.vectors)))
+    }
+
     async fn delete(&self, id: &str) -> Result<()> {
         self.client
             .delete_points(
                 DeletePointsBuilder::new(&self.collection_name)
                     .points(PointsIdsList {
-                        ids: vec![PointId {
-                            point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(id.to_string())),
-                        }],
+                        ids: vec![Self::point_id(id)],
                     })
             )
             .await
@@ -232,3 +284,26 @@ impl Default for QdrantConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qdrant_client::qdrant::point_id::PointIdOptions;
+
+    #[test]
+    fn test_point_id_mapping() {
+        let options = |id: &str| QdrantMemoryStore::point_id(id).point_id_options.unwrap();
+
+        assert_eq!(options("42"), PointIdOptions::Num(42));
+        let uuid = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        assert_eq!(options(uuid), PointIdOptions::Uuid(uuid.to_string()));
+
+        // Other IDs map to a stable UUID
+        let PointIdOptions::Uuid(mapped) = options("alice") else {
+            panic!("Expected a UUID point ID");
+        };
+        assert!(uuid::Uuid::parse_str(&mapped).is_ok());
+        assert_eq!(options("alice"), PointIdOptions::Uuid(mapped));
+        assert_ne!(options("bob"), options("alice"));
+    }
+}
@@ -6,7 +6,7 @@ use std::time::Duration;
 
 use multi_agent_core::{
     traits::{SessionStore, StateStore, DistributedRateLimiter, ProviderStore, ProviderEntry},
-    types::Session,
+    types::{Session, SessionStatus},
     Error, Result,
 };
 
@@ -179,7 +179,7 @@ impl SessionStore for RedisSessionStore {
         Ok(())
     }
 
-    async fn list_running(&self) -> Result<Vec<String>> {
+    async fn list_by_status(&self, status: SessionStatus) -> Result<Vec<String>> {
         let mut conn = self.client.get_multiplexed_async_connection().await
             .map_err(|e| Error::storage(format!("Redis connection error: {}", e)))?;
             
@@ -187,21 +187,21 @@ impl SessionStore for RedisSessionStore {
         let keys: Vec<String> = conn.keys(&pattern).await
             .map_err(|e| Error::storage(format!("Redis keys error: {}", e)))?;
             
-        let mut running_ids = Vec::new();
+        let mut ids = Vec::new();
         for key in keys {
             let data: Option<String> = conn.get(&key).await
                 .map_err(|e| Error::storage(format!("Redis get error: {}", e)))?;
                 
             if let Some(json) = data {
                 if let Ok(session) = serde_json::from_str::<Session>(&json) {
-                    if session.status == multi_agent_core::types::SessionStatus::Running {
-                        running_ids.push(session.id);
+                    if session.status == status {
+                        ids.push(session.id);
                     }
                 }
             }
         }
         
-        Ok(running_ids)
+        Ok(ids)
     }
 }
 
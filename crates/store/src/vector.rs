@@ -70,6 +70,10 @@ impl MemoryStore for SimpleVectorStore {
             .collect())
     }
 
+    async fn get(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        Ok(self.data.get(id).map(|entry| entry.value().clone()))
+    }
+
     async fn delete(&self, id: &str) -> Result<()> {
         self.data.remove(id);
         Ok(())
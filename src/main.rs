@@ -65,7 +65,7 @@ async fn main() -> anyhow::Result<()> {
         ReActController::builder()
             .with_store(store.clone())
             .with_session_store(session_store.clone())
-            .build()
+            .build()?
     );
     tracing::info!("L1 Controller initialized (mock ReAct)");
 
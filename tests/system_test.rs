@@ -39,6 +39,7 @@ impl LlmClient for ScriptedMockLlm {
             finish_reason: "stop".to_string(),
             usage: LlmUsage::default(),
             tool_calls: None,
+            reasoning: None,
         })
     }
 
@@ -97,7 +98,7 @@ async fn test_system_e2e_happy_path() -> anyhow::Result<()> {
             .with_llm(llm.clone())
             .with_tools(tools)
             .with_session_store(Arc::new(InMemorySessionStore::new()))
-            .build()
+            .build()?
     );
 
     let (addr, _handle) = start_test_server(controller, llm.clone()).await?;
@@ -130,7 +131,7 @@ async fn test_system_security_block() -> anyhow::Result<()> {
             .with_llm(llm.clone())
             .with_security(guardrail)
             .with_session_store(Arc::new(InMemorySessionStore::new()))
-            .build()
+            .build()?
     );
 
     let (addr, _handle) = start_test_server(controller, llm.clone()).await?;
@@ -171,7 +172,7 @@ async fn test_system_memory_retrieval() -> anyhow::Result<()> {
             .with_llm(llm.clone())
             .with_memory(memory_store, llm.clone())
             .with_session_store(Arc::new(InMemorySessionStore::new()))
-            .build()
+            .build()?
     );
 
     let (addr, _handle) = start_test_server(controller, llm.clone()).await?;
@@ -201,7 +202,7 @@ async fn test_system_semantic_cache() -> anyhow::Result<()> {
         ReActController::builder()
             .with_llm(llm.clone())
             .with_session_store(Arc::new(InMemorySessionStore::new()))
-            .build()
+            .build()?
     );
 
     let server = GatewayServer::new(GatewayConfig { host: "127.0.0.1".into(), port: 0, ..Default::default() }, router, cache).with_controller(controller);